@@ -0,0 +1,132 @@
+//! # Cumaea derive
+//!
+//! `#[derive(Prompt)]` for [cumaea](https://docs.rs/cumaea), which fills in a struct
+//! by asking for each of its fields in turn. Use it through cumaea's `derive`
+//! feature rather than on its own.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr};
+
+/// Implements `cumaea::Promptable`, asking for each field in the order they're
+/// declared.
+///
+/// Each field's type has to implement `cumaea::PromptField`, and its prompt is made
+/// from its name, like `Listen port` for `listen_port`. A `#[prompt(...)]` attribute
+/// on a field can set `prompt = "..."` for the question, `default = "..."` for the
+/// answer an empty line gives, and `validate = "path::to::check"` for a
+/// `fn(&str) -> Result<(), String>` to run on the answer.
+#[proc_macro_derive(Prompt, attributes(prompt))]
+pub fn derive_prompt(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// What a field's `#[prompt(...)]` attributes say.
+struct FieldOptions {
+    prompt: Option<LitStr>,
+    default: Option<LitStr>,
+    validators: Vec<syn::Path>,
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "#[derive(Prompt)] needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "#[derive(Prompt)] only works on structs",
+            ))
+        }
+    };
+
+    let mut asks = Vec::new();
+    let mut names = Vec::new();
+    for field in fields {
+        let name = field.ident.as_ref().expect("named fields have names");
+        let ty = &field.ty;
+        let options = field_options(field)?;
+        let prompt = options
+            .prompt
+            .unwrap_or_else(|| LitStr::new(&humanize(&name.to_string()), name.span()));
+        let default = match options.default {
+            Some(default) => quote!(::core::option::Option::Some(#default)),
+            None => quote!(::core::option::Option::None),
+        };
+        let validators = options.validators;
+        asks.push(quote! {
+            let #name = ::cumaea::__field::<#ty>(
+                #prompt,
+                #default,
+                &[#(#validators as fn(&str) -> ::core::result::Result<(), ::std::string::String>),*],
+            )?;
+        });
+        names.push(name);
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::cumaea::Promptable for #ident #ty_generics #where_clause {
+            fn prompt() -> ::core::result::Result<Self, ::cumaea::PromptError> {
+                #(#asks)*
+                ::core::result::Result::Ok(Self { #(#names),* })
+            }
+        }
+    })
+}
+
+fn field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions {
+        prompt: None,
+        default: None,
+        validators: Vec::new(),
+    };
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("prompt"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("prompt") {
+                options.prompt = Some(value);
+            } else if meta.path.is_ident("default") {
+                options.default = Some(value);
+            } else if meta.path.is_ident("validate") {
+                options.validators.push(value.parse()?);
+            } else {
+                return Err(meta.error("expected `prompt`, `default` or `validate`"));
+            }
+            Ok(())
+        })?;
+    }
+    if let Some(prompt) = &options.prompt {
+        if prompt.value().is_empty() {
+            return Err(syn::Error::new(prompt.span(), "the prompt can't be empty"));
+        }
+    }
+    Ok(options)
+}
+
+/// A field name as a prompt, like `Listen port` for `listen_port`.
+fn humanize(name: &str) -> String {
+    let words = name.trim_start_matches("r#").replace('_', " ");
+    let words = words.trim();
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => name.to_string(),
+    }
+}
@@ -0,0 +1,71 @@
+//! The escape sequences prompts move the cursor and clear the screen with. They're
+//! written the same way to every terminal, so drawing doesn't need crossterm,
+//! which can't be built for WebAssembly.
+
+use std::fmt::{self, Display};
+use std::io::{self, Write};
+
+/// Writes escape sequences, like crossterm's `QueueableCommand`.
+pub(crate) trait Queue {
+    fn queue(&mut self, command: impl Display) -> io::Result<&mut Self>;
+}
+
+impl<W: Write + ?Sized> Queue for W {
+    fn queue(&mut self, command: impl Display) -> io::Result<&mut Self> {
+        write!(self, "{}", command)?;
+        Ok(self)
+    }
+}
+
+/// Moves the cursor up that many rows.
+pub(crate) struct MoveUp(pub(crate) u16);
+
+impl Display for MoveUp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => Ok(()),
+            rows => write!(f, "\x1b[{}A", rows),
+        }
+    }
+}
+
+/// Moves the cursor left that many columns.
+pub(crate) struct MoveLeft(pub(crate) u16);
+
+impl Display for MoveLeft {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            0 => Ok(()),
+            columns => write!(f, "\x1b[{}D", columns),
+        }
+    }
+}
+
+/// Moves the cursor to a column of the row it's on, counted from 0.
+pub(crate) struct MoveToColumn(pub(crate) u16);
+
+impl Display for MoveToColumn {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "\x1b[{}G", self.0 + 1)
+    }
+}
+
+/// Clears part of the screen.
+pub(crate) struct Clear(pub(crate) ClearType);
+
+/// Which part of the screen to [`Clear`].
+pub(crate) enum ClearType {
+    /// The row the cursor is on.
+    CurrentLine,
+    /// From the cursor to the end of the screen.
+    FromCursorDown,
+}
+
+impl Display for Clear {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            ClearType::CurrentLine => write!(f, "\x1b[2K"),
+            ClearType::FromCursorDown => write!(f, "\x1b[J"),
+        }
+    }
+}
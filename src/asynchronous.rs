@@ -0,0 +1,254 @@
+//! Prompting from async code, behind the `async` feature. Reading the terminal
+//! blocks, so each prompt runs on tokio's blocking threads, one at a time, and the
+//! task asking waits for it without holding up the executor.
+
+use crate::term;
+use crate::{Choice, PromptError};
+use std::future::{self, Future};
+use std::ops::RangeInclusive;
+use std::panic;
+use std::pin::{pin, Pin};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::task::Poll;
+use tokio::task::{self, JoinError};
+
+/// Held by the prompt on screen, so prompts asked from tasks running side by side
+/// are shown one after another instead of over each other.
+static ASKING: Mutex<()> = Mutex::new(());
+
+/// Runs `ask`, which prompts in any of the ways the crate can, on tokio's blocking
+/// threads, and returns its result once it's answered. It's for the prompts that
+/// have no `_async` version of their own, like those made with
+/// [`Prompt`](struct@crate::Prompt). Prompts asked this way from tasks running side
+/// by side are shown one after another.
+///
+/// A panic in `ask` is passed on to the task awaiting it. A prompt asked this way
+/// doesn't see a [`Prompter`](crate::Prompter) or
+/// [`MockTerminal`](crate::test::MockTerminal) set up on the task's thread.
+///
+/// # Panics
+///
+/// Panics when it's not called from inside a tokio runtime.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// async fn setup() -> Result<i64, PromptError> {
+///     ask_async(|| Prompt::int("Workers").range(1..=64).default(4).ask()).await
+/// }
+/// ```
+pub async fn ask_async<T: Send + 'static>(ask: impl FnOnce() -> T + Send + 'static) -> T {
+    let asked = task::spawn_blocking(move || {
+        let _asking = ASKING.lock().unwrap_or_else(PoisonError::into_inner);
+        ask()
+    });
+    answered(asked.await)
+}
+
+/// Like [`ask_async`], but the prompt is called off once `call_off` finishes, like
+/// a [`CancellationToken`]'s `cancelled()` on shutdown, and
+/// [`PromptError::Cancelled`] is returned with the terminal restored. A prompt
+/// that hasn't been shown yet when it's called off isn't shown at all.
+///
+/// [`CancellationToken`]: https://docs.rs/tokio-util/latest/tokio_util/sync/struct.CancellationToken.html
+///
+/// # Panics
+///
+/// Panics when it's not called from inside a tokio runtime.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use std::future::Future;
+///
+/// async fn name(shutdown: impl Future<Output = ()>) -> Option<String> {
+///     match ask_async_until(shutdown, || Prompt::text("Name").ask()).await {
+///         Ok(name) => Some(name),
+///         Err(PromptError::Cancelled) => None,
+///         Err(err) => panic!("couldn't ask for a name: {}", err),
+///     }
+/// }
+/// ```
+pub async fn ask_async_until<T: Send + 'static>(
+    call_off: impl Future<Output = ()>,
+    ask: impl FnOnce() -> Result<T, PromptError> + Send + 'static,
+) -> Result<T, PromptError> {
+    let called_off = Arc::new(AtomicBool::new(false));
+    let flag = Arc::clone(&called_off);
+    let mut asked = task::spawn_blocking(move || {
+        let _asking = ASKING.lock().unwrap_or_else(PoisonError::into_inner);
+        match flag.load(Ordering::Relaxed) {
+            true => Err(PromptError::Cancelled),
+            false => term::call_off_with(flag, ask),
+        }
+    });
+    let mut call_off = pin!(call_off);
+    let finished = future::poll_fn(|cx| match Pin::new(&mut asked).poll(cx) {
+        Poll::Ready(answer) => Poll::Ready(Some(answer)),
+        Poll::Pending => call_off.as_mut().poll(cx).map(|()| None),
+    })
+    .await;
+    match finished {
+        Some(answer) => answered(answer),
+        None => {
+            called_off.store(true, Ordering::Relaxed);
+            // The prompt gives up within a moment, and puts the terminal back.
+            answered(asked.await).and(Err(PromptError::Cancelled))
+        }
+    }
+}
+
+/// The answer a prompt on the blocking threads gave, or its panic passed on.
+fn answered<T>(asked: Result<T, JoinError>) -> T {
+    match asked {
+        Ok(answer) => answer,
+        Err(err) => panic::resume_unwind(err.into_panic()),
+    }
+}
+
+/// Like [`prompt_text`](crate::prompt_text), without blocking the executor.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// async fn greet() {
+///     let name = prompt_text_async("Enter your", "name", None).await;
+///     println!("Hello, {}!", name);
+/// }
+/// ```
+pub async fn prompt_text_async(
+    plain_prompt: &str,
+    prompt: &str,
+    colored: Option<Choice>,
+) -> String {
+    let (plain_prompt, prompt) = (plain_prompt.to_string(), prompt.to_string());
+    ask_async(move || crate::prompt_text(&plain_prompt, &prompt, colored)).await
+}
+
+/// Like [`prompt_multiline`](crate::prompt_multiline), without blocking the executor.
+pub async fn prompt_multiline_async(prompt: &str, colored: Option<Choice>) -> String {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_multiline(&prompt, colored)).await
+}
+
+/// Like [`prompt_tf_default`](crate::prompt_tf_default), without blocking the
+/// executor.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// async fn shut_down() {
+///     if prompt_tf_default_async("Stop the server? (y/N) ", None, false).await {
+///         // ...
+///     }
+/// }
+/// ```
+pub async fn prompt_tf_default_async(prompt: &str, colored: Option<Choice>, default: bool) -> bool {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_tf_default(&prompt, colored, default)).await
+}
+
+/// Like [`prompt_selection`](crate::prompt_selection), without blocking the executor.
+pub async fn prompt_selection_async(
+    prompt: &str,
+    list: &str,
+    colored: Option<Choice>,
+    default: &str,
+) -> String {
+    let (prompt, list, default) = (prompt.to_string(), list.to_string(), default.to_string());
+    ask_async(move || crate::prompt_selection(&prompt, &list, colored, &default)).await
+}
+
+/// Like [`prompt_parse`](crate::prompt_parse), without blocking the executor.
+pub async fn prompt_parse_async<T: FromStr + Clone + Send + 'static>(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<T>,
+) -> T {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_parse(&prompt, colored, default)).await
+}
+
+/// Like [`prompt_password`](crate::prompt_password), without blocking the executor.
+pub async fn prompt_password_async(prompt: &str, colored: Option<Choice>) -> String {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_password(&prompt, colored)).await
+}
+
+/// Like [`prompt_multi_select`](crate::prompt_multi_select), without blocking the
+/// executor.
+pub async fn prompt_multi_select_async(
+    prompt: &str,
+    items: &[&str],
+    defaults: &[bool],
+) -> Vec<usize> {
+    let (prompt, items, defaults) = (prompt.to_string(), owned(items), defaults.to_vec());
+    ask_async(move || crate::prompt_multi_select(&prompt, &borrowed(&items), &defaults)).await
+}
+
+/// Like [`prompt_ranking`](crate::prompt_ranking), without blocking the executor.
+pub async fn prompt_ranking_async(prompt: &str, items: &[&str]) -> Vec<usize> {
+    let (prompt, items) = (prompt.to_string(), owned(items));
+    ask_async(move || crate::prompt_ranking(&prompt, &borrowed(&items))).await
+}
+
+/// Like [`prompt_menu`](crate::prompt_menu), without blocking the executor.
+pub async fn prompt_menu_async(
+    prompt: &str,
+    items: &[&str],
+    colored: Option<Choice>,
+    default: usize,
+) -> usize {
+    let (prompt, items) = (prompt.to_string(), owned(items));
+    ask_async(move || crate::prompt_menu(&prompt, &borrowed(&items), colored, default)).await
+}
+
+/// Like [`prompt_fuzzy_select`](crate::prompt_fuzzy_select), without blocking the
+/// executor.
+pub async fn prompt_fuzzy_select_async(
+    prompt: &str,
+    items: &[&str],
+    colored: Option<Choice>,
+) -> usize {
+    let (prompt, items) = (prompt.to_string(), owned(items));
+    ask_async(move || crate::prompt_fuzzy_select(&prompt, &borrowed(&items), colored)).await
+}
+
+/// Like [`prompt_int`](crate::prompt_int), without blocking the executor.
+pub async fn prompt_int_async(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<i64>,
+    range: Option<RangeInclusive<i64>>,
+) -> i64 {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_int(&prompt, colored, default, range)).await
+}
+
+/// Like [`prompt_float`](crate::prompt_float), without blocking the executor.
+pub async fn prompt_float_async(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<f64>,
+    range: Option<RangeInclusive<f64>>,
+    decimals: Option<u32>,
+) -> f64 {
+    let prompt = prompt.to_string();
+    ask_async(move || crate::prompt_float(&prompt, colored, default, range, decimals)).await
+}
+
+/// `items` as owned strings, to take to another thread.
+fn owned(items: &[&str]) -> Vec<String> {
+    items.iter().map(|item| item.to_string()).collect()
+}
+
+/// `items` as the string slices the prompts take.
+fn borrowed(items: &[String]) -> Vec<&str> {
+    items.iter().map(String::as_str).collect()
+}
@@ -0,0 +1,408 @@
+//! Right-to-left text, behind the `bidi` feature. Terminals mostly draw characters
+//! left to right in the order they're written, so a line with Hebrew or Arabic in
+//! it is put in the order it's read in before it's drawn, and a line that reads
+//! right to left is drawn against the right edge of the terminal.
+
+use crate::term;
+use std::sync::{Mutex, PoisonError};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Which way prompts read, set with [`set_text_direction`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TextDirection {
+    /// The way the first letter of the question reads, so a question in Hebrew or
+    /// Arabic reads right to left.
+    #[default]
+    Auto,
+    /// Left to right, even when the question is in Hebrew or Arabic.
+    LeftToRight,
+    /// Right to left, even when the question starts with a Latin letter.
+    RightToLeft,
+}
+
+static DIRECTION: Mutex<TextDirection> = Mutex::new(TextDirection::Auto);
+
+/// Sets which way prompts read from here on, on every thread. Prompts that read
+/// right to left are drawn against the right edge of the terminal, with the answer
+/// to the left of the question. Either way, runs of Hebrew or Arabic in a line are
+/// shown in reading order, and the line editor's cursor follows the text it's in.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_text_direction(TextDirection::RightToLeft);
+/// let name = Prompt::text("שם").ask();
+/// ```
+pub fn set_text_direction(direction: TextDirection) {
+    *DIRECTION.lock().unwrap_or_else(PoisonError::into_inner) = direction;
+}
+
+/// Whether a line made of `graphemes` reads right to left.
+fn right_to_left(graphemes: &[Grapheme]) -> bool {
+    match *DIRECTION.lock().unwrap_or_else(PoisonError::into_inner) {
+        TextDirection::LeftToRight => false,
+        TextDirection::RightToLeft => true,
+        TextDirection::Auto => graphemes
+            .iter()
+            .find_map(|grapheme| match class(grapheme.text) {
+                Class::Left => Some(false),
+                Class::Right => Some(true),
+                _ => None,
+            })
+            .unwrap_or(false),
+    }
+}
+
+/// Which way a character runs, as far as laying out a line needs to know.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Left,
+    Right,
+    Number,
+    Neutral,
+}
+
+/// The class of the first character of `grapheme`.
+fn class(grapheme: &str) -> Class {
+    let Some(c) = grapheme.chars().next() else {
+        return Class::Neutral;
+    };
+    match c as u32 {
+        0x0660..=0x0669 | 0x06F0..=0x06F9 => Class::Number,
+        0x0590..=0x08FF | 0xFB1D..=0xFDFF | 0xFE70..=0xFEFF => Class::Right,
+        0x10800..=0x10FFF | 0x1E800..=0x1EFFF => Class::Right,
+        _ if c.is_ascii_digit() => Class::Number,
+        _ if c.is_alphabetic() => Class::Left,
+        _ => Class::Neutral,
+    }
+}
+
+/// The character drawn in place of `grapheme` in right-to-left text, so brackets
+/// still open towards what they enclose.
+fn mirror(grapheme: &str) -> &str {
+    match grapheme {
+        "(" => ")",
+        ")" => "(",
+        "[" => "]",
+        "]" => "[",
+        "{" => "}",
+        "}" => "{",
+        "<" => ">",
+        ">" => "<",
+        "«" => "»",
+        "»" => "«",
+        _ => grapheme,
+    }
+}
+
+/// A grapheme of a line, where it starts, and the color codes in effect for it.
+struct Grapheme<'a> {
+    start: usize,
+    text: &'a str,
+    style: String,
+}
+
+/// The graphemes of `text`, leaving out its color codes.
+fn graphemes(text: &str) -> Vec<Grapheme<'_>> {
+    let mut graphemes = Vec::new();
+    let mut style = String::new();
+    let mut at = 0;
+    while at < text.len() {
+        let end = text[at..].find('\x1b').map_or(text.len(), |i| at + i);
+        graphemes.extend(
+            text[at..end]
+                .grapheme_indices(true)
+                .map(|(i, grapheme)| Grapheme {
+                    start: at + i,
+                    text: grapheme,
+                    style: style.clone(),
+                }),
+        );
+        if end == text.len() {
+            break;
+        }
+        let code = &text[end..end + term::code_len(&text[end..])];
+        match code {
+            "\x1b[0m" | "\x1b[m" => style.clear(),
+            _ => style.push_str(code),
+        }
+        at = end + code.len();
+    }
+    graphemes
+}
+
+/// How deep in right-to-left text each grapheme is, even for left to right and
+/// odd for right to left, roughly as the Unicode bidirectional algorithm has it
+/// for a line without explicit embeddings.
+fn levels(graphemes: &[Grapheme], rtl: bool) -> Vec<u8> {
+    let paragraph = if rtl { Class::Right } else { Class::Left };
+    let mut classes: Vec<Class> = graphemes.iter().map(|g| class(g.text)).collect();
+
+    // Numbers after left-to-right text are left to right.
+    let mut strong = paragraph;
+    for class in &mut classes {
+        match *class {
+            Class::Left | Class::Right => strong = *class,
+            Class::Number if strong == Class::Left => *class = Class::Left,
+            _ => {}
+        }
+    }
+
+    // Spaces and punctuation take the direction of the text on both sides of
+    // them, or the line's if that differs.
+    let side = |class: Option<&Class>| match class {
+        Some(Class::Number) => Class::Right,
+        Some(&class) => class,
+        None => paragraph,
+    };
+    let mut start = 0;
+    while start < classes.len() {
+        if classes[start] != Class::Neutral {
+            start += 1;
+            continue;
+        }
+        let end = classes[start..]
+            .iter()
+            .position(|&class| class != Class::Neutral)
+            .map_or(classes.len(), |i| start + i);
+        let before = side(classes[..start].last());
+        let after = side(classes.get(end));
+        let resolved = if before == after { before } else { paragraph };
+        classes[start..end].fill(resolved);
+        start = end;
+    }
+
+    classes
+        .into_iter()
+        .map(|class| match class {
+            Class::Right => 1,
+            Class::Left if !rtl => 0,
+            _ => 2,
+        })
+        .collect()
+}
+
+/// A line laid out the way it's drawn, left to right.
+pub(crate) struct Visual {
+    text: String,
+    width: usize,
+    /// Where each grapheme starts in the line as written, where it's drawn, how
+    /// wide it is and whether it's in right-to-left text, in the order drawn.
+    cells: Vec<(usize, usize, usize, bool)>,
+    rtl: bool,
+}
+
+impl Visual {
+    /// `line` laid out for drawing, or `None` if it reads left to right as it's
+    /// written.
+    pub(crate) fn of(line: &str) -> Option<Visual> {
+        let graphemes = graphemes(line);
+        let rtl = right_to_left(&graphemes);
+        Visual::new(&graphemes, rtl)
+    }
+
+    fn new(graphemes: &[Grapheme], rtl: bool) -> Option<Visual> {
+        let levels = levels(graphemes, rtl);
+        if !rtl && levels.iter().all(|&level| level == 0) {
+            return None;
+        }
+        let mut order: Vec<usize> = (0..graphemes.len()).collect();
+        let deepest = levels.iter().copied().max().unwrap_or(0);
+        for level in (1..=deepest).rev() {
+            let mut i = 0;
+            while i < order.len() {
+                let run = order[i..]
+                    .iter()
+                    .take_while(|&&g| levels[g] >= level)
+                    .count();
+                order[i..i + run].reverse();
+                i += run.max(1);
+            }
+        }
+
+        let mut visual = Visual {
+            text: String::new(),
+            width: 0,
+            cells: Vec::new(),
+            rtl,
+        };
+        let mut style = "";
+        for i in order {
+            let grapheme = &graphemes[i];
+            let reversed = levels[i] % 2 == 1;
+            if grapheme.style != style {
+                if !style.is_empty() {
+                    visual.text.push_str("\x1b[0m");
+                }
+                style = &grapheme.style;
+                visual.text.push_str(style);
+            }
+            visual.text.push_str(match reversed {
+                true => mirror(grapheme.text),
+                false => grapheme.text,
+            });
+            let width = grapheme.text.width();
+            visual
+                .cells
+                .push((grapheme.start, visual.width, width, reversed));
+            visual.width += width;
+        }
+        if !style.is_empty() {
+            visual.text.push_str("\x1b[0m");
+        }
+        Some(visual)
+    }
+
+    /// How far in from the left edge the line is drawn: against the right edge if
+    /// it reads right to left, leaving the last column free.
+    fn indent(&self) -> usize {
+        match self.rtl {
+            true => term::size().0.saturating_sub(1).saturating_sub(self.width),
+            false => 0,
+        }
+    }
+
+    /// The line as it's drawn, from the left edge.
+    pub(crate) fn drawn(&self) -> String {
+        format!("{}{}", " ".repeat(self.indent()), self.text)
+    }
+
+    /// The column the cursor goes in when it's at byte `at` of the line as written:
+    /// next to the character before it, on the side the text runs towards.
+    pub(crate) fn column(&self, at: usize) -> usize {
+        let before = self
+            .cells
+            .iter()
+            .filter(|&&(start, ..)| start < at)
+            .max_by_key(|&&(start, ..)| start);
+        self.indent()
+            + match before {
+                Some(&(_, column, _, true)) => column,
+                Some(&(_, column, width, false)) => column + width,
+                None if self.rtl => self.width,
+                None => 0,
+            }
+    }
+}
+
+/// `lines` laid out for drawing, all reading the way the first letter in them
+/// does, or `None` if they all read left to right as they're written.
+pub(crate) fn lay_out(lines: &[String]) -> Option<Vec<String>> {
+    let rtl = right_to_left(&graphemes(&lines.concat()));
+    let laid_out: Vec<Option<Visual>> = lines
+        .iter()
+        .map(|line| Visual::new(&graphemes(line), rtl))
+        .collect();
+    if laid_out.iter().all(Option::is_none) {
+        return None;
+    }
+    let lines = lines.iter().zip(laid_out);
+    Some(
+        lines
+            .map(|(line, visual)| visual.map_or_else(|| line.clone(), |visual| visual.drawn()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Backend;
+
+    /// A terminal `columns` wide, with nothing to read.
+    struct Columns(usize, Vec<u8>);
+
+    impl Backend for Columns {
+        fn reader(&mut self) -> &mut dyn std::io::BufRead {
+            unreachable!("nothing is read")
+        }
+
+        fn writer(&mut self) -> &mut dyn std::io::Write {
+            &mut self.1
+        }
+
+        fn size(&self) -> (usize, usize) {
+            (self.0, 24)
+        }
+    }
+
+    /// `line` laid out reading right to left if `rtl` is set, or `None` if it's drawn
+    /// as it's written.
+    fn visual(line: &str, rtl: bool) -> Option<Visual> {
+        Visual::new(&graphemes(line), rtl)
+    }
+
+    fn drawn(line: &str, rtl: bool, columns: usize) -> String {
+        let visual = visual(line, rtl).expect("the line is reordered");
+        term::with_backend(Columns(columns, Vec::new()), || visual.drawn())
+    }
+
+    #[test]
+    fn left_to_right_lines_are_left_alone() {
+        assert!(visual("Name: ada 42 (x)", false).is_none());
+        assert!(visual("", false).is_none());
+    }
+
+    #[test]
+    fn right_to_left_runs_are_reversed() {
+        assert_eq!(drawn("Name: שלום", false, 80), "Name: םולש");
+        assert_eq!(drawn("abc שלום עולם def", false, 80), "abc םלוע םולש def");
+    }
+
+    #[test]
+    fn numbers_keep_their_order() {
+        assert_eq!(drawn("abc 123 שלום", false, 80), "abc 123 םולש");
+        assert_eq!(drawn("שלום 100 עולם", true, 20), "      םלוע 100 םולש");
+        assert_eq!(drawn("מחיר: 42", true, 10), " 42 :ריחמ");
+    }
+
+    #[test]
+    fn latin_in_right_to_left_lines_keeps_its_order() {
+        assert_eq!(drawn("שלום abc", true, 10), " abc םולש");
+    }
+
+    #[test]
+    fn brackets_are_mirrored_in_right_to_left_text() {
+        assert_eq!(drawn("(שלום)", true, 7), "(םולש)");
+        assert_eq!(drawn("abc [שלום] def", false, 80), "abc [םולש] def");
+        assert_eq!(drawn("«שלום» <x>", true, 12), " <x> «םולש»");
+    }
+
+    #[test]
+    fn colors_follow_their_text() {
+        let line = "\x1b[1mשל\x1b[0mום";
+        assert_eq!(drawn(line, true, 5), "םו\x1b[1mלש\x1b[0m");
+    }
+
+    #[test]
+    fn right_to_left_lines_fit_any_width() {
+        assert_eq!(drawn("שלום", true, 0), "םולש");
+        assert_eq!(drawn("שלום", true, 3), "םולש");
+        assert_eq!(drawn("שלום", true, 6), " םולש");
+    }
+
+    #[test]
+    fn the_cursor_follows_the_text_it_is_in() {
+        let line = "ab שלום";
+        let visual = visual(line, false).unwrap();
+        // Drawn as "ab םולש": after the space it's before the Hebrew, which grows
+        // leftwards from the right end of the run.
+        assert_eq!(visual.column(0), 0);
+        assert_eq!(visual.column(1), 1);
+        assert_eq!(visual.column(3), 3);
+        assert_eq!(visual.column(3 + "ש".len()), 6);
+        assert_eq!(visual.column(line.len()), 3);
+    }
+
+    #[test]
+    fn the_cursor_starts_on_the_right_in_right_to_left_lines() {
+        let visual = visual("שלום", true).unwrap();
+        term::with_backend(Columns(10, Vec::new()), || {
+            assert_eq!(visual.column(0), 9);
+            assert_eq!(visual.column("ש".len()), 8);
+            assert_eq!(visual.column("שלום".len()), 5);
+        });
+    }
+}
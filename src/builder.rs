@@ -0,0 +1,1640 @@
+//! A builder-style API for prompts, for when the positional arguments of the free
+//! functions get unwieldy.
+
+use crate::ansi::{self, ClearType, Queue};
+use crate::config;
+use crate::confirm::{self, Countdown};
+use crate::defaults;
+use crate::editor::{self, Completer, EditMode, Extras};
+use crate::external;
+use crate::history;
+use crate::locale::{fill, locale, number};
+use crate::select::{FuzzySelect, Menu, MultiSelect, Ranking};
+use crate::session;
+use crate::term::{self, outln, Buffer, Output};
+use crate::theme::{self, Status, Style, Theme};
+use crate::wizard;
+use crate::{paint, print_error, Choice, PromptError};
+use std::fmt::Display;
+use std::ops::{Range, RangeInclusive};
+use std::panic;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Entry point for the builder API. Each constructor returns a [`PromptBuilder`]
+/// that can be configured before calling [`ask`](PromptBuilder::ask).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// let approved = Prompt::confirm("Approved? (Y/n)")
+///     .default(true)
+///     .color(Choice::Normal(ChoiceColor::Green))
+///     .ask()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Prompt;
+
+impl Prompt {
+    /// A yes/no question, like [`prompt_tf_default`](crate::prompt_tf_default).
+    /// Loops until the answer is `y` or `n`, or empty if there's a default. Other
+    /// words can be set with [`words`](PromptBuilder::words) or
+    /// [`set_yes_no`](crate::set_yes_no).
+    pub fn confirm(prompt: &str) -> PromptBuilder<bool> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(" "), parse_tf);
+        builder.describe_default = Some(|default| Some(confirm::hint(default.copied())));
+        builder.describe_answer = Some(|&answer| match answer {
+            true => locale().yes.clone(),
+            false => locale().no.clone(),
+        });
+        builder
+    }
+
+    /// A line of free text, printed as `{prompt}: `.
+    pub fn text(prompt: &str) -> PromptBuilder<String> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(": "), parse_string);
+        builder.describe_default = Some(bracketed);
+        builder.describe_answer = Some(ToString::to_string);
+        builder
+    }
+
+    /// Several lines of free text, like [`prompt_multiline`](crate::prompt_multiline).
+    /// Checks run on the whole text, and an empty answer returns the default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let description = Prompt::multiline("Describe the change")
+    ///     .validate(|text| match text.trim().is_empty() {
+    ///         true => Err("The description can't be empty.".to_string()),
+    ///         false => Ok(()),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn multiline(prompt: &str) -> PromptBuilder<String> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Lines, parse_string);
+        builder.multiline = true;
+        builder
+    }
+
+    /// Any value that implements [`FromStr`], like [`prompt_parse`](crate::prompt_parse).
+    /// Loops until the answer parses, printing an error message each time it doesn't.
+    pub fn parse<T: FromStr + Clone + 'static>(prompt: &str) -> PromptBuilder<T> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            input.parse().map_err(|_| locale().invalid.clone())
+        })
+    }
+
+    /// A whole number, like [`prompt_int`](crate::prompt_int). Combine with
+    /// [`range`](PromptBuilder::range) to set bounds. Digits can be grouped the way
+    /// the [`Locale`](crate::Locale) groups them, like `1,234`.
+    pub fn int(prompt: &str) -> PromptBuilder<i64> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            number(input)
+                .and_then(|normal| normal.parse().ok())
+                .ok_or_else(|| locale().whole_number.clone())
+        });
+        builder.describe_default = Some(bracketed);
+        builder.describe_answer = Some(ToString::to_string);
+        builder
+    }
+
+    /// A finite floating-point number, like [`prompt_float`](crate::prompt_float).
+    /// Combine with [`range`](PromptBuilder::range), [`round_to`](PromptBuilder::round_to)
+    /// or [`max_decimals`](PromptBuilder::max_decimals) as needed. The answer is read
+    /// with the [`Locale`](crate::Locale)'s separators, so `1.234,5` is taken in
+    /// German.
+    pub fn float(prompt: &str) -> PromptBuilder<f64> {
+        let mut builder =
+            PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+                match number(input).map(|normal| normal.parse::<f64>()) {
+                    Some(Ok(value)) if value.is_finite() => Ok(value),
+                    _ => Err(locale().number.clone()),
+                }
+            });
+        builder.describe_default = Some(bracketed);
+        builder.describe_answer = Some(ToString::to_string);
+        builder
+    }
+
+    /// A secret, like [`prompt_password`](crate::prompt_password). What's typed isn't
+    /// echoed, and it isn't trimmed either.
+    pub fn password(prompt: &str) -> PromptBuilder<String> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(": "), parse_string);
+        builder.hidden = true;
+        builder
+    }
+
+    /// A secret like [`password`](Prompt::password), returned in a
+    /// [`Zeroizing`](zeroize::Zeroizing) wrapper that scrubs it from memory on drop.
+    /// The intermediate buffers are scrubbed as well.
+    #[cfg(feature = "zeroize")]
+    pub fn secret(prompt: &str) -> PromptBuilder<zeroize::Zeroizing<String>> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            Ok(zeroize::Zeroizing::new(input.to_string()))
+        });
+        builder.hidden = true;
+        builder
+    }
+
+    /// A pick from a list, like [`prompt_selection`](crate::prompt_selection).
+    /// The color applies to the list rather than the question.
+    pub fn selection(prompt: &str, list: &str) -> PromptBuilder<String> {
+        let mut builder = PromptBuilder::new(prompt, Layout::List(list.to_string()), parse_string);
+        builder.show_default = Some(String::clone);
+        builder
+    }
+
+    /// A menu picked with the arrow keys, like [`prompt_menu`](crate::prompt_menu).
+    pub fn menu(prompt: &str, items: &[&str]) -> Menu {
+        Menu::new(prompt, items)
+    }
+
+    /// A menu narrowed down by typing, like
+    /// [`prompt_fuzzy_select`](crate::prompt_fuzzy_select).
+    pub fn fuzzy_select(prompt: &str, items: &[&str]) -> FuzzySelect {
+        FuzzySelect::new(prompt, items)
+    }
+
+    /// A checkbox list, like [`prompt_multi_select`](crate::prompt_multi_select).
+    pub fn multi_select(prompt: &str, items: &[&str]) -> MultiSelect {
+        MultiSelect::new(prompt, items)
+    }
+
+    /// A list put in order, like [`prompt_ranking`](crate::prompt_ranking).
+    pub fn ranking(prompt: &str, items: &[&str]) -> Ranking {
+        Ranking::new(prompt, items)
+    }
+}
+
+static ASSUME_DEFAULTS: AtomicBool = AtomicBool::new(false);
+
+/// Makes every prompt from here on return its default without asking, for a
+/// command-line tool's `--yes` flag. Menus pick their default item and checkbox
+/// lists keep their default selection. Prompts without a default are still asked.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if std::env::args().any(|arg| arg == "--yes") {
+///     set_assume_defaults(true);
+/// }
+/// // Returns `true` without asking when run with `--yes`.
+/// let proceed = prompt_tf_default("Proceed? (Y/n) ", None, true);
+/// ```
+pub fn set_assume_defaults(assume: bool) {
+    ASSUME_DEFAULTS.store(assume, Ordering::Relaxed);
+}
+
+/// Whether [`set_assume_defaults`] is on, or `CUMAEA_ASSUME_DEFAULTS` is set.
+pub(crate) fn assume_defaults() -> bool {
+    ASSUME_DEFAULTS.load(Ordering::Relaxed) || config::env_config().assume_defaults
+}
+
+/// What a prompt does when stdin or stderr isn't a terminal, like in a CI job.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// // Goes with 4 straight away under CI, without waiting on stdin.
+/// let workers = Prompt::int("Workers")
+///     .default(4)
+///     .non_interactive(NonInteractive::Default)
+///     .ask()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NonInteractive {
+    /// Read the answer from stdin as usual, so answers can be piped in.
+    #[default]
+    Read,
+    /// Return the default without reading anything. A skippable prompt without a
+    /// default is skipped; otherwise it's [`PromptError::NotInteractive`].
+    Default,
+    /// Return [`PromptError::NotInteractive`] without reading anything.
+    Fail,
+}
+
+impl NonInteractive {
+    /// Whether the prompt should go with its default instead of reading an answer,
+    /// or an error if it shouldn't run at all.
+    pub(crate) fn use_default(self) -> Result<bool, PromptError> {
+        if term::interactive() {
+            return Ok(false);
+        }
+        match self {
+            NonInteractive::Read => Ok(false),
+            NonInteractive::Default => Ok(true),
+            NonInteractive::Fail => Err(PromptError::NotInteractive),
+        }
+    }
+}
+
+/// How the prompt line is put together.
+pub(crate) enum Layout {
+    /// `{prompt}`, exactly as given.
+    Verbatim,
+    /// `{prompt}{separator}`.
+    Question(&'static str),
+    /// `{prompt} {hint}`, with the locale's hint for several lines.
+    Lines,
+    /// `{plain} {prompt}: `, where only `prompt` is colored.
+    Labelled(String),
+    /// `{prompt}: [{list}]: `, where only `list` is colored.
+    List(String),
+}
+
+/// Where `default` is listed in `list`: the item with it in parentheses, like
+/// `(D)oughnuts`, or else the item that's just `default`. Items are separated by
+/// commas or slashes.
+fn default_item(list: &str, default: &str) -> Option<Range<usize>> {
+    if default.is_empty() {
+        return None;
+    }
+    let mut items = Vec::new();
+    let mut start = 0;
+    for (i, c) in list.char_indices().chain([(list.len(), ',')]) {
+        if c == ',' || c == '/' {
+            let item = &list[start..i];
+            let trimmed = item.trim_start();
+            let begin = start + item.len() - trimmed.len();
+            items.push(begin..begin + trimmed.trim_end().len());
+            start = i + c.len_utf8();
+        }
+    }
+    let marked = format!("({})", default);
+    let text = |item: &&Range<usize>| &list[(*item).clone()];
+    items
+        .iter()
+        .find(|item| text(item).contains(&marked))
+        .or_else(|| {
+            items
+                .iter()
+                .find(|item| text(item).eq_ignore_ascii_case(default))
+        })
+        .cloned()
+}
+
+/// Turns an answer into a value, or the message to print before asking again.
+/// An empty message asks again without printing anything.
+type Parser<T> = Box<dyn Fn(&str) -> Result<T, String>>;
+
+/// A caller's own check on an answer, returning the message to print if it fails.
+pub(crate) type Validator = Box<dyn Fn(&str) -> Result<(), String>>;
+
+/// A check that can take a while, run on a thread of its own.
+type SlowValidator = Arc<dyn Fn(&str) -> Result<(), String> + Send + Sync>;
+
+/// Reworks an accepted value before it's returned.
+type Transform<T> = Box<dyn Fn(T) -> T>;
+
+/// How the default, or that there's none, is shown after the question when
+/// [`show_default`](PromptBuilder::show_default) is on.
+type DescribeDefault<T> = fn(Option<&T>) -> Option<String>;
+
+/// How an answer is shown in the line a prompt leaves behind, when the
+/// [`Theme`]'s `summary` is set.
+type DescribeAnswer<T> = fn(&T) -> String;
+
+/// A configurable prompt, created through [`Prompt`].
+///
+/// If a default is set, an empty answer returns it. Otherwise the empty string is
+/// handed to the parser like any other answer.
+///
+/// In a terminal, answers can be edited like on a shell's command line: Left and
+/// Right move the cursor, Home and End (or Ctrl+A and Ctrl+E) jump to either end,
+/// Ctrl+W deletes the word before the cursor, and Ctrl+U and Ctrl+K delete up to
+/// the start and to the end.
+///
+/// Up and Down bring back the answers typed earlier in the process at prompts with
+/// the same text, so a prompt asked in a loop doesn't have to be retyped from
+/// scratch. Earlier copies of an answer are dropped when it's given
+/// again. Hidden and multi-line prompts don't keep them.
+pub struct PromptBuilder<T> {
+    prompt: String,
+    layout: Layout,
+    style: Option<Style>,
+    hint: Option<String>,
+    placeholder: Option<String>,
+    default: Option<T>,
+    pub(crate) show_default: Option<fn(&T) -> String>,
+    pub(crate) describe_default: Option<DescribeDefault<T>>,
+    pub(crate) describe_answer: Option<DescribeAnswer<T>>,
+    default_shown: bool,
+    erased: bool,
+    error_message: Option<String>,
+    pub(crate) hidden: bool,
+    pub(crate) multiline: bool,
+    pub(crate) editor: Option<String>,
+    confirmation: Option<String>,
+    max_attempts: Option<usize>,
+    timeout: Option<Duration>,
+    countdown: Option<u64>,
+    default_on_eof: bool,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    history_file: Option<PathBuf>,
+    history_limit: usize,
+    pub(crate) keep_history: bool,
+    edit_mode: Option<EditMode>,
+    output: Option<Output>,
+    skip: Option<String>,
+    validators: Vec<Validator>,
+    slow_validators: Vec<SlowValidator>,
+    transforms: Vec<Transform<T>>,
+    pub(crate) completer: Option<Completer>,
+    pub(crate) parser: Parser<T>,
+}
+
+impl<T: Clone> PromptBuilder<T> {
+    pub(crate) fn new(
+        prompt: &str,
+        layout: Layout,
+        parser: impl Fn(&str) -> Result<T, String> + 'static,
+    ) -> Self {
+        PromptBuilder {
+            prompt: prompt.to_string(),
+            layout,
+            style: None,
+            hint: None,
+            placeholder: None,
+            default: None,
+            show_default: None,
+            describe_default: None,
+            describe_answer: None,
+            default_shown: false,
+            erased: false,
+            error_message: None,
+            hidden: false,
+            multiline: false,
+            editor: None,
+            confirmation: None,
+            max_attempts: None,
+            timeout: None,
+            countdown: None,
+            default_on_eof: false,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            history_file: None,
+            history_limit: 500,
+            keep_history: true,
+            edit_mode: None,
+            output: None,
+            skip: None,
+            validators: Vec::new(),
+            slow_validators: Vec::new(),
+            transforms: Vec::new(),
+            completer: None,
+            parser: Box::new(parser),
+        }
+    }
+
+    /// Sets the coloration of the prompt.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the prompt, in place of the theme's.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let wipe = Prompt::confirm("Wipe the disk? (y/N)")
+    ///     .default(false)
+    ///     .style(Style::new().color(Choice::Normal(ChoiceColor::Red)).bold())
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Shows `hint` dimmed under the question while it's answered, and wipes it
+    /// once it is, so the question itself can stay short. Where the prompt can't be
+    /// redrawn, like when stdin isn't a terminal, the hint is printed on the line
+    /// before it instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let port = Prompt::int("Port")
+    ///     .range(1..=65535)
+    ///     .hint("Port the server listens on, 1-65535")
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn hint(mut self, hint: &str) -> Self {
+        self.hint = Some(hint.to_string());
+        self
+    }
+
+    /// Shows `placeholder` dimmed where the answer goes until something's typed,
+    /// like an example of what's asked for. It's never taken as the answer. Only
+    /// prompts read with the line editor in a terminal show it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let email = Prompt::text("Email").placeholder("user@example.com").ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn placeholder(mut self, placeholder: &str) -> Self {
+        self.placeholder = Some(placeholder.to_string());
+        self
+    }
+
+    /// Sets the coloration from the `Option<Choice>` the free functions take.
+    pub(crate) fn colored(mut self, colored: Option<Choice>) -> Self {
+        self.style = colored.map(Style::from);
+        self
+    }
+
+    /// Sets the value returned when the answer is left empty.
+    pub fn default(mut self, default: T) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Shows the default after the question, so it doesn't have to be written into
+    /// it by hand and can't fall out of step with it: `[8080]` for text and number
+    /// prompts, and `(Y/n)` or `(y/N)` for yes/no questions, or `(y/n)` without a
+    /// default. A default from [`set_defaults`](crate::set_defaults) is the one
+    /// shown. Other prompts show nothing more.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// // Asks "Port [8080]: ".
+    /// let port = Prompt::int("Port").default(8080).show_default().ask()?;
+    /// // Asks "Continue? (Y/n) ".
+    /// let go_on = Prompt::confirm("Continue?").default(true).show_default().ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn show_default(mut self) -> Self {
+        self.default_shown = true;
+        self
+    }
+
+    /// Takes the answer off the screen once Enter is pressed, leaving the question on
+    /// its own, for something like a token that's fine to see while it's typed but
+    /// shouldn't stay up afterwards. Unlike [`password`](Prompt::password), it's
+    /// echoed as it's typed. Where the prompt can't be redrawn, like when stdin isn't
+    /// a terminal, the answer stays where it was typed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let token = Prompt::text("API token").erase_answer().ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn erase_answer(mut self) -> Self {
+        self.erased = true;
+        self
+    }
+
+    /// Sets the message printed when an answer is rejected, before asking again.
+    /// This replaces the prompt's own, more specific messages.
+    pub fn error_message(mut self, message: &str) -> Self {
+        self.error_message = Some(message.to_string());
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` answers in a
+    /// row have been rejected, instead of asking forever. Useful when the answers
+    /// come from a pipe that may never send a valid one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// match Prompt::int("Port").range(1..=65535).max_attempts(3).ask() {
+    ///     Ok(port) => println!("Listening on {}", port),
+    ///     Err(PromptError::TooManyAttempts) => eprintln!("No valid port given."),
+    ///     Err(err) => eprintln!("{}", err),
+    /// }
+    /// ```
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Stops waiting once `timeout` has passed without an accepted answer, so
+    /// unattended scripts don't hang. The prompt then returns its default, or
+    /// [`PromptError::Timeout`] if it has none.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # use std::time::Duration;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let update = Prompt::confirm("Install updates? (Y/n)")
+    ///     .default(true)
+    ///     .timeout(Duration::from_secs(30))
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Counts down from `seconds` next to the prompt before asking, and returns the
+    /// default if nothing is pressed by the time it reaches zero, for batch tools
+    /// that shouldn't wait on an empty terminal. Enter takes the default right away,
+    /// a key that's an answer on its own, like `y` or `n`, is taken like a typed
+    /// answer, and anything else stops the countdown and asks as usual, as does a
+    /// key the [slow checks](PromptBuilder::validate_slow) or the
+    /// [confirmation](PromptBuilder::confirm) turn down.
+    ///
+    /// Only prompts with a default count down, and only in a terminal outside
+    /// [accessibility mode](crate::set_accessible).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let resume = Prompt::confirm("Resume the last run? (Y/n)")
+    ///     .default(true)
+    ///     .countdown(5)
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn countdown(mut self, seconds: u64) -> Self {
+        self.countdown = Some(seconds);
+        self
+    }
+
+    /// Returns the default when stdin is closed before an answer is given, rather
+    /// than [`PromptError::Eof`]. Handy when a script pipes in fewer answers than
+    /// there are prompts. Without a default, it's still an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// // `yes '' | my-installer` and `my-installer < /dev/null` both go with `true`.
+    /// let telemetry = Prompt::confirm("Send anonymous usage stats? (Y/n)")
+    ///     .default(true)
+    ///     .default_on_eof()
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn default_on_eof(mut self) -> Self {
+        self.default_on_eof = true;
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// answer is read from stdin anyway.
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the answer from the environment variable `var` when it's set and not
+    /// empty, without prompting. The value goes through the same checks as a typed
+    /// answer, but a rejected one is returned as [`PromptError::Validation`] rather
+    /// than asked again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// // `MYAPP_REGION=eu-west myapp` doesn't ask.
+    /// let region = Prompt::text("Region").env("MYAPP_REGION").ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the answer from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without prompting. The environment variable set
+    /// with [`env`](PromptBuilder::env) still comes first. Arguments loaded with
+    /// [`load_args`](crate::load_args) are taken before the file. The defaults set
+    /// with [`set_defaults`](crate::set_defaults) are looked up by `key` too, in
+    /// place of the prompt's text.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// # #[cfg(feature = "toml")]
+    /// load_answers("answers.toml")?;
+    /// let host = Prompt::text("Database host").key("db_host").ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Keeps the answers typed at this prompt in the file at `path`, one per line,
+    /// so Up and Down bring back the ones from earlier runs as well, like a shell's
+    /// history. Prompts given the same file share their history. A missing file
+    /// is created once there's an answer to write.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let query = Prompt::text("Search")
+    ///     .history_file(".search_history")
+    ///     .history_limit(100)
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn history_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.history_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets how many answers the [`history_file`](PromptBuilder::history_file) keeps,
+    /// in place of 500. The oldest ones go first.
+    pub fn history_limit(mut self, limit: usize) -> Self {
+        self.history_limit = limit;
+        self
+    }
+
+    /// Sets which keys edit the answer, in place of the mode set with
+    /// [`set_edit_mode`](crate::set_edit_mode).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let command = Prompt::text(":").edit_mode(EditMode::Vi).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn edit_mode(mut self, mode: EditMode) -> Self {
+        self.edit_mode = Some(mode);
+        self
+    }
+
+    /// Sets where the prompt is drawn, in place of the output set with
+    /// [`set_output`](crate::set_output).
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Adds a check that runs on the answer as typed, once it has parsed. If it
+    /// returns an error, the message is printed and the prompt is asked again.
+    /// Checks run in the order they were added, and aren't affected by
+    /// [`error_message`](PromptBuilder::error_message). The default isn't checked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let username = Prompt::text("Username")
+    ///     .validate(|input| match input.len() {
+    ///         3..=16 => Ok(()),
+    ///         _ => Err("Usernames are 3 to 16 characters long.".to_string()),
+    ///     })
+    ///     .validate(|input| match input {
+    ///         "root" | "admin" => Err(format!("{} is reserved.", input)),
+    ///         _ => Ok(()),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(mut self, validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Adds a check like [`validate`](PromptBuilder::validate) that can take a while,
+    /// like looking up a hostname or asking an API. It runs on a thread of its own
+    /// once the other checks pass, with a spinner under the question until it's
+    /// done, so the prompt doesn't look stuck. Slow checks run in the order they
+    /// were added, and never on the default.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// use std::net::ToSocketAddrs;
+    ///
+    /// # fn main() -> Result<(), PromptError> {
+    /// let host = Prompt::text("Host")
+    ///     .validate_slow(|input| match (input, 443).to_socket_addrs() {
+    ///         Ok(_) => Ok(()),
+    ///         Err(_) => Err(format!("Couldn't find {}.", input)),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_slow(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        self.slow_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Adds a step that reworks the value once it has been accepted, just before
+    /// it's returned, like lowercasing it or stripping quotes. Steps run in the order
+    /// they were added. The default is returned as given.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let branch = Prompt::text("Branch")
+    ///     .validate(|input| match input.contains(' ') {
+    ///         true => Err("Branch names can't contain spaces.".to_string()),
+    ///         false => Ok(()),
+    ///     })
+    ///     .transform(|branch| branch.trim_matches(['"', '\'']).to_lowercase())
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn transform(mut self, transform: impl Fn(T) -> T + 'static) -> Self {
+        self.transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Completes answers with `completions`, which is handed what's been typed so
+    /// far and returns the whole answers it could be. In a terminal, the rest of the
+    /// first one is hinted after the cursor as each key is typed, and Right takes it.
+    /// Tab fills in as much as they all agree on, or lists them if that adds nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let branches = ["main", "release/1.0", "release/1.1"];
+    /// let branch = Prompt::text("Branch")
+    ///     .completions(move |input| {
+    ///         branches
+    ///             .iter()
+    ///             .filter(|branch| branch.starts_with(input))
+    ///             .map(|branch| branch.to_string())
+    ///             .collect()
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn completions(mut self, completions: impl Fn(&str) -> Vec<String> + 'static) -> Self {
+        self.completer = Some(Box::new(completions));
+        self
+    }
+
+    /// Asks a second time with `prompt` after each accepted answer, and starts over
+    /// with a mismatch message unless both entries agree. Meant for "set a new
+    /// password" flows, but works with any prompt. An empty answer that falls back
+    /// to the default isn't confirmed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let password = Prompt::password("New password")
+    ///     .confirm("Repeat password")
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn confirm(mut self, prompt: &str) -> Self {
+        self.confirmation = Some(prompt.to_string());
+        self
+    }
+
+    /// Lets the prompt be skipped, so it returns `None` rather than insisting on an
+    /// answer. Pressing Esc skips it, and so does answering `-`, which works when
+    /// stdin isn't a terminal as well. Call this last, once everything else is set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// match Prompt::text("Nickname (- to skip)").allow_skip().ask()? {
+    ///     Some(nickname) => println!("Hi, {}!", nickname),
+    ///     None => println!("Hi there!"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn allow_skip(mut self) -> Skippable<T> {
+        self.skip = Some("-".to_string());
+        Skippable { builder: self }
+    }
+
+    /// Shows the prompt and reads answers until one is accepted.
+    pub fn ask(&self) -> Result<T, PromptError> {
+        self.ask_from(None)
+    }
+
+    /// Like [`ask`](PromptBuilder::ask), with the line in a terminal starting out as
+    /// `prefill` the first time it's read.
+    pub(crate) fn ask_from(&self, prefill: Option<&str>) -> Result<T, PromptError> {
+        self.run(prefill)
+            .map(|value| value.expect("only skippable prompts are skipped"))
+    }
+
+    /// Reads answers until one is accepted, or the prompt is skipped, with the
+    /// first one read starting out as `prefill`.
+    fn run(&self, prefill: Option<&str>) -> Result<Option<T>, PromptError> {
+        let _output = term::draw_on(self.output);
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            if let Some((origin, value)) = given_answer(
+                &self.prompt,
+                self.key.as_ref(),
+                self.env.as_ref(),
+                self.hidden,
+            ) {
+                // Secrets are taken as they are, the way they're read when typed.
+                let given = match self.hidden {
+                    true => value.as_str(),
+                    false => value.trim(),
+                };
+                let answer = self
+                    .given(given)
+                    .map_err(|message| rejected(origin, message))?;
+                self.record(&value)?;
+                return Ok(answer);
+            }
+            let default = self.fallback();
+            if let Some(default) = default.as_ref().filter(|_| assume_defaults()) {
+                return Ok(Some(default.clone()));
+            }
+            if self.non_interactive.use_default()? {
+                return match (&default, &self.skip) {
+                    (Some(default), _) => Ok(Some(default.clone())),
+                    (None, Some(_)) => Ok(None),
+                    (None, None) => Err(PromptError::NotInteractive),
+                };
+            }
+            let mut error = None;
+            if let Some((seconds, default)) = self.countdown.zip(default.as_ref()) {
+                if term::redraws() {
+                    let prompt = self.render(&self.prompt);
+                    let answer = |c: char| self.check(&c.to_string()).ok().map(|_| c);
+                    match confirm::count_down(&prompt, seconds, answer)? {
+                        Countdown::Done => {
+                            self.record("")?;
+                            return Ok(Some(default.clone()));
+                        }
+                        // It's taken like a typed answer, so it can still be turned
+                        // down by a slow check or its confirmation.
+                        Countdown::Answer(c) => {
+                            let input = c.to_string();
+                            match self.accept(&input, None)? {
+                                Ok(value) => {
+                                    self.add_to_history(&input)?;
+                                    self.record(&input)?;
+                                    self.settle(Status::Answered, &input, Some(&value))?;
+                                    return Ok(Some(value));
+                                }
+                                Err(message) => error = Some(message),
+                            }
+                        }
+                        Countdown::Stopped => {}
+                    }
+                }
+            }
+            let deadline = self.timeout.map(|timeout| Instant::now() + timeout);
+            match self.run_until(deadline, prefill, error) {
+                Err(err @ (PromptError::Interrupted | PromptError::Cancelled)) => {
+                    outln!()?;
+                    Err(err)
+                }
+                Err(PromptError::Eof) if self.default_on_eof && default.is_some() => {
+                    outln!()?;
+                    Ok(default)
+                }
+                Err(PromptError::Timeout) => {
+                    // Whatever was typed so far is left on the prompt line.
+                    outln!()?;
+                    match default {
+                        Some(default) => Ok(Some(default)),
+                        None => Err(PromptError::Timeout),
+                    }
+                }
+                result => result,
+            }
+        })
+    }
+
+    /// Asks until an answer's accepted, with `error` shown by the first read if an
+    /// answer was already turned down.
+    fn run_until(
+        &self,
+        deadline: Option<Instant>,
+        mut prefill: Option<&str>,
+        mut error: Option<String>,
+    ) -> Result<Option<T>, PromptError> {
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            let input = self.read(&self.prompt, deadline, error.take(), prefill.take())?;
+            if !self.skips(&input) && wizard::goes_back(&input) {
+                return Err(PromptError::Back);
+            }
+            if input.is_empty() {
+                if let Some(default) = self.fallback() {
+                    self.record(&input)?;
+                    self.settle(Status::Answered, &input, Some(&default))?;
+                    return Ok(Some(default));
+                }
+            }
+            if self.skips(&input) {
+                self.record(self.skip.as_deref().unwrap_or_default())?;
+                self.settle(Status::Cancelled, "", None)?;
+                return Ok(None);
+            }
+            match self.accept(&input, deadline)? {
+                Ok(value) => {
+                    self.add_to_history(&input)?;
+                    self.record(&input)?;
+                    self.settle(Status::Answered, &input, Some(&value))?;
+                    return Ok(Some(value));
+                }
+                // The message is shown by the next read, unless that was the last try.
+                Err(message) => match attempts.reject() {
+                    Ok(()) => error = Some(message),
+                    Err(err) => {
+                        print_error(&message)?;
+                        return Err(err);
+                    }
+                },
+            }
+        }
+    }
+
+    /// Redraws the line of the prompt just answered with `input`, which gave `value`,
+    /// after the theme's symbol for `status`, or as the theme's summary of it, and
+    /// without the answer if it's erased. It's left as it is if there's nothing to
+    /// change, or the line isn't there to redraw.
+    fn settle(&self, status: Status, input: &str, value: Option<&T>) -> Result<(), PromptError> {
+        let theme = theme::theme();
+        let mark = theme.mark(status);
+        let redrawn = !self.multiline && self.editor.is_none() && self.confirmation.is_none();
+        let unchanged = mark.is_empty() && theme.summary.is_none() && !self.erased;
+        if unchanged || !redrawn || !term::redraws() {
+            return Ok(());
+        }
+        let shown = match (value, self.describe_answer) {
+            _ if self.hidden || self.erased => locale().hidden.clone(),
+            (None, _) => locale().none.clone(),
+            (Some(value), Some(describe)) => describe(value),
+            (Some(_), None) => input.to_string(),
+        };
+        let line = match theme.summary(status, &self.prompt, &shown) {
+            Some(summary) => summary,
+            None if self.hidden || self.erased => {
+                format!("{}{}", mark, self.question(&self.prompt, &theme))
+            }
+            None => format!(
+                "{}{}{}",
+                mark,
+                self.question(&self.prompt, &theme),
+                theme.answer_style.paint(input)
+            ),
+        };
+        term::output(|out| {
+            out.queue(ansi::MoveUp(1))?;
+            out.queue(ansi::MoveToColumn(0))?;
+            out.queue(ansi::Clear(ClearType::FromCursorDown))?;
+            writeln!(out, "{}", line)?;
+            out.flush()
+        })?;
+        Ok(())
+    }
+
+    /// Runs an answer through the parser, the checks, the confirmation and the
+    /// transforms, or returns the message to print if it's rejected.
+    fn accept(
+        &self,
+        input: &str,
+        deadline: Option<Instant>,
+    ) -> Result<Result<T, String>, PromptError> {
+        let value = match self.check(input) {
+            Ok(value) => value,
+            Err(message) => return Ok(Err(message)),
+        };
+        if let Err(message) = validate_slowly(&self.slow_validators, input)? {
+            return Ok(Err(message));
+        }
+        if let Some(confirmation) = &self.confirmation {
+            if *self.read(confirmation, deadline, None, None)? != *input {
+                return Ok(Err(locale().mismatch.clone()));
+            }
+        }
+
+        Ok(Ok(self.finish(value)))
+    }
+
+    /// Runs an answer through the parser and the checks.
+    fn check(&self, input: &str) -> Result<T, String> {
+        let value = (self.parser)(input)
+            .map_err(|message| self.error_message.clone().unwrap_or(message))?;
+        validate(&self.validators, input)?;
+        Ok(value)
+    }
+
+    /// Runs an accepted value through the transforms.
+    fn finish(&self, value: T) -> T {
+        self.transforms
+            .iter()
+            .fold(value, |value, transform| transform(value))
+    }
+
+    /// The default from the providers set with [`set_defaults`](crate::set_defaults)
+    /// if one of them has an answer this prompt takes, or else the prompt's own.
+    fn fallback(&self) -> Option<T> {
+        defaults::provided(&self.prompt, self.key.as_ref())
+            .and_then(|input| self.check(input.trim()).ok())
+            .map(|value| self.finish(value))
+            .or_else(|| self.default.clone())
+    }
+
+    /// Whether `input` skips a skippable prompt.
+    fn skips(&self, input: &str) -> bool {
+        self.skip
+            .as_ref()
+            .is_some_and(|sentinel| input == sentinel || input == term::ESCAPE)
+    }
+
+    /// What an answer given up front, rather than typed, comes to. It's taken like
+    /// a typed one, except that it isn't confirmed.
+    fn given(&self, input: &str) -> Result<Option<T>, String> {
+        match self.fallback() {
+            Some(default) if input.is_empty() => Ok(Some(default)),
+            _ if self.skips(input) => Ok(None),
+            _ => self.check(input).map(|value| Some(self.finish(value))),
+        }
+    }
+
+    /// Whether the answers typed here are kept for Up and Down to bring back.
+    fn keeps_history(&self) -> bool {
+        self.keep_history && !self.hidden && !self.multiline
+    }
+
+    /// Adds `input`, an accepted answer, to the answers Up and Down bring back, if
+    /// they're kept.
+    fn add_to_history(&self, input: &str) -> Result<(), PromptError> {
+        if self.keeps_history() {
+            match &self.history_file {
+                Some(path) => history::add_to_file(path, input, self.history_limit)?,
+                None => history::add(&self.prompt, input),
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes `input` down if answers are being recorded, unless it's a secret.
+    fn record(&self, input: &str) -> Result<(), PromptError> {
+        match self.hidden {
+            true => {
+                #[cfg(feature = "log")]
+                crate::logging::answered(&self.prompt, None);
+                Ok(())
+            }
+            false => session::record(&self.prompt, input),
+        }
+    }
+
+    /// Shows `prompt` and reads an answer in whichever way this prompt needs, after
+    /// `error` if the last answer was rejected, starting out as `prefill` where
+    /// it's typed on a line.
+    fn read(
+        &self,
+        prompt: &str,
+        deadline: Option<Instant>,
+        error: Option<String>,
+        prefill: Option<&str>,
+    ) -> Result<Buffer, PromptError> {
+        let rendered = self.render(prompt);
+        let escape = self.skip.is_some() || wizard::can_go_back();
+        let mode = self.edit_mode.unwrap_or_else(editor::edit_mode);
+        if self.hidden {
+            let below = self.hint.as_deref();
+            return editor::read_secret(&rendered, below, escape, deadline, error.as_deref());
+        }
+
+        if let Some(template) = self.editor.as_ref().filter(|_| term::interactive()) {
+            let label = paint(prompt, &self.style, &theme::theme().prompt_style);
+            return external::edit(&label, template, error.as_deref()).map(term::buffer);
+        }
+        if self.multiline {
+            if let Some(hint) = &self.hint {
+                outln!("{}", Style::new().dimmed().paint(hint))?;
+            }
+            return editor::read_lines(&rendered, mode, deadline, error.as_deref())
+                .map(term::buffer);
+        }
+
+        // The editor reads in raw mode in a terminal, where Ctrl+C arrives as a key
+        // and comes back as `PromptError::Interrupted` instead of killing the process.
+        let completer = self.completer.as_ref();
+        let history = match &self.history_file {
+            _ if !self.keeps_history() => Vec::new(),
+            Some(path) if prompt == self.prompt => history::file_entries(path)?,
+            _ => history::entries(prompt),
+        };
+        let extras = Extras {
+            completer,
+            history: &history,
+            below: self.hint.as_deref(),
+            placeholder: self.placeholder.as_deref(),
+            prefill,
+        };
+        editor::read_line(&rendered, &extras, escape, mode, deadline, error.as_deref())
+            .map(term::buffer)
+    }
+
+    /// The default as it's shown after the question, with a space before it, if
+    /// it's to be shown.
+    fn default_label(&self, theme: &Theme) -> String {
+        let label = self
+            .describe_default
+            .filter(|_| self.default_shown)
+            .and_then(|describe| describe(self.fallback().as_ref()));
+        match label {
+            Some(label) => format!(" {}", theme.default_style.paint(&label)),
+            None => String::new(),
+        }
+    }
+
+    /// `prompt` as it's asked, after the theme's symbol for asking.
+    fn render(&self, prompt: &str) -> String {
+        let theme = theme::theme();
+        format!(
+            "{}{}",
+            theme.mark(Status::Asking),
+            self.question(prompt, &theme)
+        )
+    }
+
+    /// `prompt` laid out the way this prompt asks it.
+    fn question(&self, prompt: &str, theme: &Theme) -> String {
+        match &self.layout {
+            Layout::Verbatim => paint(prompt, &self.style, &theme.prompt_style),
+            Layout::Question(separator) => {
+                format!(
+                    "{}{}{}",
+                    paint(prompt, &self.style, &theme.prompt_style),
+                    self.default_label(theme),
+                    separator
+                )
+            }
+            Layout::Lines => {
+                format!(
+                    "{} {}",
+                    paint(prompt, &self.style, &theme.prompt_style),
+                    locale().lines_hint
+                )
+            }
+            Layout::Labelled(plain) => {
+                format!(
+                    "{} {}: ",
+                    plain,
+                    paint(prompt, &self.style, &theme.prompt_style)
+                )
+            }
+            Layout::List(list) => {
+                let list_style = self.style.as_ref().unwrap_or(&theme.list_style);
+                let default = self.fallback().zip(self.show_default);
+                let list =
+                    match default.and_then(|(default, show)| default_item(list, &show(&default))) {
+                        Some(item) => format!(
+                            "{}{}{}",
+                            list_style.paint(&list[..item.start]),
+                            theme.default_style.paint(&list[item.clone()]),
+                            list_style.paint(&list[item.end..])
+                        ),
+                        None => list_style.paint(list),
+                    };
+                format!("{}: [{}]: ", theme.prompt_style.paint(prompt), list)
+            }
+        }
+    }
+}
+
+/// A prompt that can be skipped, created with
+/// [`allow_skip`](PromptBuilder::allow_skip).
+pub struct Skippable<T> {
+    builder: PromptBuilder<T>,
+}
+
+impl<T: Clone> Skippable<T> {
+    /// Sets the answer that skips the prompt, in place of `-`. Esc still works too.
+    pub fn sentinel(mut self, sentinel: &str) -> Self {
+        self.builder.skip = Some(sentinel.to_string());
+        self
+    }
+
+    /// Shows the prompt and reads answers until one is accepted, returning `None`
+    /// if the prompt is skipped instead.
+    pub fn ask(&self) -> Result<Option<T>, PromptError> {
+        self.builder.run(None)
+    }
+}
+
+impl<T: PartialOrd + Display + Clone + 'static> PromptBuilder<T> {
+    /// Only accepts answers inside `range`, printing the bounds otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let workers = Prompt::int("Workers").range(1..=64).default(4).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn range(mut self, range: RangeInclusive<T>) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let value = parser(input)?;
+            if range.contains(&value) {
+                Ok(value)
+            } else {
+                Err(fill(
+                    &locale().between,
+                    &[("min", range.start()), ("max", range.end())],
+                ))
+            }
+        });
+        self
+    }
+
+    /// Only accepts answers of at least `min`.
+    pub fn min(mut self, min: T) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let value = parser(input)?;
+            if value >= min {
+                Ok(value)
+            } else {
+                Err(fill(&locale().at_least, &[("min", &min)]))
+            }
+        });
+        self
+    }
+
+    /// Only accepts answers of at most `max`.
+    pub fn max(mut self, max: T) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let value = parser(input)?;
+            if value <= max {
+                Ok(value)
+            } else {
+                Err(fill(&locale().at_most, &[("max", &max)]))
+            }
+        });
+        self
+    }
+}
+
+impl PromptBuilder<f64> {
+    /// Rounds answers to `decimals` places. Checks added before this one see the
+    /// answer as typed; checks added after see it rounded.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let price = Prompt::float("Price").min(0.0).round_to(2).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn round_to(mut self, decimals: u32) -> Self {
+        let parser = self.parser;
+        // An f64 doesn't hold more decimal digits than this, so there's nothing to
+        // round past it.
+        let scale = 10f64.powi(decimals.min(f64::DIGITS) as i32);
+        self.parser = Box::new(move |input| {
+            let value = parser(input)?;
+            let rounded = (value * scale).round() / scale;
+            Ok(if rounded.is_finite() { rounded } else { value })
+        });
+        self
+    }
+
+    /// Rejects answers typed with more than `decimals` digits after the decimal point.
+    pub fn max_decimals(mut self, decimals: usize) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let value = parser(input)?;
+            let normal = number(input).unwrap_or_default();
+            let mantissa = normal.split(['e', 'E']).next().unwrap_or_default();
+            let typed = mantissa
+                .split_once('.')
+                .map_or(0, |(_, fraction)| fraction.len());
+            if typed <= decimals {
+                Ok(value)
+            } else {
+                Err(fill(&locale().decimals, &[("count", &decimals)]))
+            }
+        });
+        self
+    }
+}
+
+/// Counts rejected answers to `prompt` against an optional limit.
+pub(crate) struct Attempts<'a> {
+    #[cfg_attr(not(feature = "log"), allow(dead_code))]
+    prompt: &'a str,
+    limit: Option<usize>,
+    rejected: usize,
+}
+
+impl<'a> Attempts<'a> {
+    pub(crate) fn new(prompt: &'a str, limit: Option<usize>) -> Self {
+        Attempts {
+            prompt,
+            limit,
+            rejected: 0,
+        }
+    }
+
+    /// Counts one more rejected answer, failing once the limit has been reached.
+    pub(crate) fn reject(&mut self) -> Result<(), PromptError> {
+        self.rejected += 1;
+        #[cfg(feature = "log")]
+        crate::logging::rejected(self.prompt, self.rejected);
+        #[cfg(feature = "tracing")]
+        crate::spans::attempt(self.rejected + 1);
+        match self.limit {
+            Some(limit) if self.rejected >= limit => Err(PromptError::TooManyAttempts),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The answer to `prompt` given up front, from the environment variable `env` if
+/// it's set and not empty, or else from the arguments or answers file under `key`,
+/// or else from a replayed session unless it's `hidden`. Comes with a description
+/// of where it came from, for error messages, which leaves out the answer if it's
+/// hidden.
+pub(crate) fn given_answer(
+    prompt: &str,
+    key: Option<&String>,
+    env: Option<&String>,
+    hidden: bool,
+) -> Option<(String, String)> {
+    let from_env = env.and_then(|var| {
+        let value = std::env::var(var).ok().filter(|value| !value.is_empty())?;
+        match hidden {
+            true => Some((var.clone(), value)),
+            false => Some((format!("{}={:?}", var, value), value)),
+        }
+    });
+    let from_file = || {
+        let key = key?;
+        session::answer(key, hidden)
+    };
+    from_env.or_else(from_file).or_else(|| {
+        let value = (!hidden).then(|| session::replayed(prompt)).flatten()?;
+        Some((
+            format!("replayed answer {:?} to {:?}", value, prompt),
+            value,
+        ))
+    })
+}
+
+/// The error for an answer given up front that was rejected with `message`.
+pub(crate) fn rejected(origin: String, message: String) -> PromptError {
+    let reason = match message.is_empty() {
+        true => "that isn't a valid answer".to_string(),
+        false => message,
+    };
+    PromptError::Validation(format!("{}: {}", origin, reason))
+}
+
+/// Runs `validators` on `input` in order, stopping at the first failure.
+pub(crate) fn validate(validators: &[Validator], input: &str) -> Result<(), String> {
+    validators.iter().try_for_each(|validator| validator(input))
+}
+
+/// Runs `validators` on `input` in order on a thread of their own, stopping at the
+/// first failure. A spinner turns on the cursor's line while they run, once they've
+/// taken long enough for it to be worth drawing.
+fn validate_slowly(
+    validators: &[SlowValidator],
+    input: &str,
+) -> Result<Result<(), String>, PromptError> {
+    if validators.is_empty() {
+        return Ok(Ok(()));
+    }
+    let (sender, receiver) = mpsc::channel();
+    let validators = validators.to_vec();
+    let input = input.to_string();
+    let worker = thread::spawn(move || {
+        let result = validators
+            .iter()
+            .try_for_each(|validator| validator(&input));
+        let _ = sender.send(result);
+    });
+    let theme = theme::theme();
+    let mut frames = theme::spinner().iter().cycle();
+    let mut drawn = false;
+    let mut wait = Duration::from_millis(150);
+    let result = loop {
+        match receiver.recv_timeout(wait) {
+            Ok(result) => break result,
+            Err(RecvTimeoutError::Timeout) if term::redraws() => {
+                let spinner = theme.highlight_style.paint(frames.next().unwrap_or(&""));
+                term::output(|out| {
+                    out.queue(ansi::MoveToColumn(0))?;
+                    out.queue(ansi::Clear(ClearType::CurrentLine))?;
+                    write!(out, "{} {}", spinner, locale().checking)?;
+                    out.flush()
+                })?;
+                drawn = true;
+                wait = Duration::from_millis(80);
+            }
+            Err(RecvTimeoutError::Timeout) => wait = Duration::from_secs(1),
+            Err(RecvTimeoutError::Disconnected) => match worker.join() {
+                Err(panic) => panic::resume_unwind(panic),
+                Ok(()) => unreachable!("the checks finished without a result"),
+            },
+        }
+    };
+    if drawn {
+        term::output(|out| {
+            out.queue(ansi::MoveToColumn(0))?;
+            out.queue(ansi::Clear(ClearType::CurrentLine))?;
+            out.flush()
+        })?;
+    }
+    Ok(result)
+}
+
+/// Accepts any answer as-is.
+pub(crate) fn parse_string(input: &str) -> Result<String, String> {
+    Ok(input.to_string())
+}
+
+/// `default` in brackets, as text and number prompts show it.
+fn bracketed<T: Display>(default: Option<&T>) -> Option<String> {
+    default.map(|default| format!("[{}]", default))
+}
+
+/// Accepts the words set with [`set_yes_no`](crate::set_yes_no), `y` or `n` by
+/// default, in either case, and asks again after anything else.
+pub(crate) fn parse_tf(input: &str) -> Result<bool, String> {
+    let words = confirm::yes_no();
+    words.parse(input).ok_or_else(|| words.message())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::MockTerminal;
+
+    #[test]
+    fn rejects_then_accepts() {
+        let mut terminal = MockTerminal::new().type_text("abc\n42\n");
+        let answer = terminal.run(|| Prompt::int("Workers").ask());
+        assert_eq!(answer.unwrap(), 42);
+        assert_eq!(terminal.keys_left(), 0);
+        assert_eq!(terminal.screen(), "Workers: 42");
+        assert!(terminal.output().contains(&locale().whole_number));
+    }
+
+    #[test]
+    fn runs_out_of_keys() {
+        let mut terminal = MockTerminal::new().type_text("ada");
+        let answer = terminal.run(|| Prompt::text("Name").ask());
+        assert!(matches!(answer, Err(PromptError::Eof)));
+    }
+
+    #[test]
+    fn keeps_only_accepted_answers_in_the_history() {
+        let prompt = "Workers, kept in the history";
+        let mut terminal = MockTerminal::new().type_text("abc\n42\n");
+        terminal.run(|| Prompt::int(prompt).ask()).unwrap();
+        assert_eq!(history::entries(prompt), ["42"]);
+    }
+
+    #[test]
+    fn leaves_hidden_answers_out_of_errors() {
+        std::env::set_var("CUMAEA_TEST_WEAK_PASSWORD", "hunter2");
+        let answer = Prompt::password("Password")
+            .env("CUMAEA_TEST_WEAK_PASSWORD")
+            .validate(|password| match password.len() < 12 {
+                true => Err("too weak".to_string()),
+                false => Ok(()),
+            })
+            .ask();
+        match answer {
+            Err(PromptError::Validation(message)) => {
+                assert_eq!(message, "CUMAEA_TEST_WEAK_PASSWORD: too weak");
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn keeps_hidden_answers_as_given() {
+        std::env::set_var("CUMAEA_TEST_SPACED_PASSWORD", " hunter2 ");
+        let answer = Prompt::password("Password")
+            .env("CUMAEA_TEST_SPACED_PASSWORD")
+            .ask();
+        assert_eq!(answer.unwrap(), " hunter2 ");
+    }
+
+    #[test]
+    fn rounds_to_what_an_f64_holds() {
+        let terminal = || MockTerminal::new().type_text("2.675\n");
+        let rounded = terminal().run(|| Prompt::float("Price").round_to(1).ask());
+        assert_eq!(rounded.unwrap(), 2.7);
+        let kept = terminal().run(|| Prompt::float("Price").round_to(u32::MAX).ask());
+        assert_eq!(kept.unwrap(), 2.675);
+    }
+
+    #[test]
+    fn checks_keys_pressed_during_a_countdown_like_typed_answers() {
+        let prompt = "Resume, counting down";
+        let mut terminal = MockTerminal::new().type_text("yn\n");
+        let answer = terminal.run(|| {
+            Prompt::confirm(prompt)
+                .default(true)
+                .countdown(5)
+                .validate_slow(|input| match input {
+                    "y" => Err("Nothing to resume.".to_string()),
+                    _ => Ok(()),
+                })
+                .ask()
+        });
+        assert!(!answer.unwrap());
+        assert!(terminal.output().contains("Nothing to resume."));
+        assert_eq!(history::entries(prompt), ["n"]);
+    }
+
+    #[test]
+    fn keeps_keys_pressed_during_a_countdown_in_the_history() {
+        let prompt = "Resume, kept in the history";
+        let mut terminal = MockTerminal::new().type_text("n");
+        let answer = terminal.run(|| Prompt::confirm(prompt).default(true).countdown(5).ask());
+        assert!(!answer.unwrap());
+        assert_eq!(history::entries(prompt), ["n"]);
+    }
+}
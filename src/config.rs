@@ -0,0 +1,42 @@
+//! Settings read once from `CUMAEA_*` environment variables, so the people using a
+//! tool built on this crate can change how it prompts without the tool's help.
+
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// The settings from the environment that are switched on or off.
+pub(crate) struct EnvConfig {
+    /// `CUMAEA_NO_COLOR`: never draw in color.
+    pub(crate) no_color: bool,
+    /// `CUMAEA_ASSUME_DEFAULTS`: answer every prompt with its default.
+    pub(crate) assume_defaults: bool,
+    /// `CUMAEA_ACCESSIBLE`: never move the cursor or redraw.
+    pub(crate) accessible: bool,
+    /// `CUMAEA_PLAIN`: print no escape sequences.
+    pub(crate) plain: bool,
+}
+
+static CONFIG: OnceLock<EnvConfig> = OnceLock::new();
+
+/// The settings from the environment, read the first time they're needed.
+pub(crate) fn env_config() -> &'static EnvConfig {
+    CONFIG.get_or_init(|| EnvConfig {
+        no_color: enabled("CUMAEA_NO_COLOR"),
+        assume_defaults: enabled("CUMAEA_ASSUME_DEFAULTS"),
+        accessible: enabled("CUMAEA_ACCESSIBLE"),
+        plain: enabled("CUMAEA_PLAIN"),
+    })
+}
+
+/// Whether `var` is set to something other than nothing, `0` or `false`.
+fn enabled(var: &str) -> bool {
+    env::var(var).is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+}
+
+/// The file named by `var`, if it's set and not empty.
+pub(crate) fn env_path(var: &str) -> Option<PathBuf> {
+    env::var_os(var)
+        .filter(|value| !value.is_empty())
+        .map(PathBuf::from)
+}
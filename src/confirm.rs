@@ -0,0 +1,725 @@
+//! Yes/no questions, and the words they take for an answer.
+
+use crate::ansi::{self, Queue};
+use crate::builder::{parse_tf, Layout};
+use crate::locale::{fill, locale};
+use crate::term::{self, outln, Key, RawMode};
+use crate::{Choice, Prompt, PromptBuilder, PromptError};
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, PoisonError, RwLock};
+use std::time::{Duration, Instant};
+
+/// The words a yes/no question takes for yes and for no, like `o`/`n` in French or
+/// `j`/`n` in German. They're matched ignoring case and surrounding whitespace.
+///
+/// The default takes `y` and `n`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// let words = YesNo::new(&["o", "oui"], &["n", "non"]);
+/// assert_eq!(words.parse("Oui"), Some(true));
+/// assert_eq!(words.parse("n"), Some(false));
+/// assert_eq!(words.parse("yes"), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct YesNo {
+    yes: Vec<String>,
+    no: Vec<String>,
+    cancel: Vec<String>,
+    all: Vec<String>,
+    never: Vec<String>,
+}
+
+impl YesNo {
+    /// Takes any of `yes` for yes and any of `no` for no. The first word of each is
+    /// the one suggested when an answer is rejected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `yes` or `no` is empty.
+    pub fn new(yes: &[&str], no: &[&str]) -> Self {
+        assert!(
+            !yes.is_empty() && !no.is_empty(),
+            "a yes/no question needs a word for yes and one for no"
+        );
+        let words = |words: &[&str]| words.iter().map(|word| word.to_lowercase()).collect();
+        YesNo {
+            yes: words(yes),
+            no: words(no),
+            cancel: vec!["c".to_string()],
+            all: words(&["a", "all"]),
+            never: words(&["v", "never"]),
+        }
+    }
+
+    /// Takes any of `cancel` for cancel at the questions that have that option, like
+    /// [`prompt_tf_cancel`](crate::prompt_tf_cancel), in place of `c`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cancel` is empty.
+    pub fn cancel(mut self, cancel: &[&str]) -> Self {
+        assert!(!cancel.is_empty(), "cancelling needs a word");
+        self.cancel = cancel.iter().map(|word| word.to_lowercase()).collect();
+        self
+    }
+
+    /// Takes any of `all` for all and any of `never` for never at the questions
+    /// asked for each of a batch, like [`prompt_tf_all`](crate::prompt_tf_all), in
+    /// place of `a` or `all` and `v` or `never`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `all` or `never` is empty.
+    pub fn batch(mut self, all: &[&str], never: &[&str]) -> Self {
+        assert!(
+            !all.is_empty() && !never.is_empty(),
+            "a batch question needs a word for all and one for never"
+        );
+        let words = |words: &[&str]| words.iter().map(|word| word.to_lowercase()).collect();
+        self.all = words(all);
+        self.never = words(never);
+        self
+    }
+
+    /// Whether `answer` is one of the words for yes or for no, or `None` if it's
+    /// neither.
+    pub fn parse(&self, answer: &str) -> Option<bool> {
+        let answer = answer.trim().to_lowercase();
+        if self.yes.contains(&answer) {
+            Some(true)
+        } else if self.no.contains(&answer) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Whether `answer` is one of the words for cancel.
+    pub fn cancels(&self, answer: &str) -> bool {
+        self.cancel.contains(&answer.trim().to_lowercase())
+    }
+
+    /// The first word for `answer`, which is what's suggested for it.
+    pub(crate) fn word(&self, answer: bool) -> &str {
+        match answer {
+            true => &self.yes[0],
+            false => &self.no[0],
+        }
+    }
+
+    /// What's printed after an answer that's neither.
+    pub(crate) fn message(&self) -> String {
+        fill(
+            &locale().yes_or_no,
+            &[("yes", &self.yes[0]), ("no", &self.no[0])],
+        )
+    }
+
+    /// What's printed after an answer that's none of the three.
+    fn cancel_message(&self) -> String {
+        fill(
+            &locale().yes_no_or_cancel,
+            &[
+                ("yes", &self.yes[0]),
+                ("no", &self.no[0]),
+                ("cancel", &self.cancel[0]),
+            ],
+        )
+    }
+
+    /// Reads `answer` as yes, no or cancel.
+    fn parse_cancel(&self, answer: &str) -> Result<Confirmation, String> {
+        match self.parse(answer) {
+            Some(true) => Ok(Confirmation::Yes),
+            Some(false) => Ok(Confirmation::No),
+            None if self.cancels(answer) => Ok(Confirmation::Cancel),
+            None => Err(self.cancel_message()),
+        }
+    }
+
+    /// Reads `answer` as yes, no, all or never.
+    fn parse_batch(&self, answer: &str) -> Result<BatchAnswer, String> {
+        match self.parse(answer) {
+            Some(true) => return Ok(BatchAnswer::Yes),
+            Some(false) => return Ok(BatchAnswer::No),
+            None => {}
+        }
+        let answer = answer.trim().to_lowercase();
+        if self.all.contains(&answer) {
+            Ok(BatchAnswer::All)
+        } else if self.never.contains(&answer) {
+            Ok(BatchAnswer::Never)
+        } else {
+            Err(fill(
+                &locale().yes_no_all_never,
+                &[
+                    ("yes", &self.yes[0]),
+                    ("no", &self.no[0]),
+                    ("all", &self.all[0]),
+                    ("never", &self.never[0]),
+                ],
+            ))
+        }
+    }
+}
+
+impl Default for YesNo {
+    fn default() -> Self {
+        YesNo::new(&["y"], &["n"])
+    }
+}
+
+/// The answer to a yes/no question that can be cancelled, from
+/// [`prompt_tf_cancel`](crate::prompt_tf_cancel).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Confirmation {
+    /// Go ahead.
+    Yes,
+    /// Don't, but carry on with the rest.
+    No,
+    /// Neither: the whole operation should be abandoned.
+    Cancel,
+}
+
+/// The answer to a yes/no question asked over and over, like whether to overwrite
+/// each of a batch of files, from [`prompt_tf_all`](crate::prompt_tf_all).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BatchAnswer {
+    /// Yes, this time.
+    Yes,
+    /// No, this time.
+    No,
+    /// Yes, and to the rest of the batch without asking.
+    All,
+    /// No, and to the rest of the batch without asking.
+    Never,
+}
+
+impl BatchAnswer {
+    /// Whether this is yes, for this time or all of them.
+    pub fn is_yes(self) -> bool {
+        matches!(self, BatchAnswer::Yes | BatchAnswer::All)
+    }
+}
+
+/// The all or never answers given so far, by key.
+static BATCHES: Mutex<BTreeMap<String, BatchAnswer>> = Mutex::new(BTreeMap::new());
+
+fn batches() -> MutexGuard<'static, BTreeMap<String, BatchAnswer>> {
+    BATCHES.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Forgets an all or never answered to the questions asked under `key`, so the
+/// next one is asked again.
+pub fn forget_batch_answer(key: &str) {
+    batches().remove(key);
+}
+
+static YES_NO: RwLock<Option<YesNo>> = RwLock::new(None);
+
+/// Sets the words every yes/no question takes from here on, on every thread, like
+/// [`prompt_tf_default`](crate::prompt_tf_default) and
+/// [`Prompt::confirm`](crate::Prompt::confirm). Questions given their own with
+/// [`words`](PromptBuilder::words) keep them.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_yes_no(YesNo::new(&["j", "ja"], &["n", "nein"]));
+/// let proceed = prompt_tf_default("Fortfahren? (J/n) ", None, true);
+/// ```
+pub fn set_yes_no(words: YesNo) {
+    *YES_NO.write().unwrap_or_else(PoisonError::into_inner) = Some(words);
+}
+
+/// The words set with [`set_yes_no`], or else the locale's.
+pub(crate) fn yes_no() -> YesNo {
+    YES_NO
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_else(|| locale().yes_no.clone())
+}
+
+/// What's shown after a yes/no question the crate asks itself, like `(Y/n)`, with
+/// the default's word in capitals if it has one.
+pub(crate) fn hint(default: Option<bool>) -> String {
+    let words = yes_no();
+    let (yes, no) = (words.word(true), words.word(false));
+    match default {
+        Some(true) => format!("({}/{})", yes.to_uppercase(), no),
+        Some(false) => format!("({}/{})", yes, no.to_uppercase()),
+        None => format!("({}/{})", yes, no),
+    }
+}
+
+impl Prompt {
+    /// A yes/no question that can also be cancelled, like
+    /// [`prompt_tf_cancel`](crate::prompt_tf_cancel).
+    pub fn confirm_or_cancel(prompt: &str) -> PromptBuilder<Confirmation> {
+        PromptBuilder::new(prompt, Layout::Question(" "), |input| {
+            yes_no().parse_cancel(input)
+        })
+    }
+}
+
+impl Prompt {
+    /// A yes/no question for each of a batch, which also takes all and never for
+    /// an answer, like [`prompt_tf_all`](crate::prompt_tf_all). Call
+    /// [`remember`](PromptBuilder::remember) on it so an all or never answers the
+    /// rest of the batch.
+    pub fn confirm_all(prompt: &str) -> PromptBuilder<BatchAnswer> {
+        PromptBuilder::new(prompt, Layout::Question(" "), |input| {
+            yes_no().parse_batch(input)
+        })
+    }
+}
+
+impl Prompt {
+    /// A guard for something that can't be undone, like
+    /// [`prompt_confirm_phrase`](crate::prompt_confirm_phrase). Returns `true` only
+    /// if `phrase` is typed exactly, and `false` after anything else, without asking
+    /// again. Up and Down don't bring back earlier answers here.
+    pub fn confirm_phrase(prompt: &str, phrase: &str) -> PromptBuilder<bool> {
+        let hint = fill(&locale().phrase_hint, &[("phrase", &phrase)]);
+        let prompt = format!("{} {}", prompt, hint);
+        let phrase = phrase.to_string();
+        let mut builder = PromptBuilder::new(&prompt, Layout::Question(": "), move |input| {
+            Ok(input == phrase)
+        });
+        builder.keep_history = false;
+        builder
+    }
+}
+
+impl PromptBuilder<bool> {
+    /// Takes `words` for yes and no, in place of the ones set with [`set_yes_no`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let delete = Prompt::confirm("Delete everything? (yes/no)")
+    ///     .words(YesNo::new(&["yes"], &["no"]))
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn words(mut self, words: YesNo) -> Self {
+        self.parser = Box::new(move |input| words.parse(input).ok_or_else(|| words.message()));
+        self
+    }
+}
+
+impl PromptBuilder<Confirmation> {
+    /// Takes `words` for yes, no and cancel, in place of the ones set with
+    /// [`set_yes_no`].
+    pub fn words(mut self, words: YesNo) -> Self {
+        self.parser = Box::new(move |input| words.parse_cancel(input));
+        self
+    }
+}
+
+impl PromptBuilder<BatchAnswer> {
+    /// Takes `words` for yes, no, all and never, in place of the ones set with
+    /// [`set_yes_no`].
+    pub fn words(mut self, words: YesNo) -> Self {
+        self.parser = Box::new(move |input| words.parse_batch(input));
+        self
+    }
+
+    /// Remembers an all or never answer under `key` for the rest of the process, so
+    /// the questions asked under the same key after it return it without asking.
+    /// Call this last, once everything else is set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// for file in ["a.txt", "b.txt", "c.txt"] {
+    ///     let overwrite = Prompt::confirm_all(&format!("Overwrite {}? (y/n/a/v)", file))
+    ///         .remember("overwrite")
+    ///         .ask()?;
+    ///     if overwrite.is_yes() {
+    ///         // ...
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn remember(self, key: &str) -> Remembered {
+        Remembered {
+            builder: self,
+            key: key.to_string(),
+        }
+    }
+}
+
+/// A batch question that remembers an all or never answer, created with
+/// [`remember`](PromptBuilder::remember).
+pub struct Remembered {
+    builder: PromptBuilder<BatchAnswer>,
+    key: String,
+}
+
+impl Remembered {
+    /// Returns the all or never answered earlier under the same key, or shows the
+    /// prompt and reads answers until one is accepted.
+    pub fn ask(&self) -> Result<BatchAnswer, PromptError> {
+        if let Some(answer) = batches().get(&self.key) {
+            return Ok(*answer);
+        }
+        let answer = self.builder.ask()?;
+        if matches!(answer, BatchAnswer::All | BatchAnswer::Never) {
+            batches().insert(self.key.clone(), answer);
+        }
+        Ok(answer)
+    }
+}
+
+/// Prompts for yes, no, all or never given a prompt, color option, and the key
+/// the batch is asked under, for loops like overwriting a batch of files. Once
+/// all or never is answered, the calls with the same key return it without
+/// asking, until [`forget_batch_answer`] is called.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// for file in ["a.txt", "b.txt"] {
+///     let prompt = format!("Overwrite {}? (y/n/a/v) ", file);
+///     if prompt_tf_all(&prompt, None, "overwrite").is_yes() {
+///         // ...
+///     }
+/// }
+/// ```
+///
+/// Loops until the input is `y`, `n`, `a` or `all`, or `v` or `never`, or the
+/// words [`set_yes_no`] or the [locale](crate::set_locale) has for them. Like with
+/// [`prompt_tf_default`](crate::prompt_tf_default), the caller formats the prompt.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::confirm_all`] returns a [`PromptError`] instead.
+pub fn prompt_tf_all(prompt: &str, colored: Option<Choice>, key: &str) -> BatchAnswer {
+    let prompt = match colored {
+        Some(_) => prompt,
+        None => prompt.trim(),
+    };
+    PromptBuilder::new(prompt, Layout::Verbatim, |input| {
+        yes_no().parse_batch(input)
+    })
+    .colored(colored)
+    .remember(key)
+    .ask()
+    .expect("Failed to read line.")
+}
+
+/// How a countdown shown with [`count_down`] ended.
+pub(crate) enum Countdown<T> {
+    /// It reached zero, or Enter was pressed.
+    Done,
+    /// A key that's an answer on its own was pressed.
+    Answer(T),
+    /// Some other key was pressed.
+    Stopped,
+}
+
+/// Counts down from `seconds` after `prompt`, once a second, until it reaches zero
+/// or a key is pressed. A key `answer` takes is echoed after the prompt; the
+/// countdown is wiped if another key stops it.
+pub(crate) fn count_down<T>(
+    prompt: &str,
+    seconds: u64,
+    answer: impl Fn(char) -> Option<T>,
+) -> Result<Countdown<T>, PromptError> {
+    term::asking(|| {
+        let raw = RawMode::enable()?;
+        let start = Instant::now();
+        let mut shown = String::new();
+        let mut outcome = Countdown::Done;
+        let mut echo = String::new();
+        let gap = if prompt.ends_with(' ') { "" } else { " " };
+        for left in (1..=seconds).rev() {
+            shown.push_str(&format!("{}…", left));
+            term::output(|out| {
+                out.queue(ansi::MoveToColumn(0))?;
+                out.queue(ansi::Clear(ansi::ClearType::CurrentLine))?;
+                let shown = fill(&locale().countdown, &[("seconds", &shown)]);
+                write!(out, "{}{}{}", prompt, gap, shown)?;
+                out.flush()
+            })?;
+            let tick = start + Duration::from_secs(seconds - left + 1);
+            match term::read_key_before(Some(tick)) {
+                Err(PromptError::Timeout) => continue,
+                Err(err) => return Err(err),
+                Ok(Key::Enter) => break,
+                Ok(Key::Char(c)) => {
+                    outcome = match answer(c) {
+                        Some(value) => {
+                            echo.push(c);
+                            Countdown::Answer(value)
+                        }
+                        None => Countdown::Stopped,
+                    };
+                    break;
+                }
+                Ok(_) => {
+                    outcome = Countdown::Stopped;
+                    break;
+                }
+            }
+        }
+
+        term::output(|out| {
+            out.queue(ansi::MoveToColumn(0))?;
+            out.queue(ansi::Clear(ansi::ClearType::CurrentLine))?;
+            Ok(())
+        })?;
+        drop(raw);
+        if !matches!(outcome, Countdown::Stopped) {
+            outln!("{}{}{}", prompt, gap, echo)?;
+        }
+        Ok(outcome)
+    })
+}
+
+/// Prompts for a true/false value given a prompt, color option, default value, and
+/// how many seconds to count down from, and returns the default if nothing is
+/// pressed before it reaches zero, for batch tools that can carry on by themselves.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let resume = prompt_tf_countdown("Resume? (Y/n) ", None, true, 5);
+/// ```
+///
+/// While it counts down, the crate prints `(continuing in 5…4…)` after the prompt.
+/// Enter takes the default right away, `y` or `n` answers, and any other key stops
+/// the countdown and asks like [`prompt_tf_default`](crate::prompt_tf_default).
+/// When stdin or stderr isn't a terminal, or in [accessibility
+/// mode](crate::set_accessible), it asks without counting down.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`PromptBuilder::countdown`] returns a [`PromptError`] instead.
+pub fn prompt_tf_countdown(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: bool,
+    seconds: u64,
+) -> bool {
+    let prompt = match colored {
+        Some(_) => prompt,
+        None => prompt.trim(),
+    };
+    PromptBuilder::new(prompt, Layout::Verbatim, parse_tf)
+        .colored(colored)
+        .default(default)
+        .countdown(seconds)
+        .ask()
+        .expect("Failed to read line.")
+}
+
+/// Asks for `phrase` to be typed to go ahead with something that can't be undone,
+/// like the name of what's about to be deleted, and returns whether it was typed
+/// exactly. Anything else returns `false` without asking again.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if prompt_confirm_phrase("This deletes the production database.", "prod-db") {
+///     // ...
+/// }
+/// ```
+///
+/// The crate prints the prompt followed by ` (type {phrase} to confirm): `. The
+/// answer is compared after trimming the whitespace around it, and case matters.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::confirm_phrase`] returns a [`PromptError`] instead.
+pub fn prompt_confirm_phrase(prompt: &str, phrase: &str) -> bool {
+    Prompt::confirm_phrase(prompt, phrase)
+        .ask()
+        .expect("Failed to read line.")
+}
+
+/// Prompts for yes, no or cancel given a prompt, color option, and default value,
+/// so a destructive flow can tell "no" apart from "stop altogether". Loops until
+/// the input is `y`, `n` or `c`, printing `Please answer y, n or c.` after
+/// anything else.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// match prompt_tf_cancel("Save changes? (Y/n/c) ", None, Confirmation::Yes) {
+///     Confirmation::Yes => println!("Saving."),
+///     Confirmation::No => println!("Discarding."),
+///     Confirmation::Cancel => return,
+/// }
+/// ```
+///
+/// Like with [`prompt_tf_default`](crate::prompt_tf_default), the caller formats
+/// the prompt. Other words can be set with [`set_yes_no`] and [`YesNo::cancel`].
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::confirm_or_cancel`] returns a [`PromptError`]
+/// instead.
+pub fn prompt_tf_cancel(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Confirmation,
+) -> Confirmation {
+    let prompt = match colored {
+        Some(_) => prompt,
+        None => prompt.trim(),
+    };
+    PromptBuilder::new(prompt, Layout::Verbatim, |input| {
+        yes_no().parse_cancel(input)
+    })
+    .colored(colored)
+    .default(default)
+    .ask()
+    .expect("Failed to read line.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history;
+    use crate::test::MockTerminal;
+
+    #[test]
+    fn batches_take_all_and_never() {
+        let words = YesNo::default();
+        assert_eq!(words.parse_batch("y"), Ok(BatchAnswer::Yes));
+        assert_eq!(words.parse_batch(" N "), Ok(BatchAnswer::No));
+        assert_eq!(words.parse_batch("a"), Ok(BatchAnswer::All));
+        assert_eq!(words.parse_batch("ALL"), Ok(BatchAnswer::All));
+        assert_eq!(words.parse_batch("v"), Ok(BatchAnswer::Never));
+        assert_eq!(words.parse_batch("never"), Ok(BatchAnswer::Never));
+        assert!(words.parse_batch("x").is_err());
+    }
+
+    #[test]
+    fn batches_take_their_own_words() {
+        let words = YesNo::new(&["j"], &["n"]).batch(&["alle"], &["nie"]);
+        assert_eq!(words.parse_batch("Alle"), Ok(BatchAnswer::All));
+        assert_eq!(words.parse_batch("nie"), Ok(BatchAnswer::Never));
+        assert!(words.parse_batch("all").is_err());
+        let message = words.parse_batch("x").unwrap_err();
+        let expected = fill(
+            &locale().yes_no_all_never,
+            &[
+                ("yes", &"j"),
+                ("no", &"n"),
+                ("all", &"alle"),
+                ("never", &"nie"),
+            ],
+        );
+        assert_eq!(message, expected);
+    }
+
+    #[test]
+    fn remembers_all_and_never_under_their_key() {
+        let key = "remembered all";
+        let ask = || {
+            Prompt::confirm_all("Overwrite? (y/n/a/v)")
+                .remember(key)
+                .ask()
+        };
+        let mut terminal = MockTerminal::new().type_text("x\na\n");
+        assert_eq!(terminal.run(ask).unwrap(), BatchAnswer::All);
+        // It's answered without reading a key.
+        assert_eq!(MockTerminal::new().run(ask).unwrap(), BatchAnswer::All);
+        forget_batch_answer(key);
+        let mut terminal = MockTerminal::new().type_text("n\nv\n");
+        assert_eq!(terminal.run(ask).unwrap(), BatchAnswer::No);
+        assert_eq!(terminal.run(ask).unwrap(), BatchAnswer::Never);
+        assert_eq!(MockTerminal::new().run(ask).unwrap(), BatchAnswer::Never);
+        forget_batch_answer(key);
+    }
+
+    #[test]
+    fn batch_questions_take_the_words_given() {
+        let words = YesNo::new(&["yes"], &["no"]).batch(&["every"], &["none"]);
+        let mut terminal = MockTerminal::new().type_text("a\nevery\n");
+        let answer = terminal.run(|| Prompt::confirm_all("Overwrite?").words(words.clone()).ask());
+        assert_eq!(answer.unwrap(), BatchAnswer::All);
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn yes_and_no_ignore_case_and_space() {
+        let words = YesNo::new(&["O", "oui"], &["n", "non"]);
+        assert_eq!(words.parse(" OUI "), Some(true));
+        assert_eq!(words.parse("o"), Some(true));
+        assert_eq!(words.parse("Non"), Some(false));
+        assert_eq!(words.parse("y"), None);
+        assert_eq!(words.parse(""), None);
+        assert_eq!(words.word(true), "o");
+    }
+
+    #[test]
+    fn cancel_takes_its_own_words() {
+        let words = YesNo::default();
+        assert!(words.cancels(" C "));
+        assert!(!words.cancels("cancel"));
+        let words = words.cancel(&["Annuler"]);
+        assert!(words.cancels("annuler"));
+        assert!(!words.cancels("c"));
+        assert_eq!(words.parse_cancel("y"), Ok(Confirmation::Yes));
+        assert_eq!(words.parse_cancel("annuler"), Ok(Confirmation::Cancel));
+        assert!(words.parse_cancel("c").is_err());
+    }
+
+    #[test]
+    fn questions_take_the_words_given() {
+        let words = YesNo::new(&["yes"], &["no"]);
+        let mut terminal = MockTerminal::new().type_text("y\nyes\n");
+        let answer = terminal.run(|| {
+            Prompt::confirm("Delete? (yes/no)")
+                .words(words.clone())
+                .ask()
+        });
+        assert!(answer.unwrap());
+        assert!(terminal.output().contains(&words.message()));
+    }
+
+    #[test]
+    fn phrases_have_to_be_typed_exactly() {
+        let ask = || Prompt::confirm_phrase("Delete the bucket?", "prod-logs").ask();
+        let mut terminal = MockTerminal::new().type_text("prod-logs\n");
+        assert!(terminal.run(ask).unwrap());
+        let mut terminal = MockTerminal::new().type_text("Prod-Logs\nprod-logs\n");
+        assert!(!terminal.run(ask).unwrap());
+        // It isn't asked again.
+        assert_eq!(terminal.keys_left(), 10);
+    }
+
+    #[test]
+    fn phrases_are_shown_and_kept_out_of_the_history() {
+        let hint = fill(&locale().phrase_hint, &[("phrase", &"DROP")]);
+        let prompt = format!("Drop the table? {}", hint);
+        let mut terminal = MockTerminal::new().type_text("DROP\n");
+        let answer = terminal.run(|| Prompt::confirm_phrase("Drop the table?", "DROP").ask());
+        assert!(answer.unwrap());
+        assert_eq!(terminal.screen(), format!("{}: DROP", prompt));
+        assert!(history::entries(&prompt).is_empty());
+    }
+}
@@ -0,0 +1,107 @@
+//! Date and time prompts, behind the `chrono` feature.
+
+use crate::builder::Layout;
+use crate::locale::{fill, locale};
+use crate::{Choice, Prompt, PromptBuilder};
+use chrono::{NaiveDate, NaiveTime};
+use std::fmt::Write;
+
+/// The format dates are read in unless told otherwise: ISO-8601, like `2024-03-14`.
+pub const ISO_DATE: &str = "%Y-%m-%d";
+
+impl Prompt {
+    /// A calendar date in ISO-8601 format, like [`prompt_date`](crate::prompt_date).
+    pub fn date(prompt: &str) -> PromptBuilder<NaiveDate> {
+        Prompt::date_with_format(prompt, ISO_DATE)
+    }
+
+    /// A calendar date in the given [`chrono` format](chrono::format::strftime).
+    /// The error message shows an example date in that format.
+    pub fn date_with_format(prompt: &str, format: &str) -> PromptBuilder<NaiveDate> {
+        let format = format.to_string();
+        let example = example(&format);
+        PromptBuilder::new(prompt, Layout::Question(": "), move |input| {
+            NaiveDate::parse_from_str(input, &format)
+                .map_err(|_| fill(&locale().date, &[("example", &example)]))
+        })
+    }
+
+    /// A time of day as `HH:MM` or `HH:MM:SS`, on a 24-hour clock, like
+    /// [`prompt_time`](crate::prompt_time).
+    pub fn time(prompt: &str) -> PromptBuilder<NaiveTime> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            NaiveTime::parse_from_str(input, "%H:%M:%S")
+                .or_else(|_| NaiveTime::parse_from_str(input, "%H:%M"))
+                .map_err(|_| locale().time.clone())
+        })
+    }
+}
+
+/// Formats a sample date, or gives back the format itself if it can't be formatted.
+fn example(format: &str) -> String {
+    let sample = NaiveDate::from_ymd_opt(2024, 3, 14).expect("valid date");
+    let mut example = String::new();
+    match write!(example, "{}", sample.format(format)) {
+        Ok(()) => example,
+        Err(_) => format.to_string(),
+    }
+}
+
+/// Prompts for a date given a prompt, color option, optional default, and optional
+/// format. Loops until the input parses. The format defaults to [`ISO_DATE`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let start = prompt_date("Start date", None, None, None);
+/// let birthday = prompt_date("Birthday", None, None, Some("%d/%m/%Y"));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. Formats use
+/// `chrono`'s `strftime`-style syntax.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::date`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_date(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<NaiveDate>,
+    format: Option<&str>,
+) -> NaiveDate {
+    let mut builder = Prompt::date_with_format(prompt, format.unwrap_or(ISO_DATE)).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+/// Prompts for a time of day given a prompt, color option, and optional default.
+/// Accepts `HH:MM` or `HH:MM:SS` on a 24-hour clock, and loops until the input is
+/// a valid time.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # use chrono::NaiveTime;
+/// let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+/// let start = prompt_time("Start time", None, Some(nine));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. An answer like
+/// `25:00` or `9.30` prints `Please enter a time like 09:30 or 17:45:00.`
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::time`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_time(prompt: &str, colored: Option<Choice>, default: Option<NaiveTime>) -> NaiveTime {
+    let mut builder = Prompt::time(prompt).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
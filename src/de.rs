@@ -0,0 +1,448 @@
+//! Filling in anything serde can deserialize by asking for each of its fields,
+//! behind the `serde` feature.
+
+use crate::confirm;
+use crate::fields::{humanize, PromptField};
+use crate::locale::{fill, locale};
+use crate::{Prompt, PromptError};
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::forward_to_deserialize_any;
+use std::fmt::Display;
+use std::str::FromStr;
+
+impl Prompt {
+    /// Asks for a value of `T` one field at a time, like
+    /// [`prompt_deserialize`](crate::prompt_deserialize), returning a
+    /// [`PromptError`] if anything goes wrong.
+    pub fn deserialize<T: DeserializeOwned>() -> Result<T, PromptError> {
+        T::deserialize(Field { label: None })
+    }
+}
+
+impl de::Error for PromptError {
+    fn custom<T: Display>(message: T) -> Self {
+        PromptError::Validation(message.to_string())
+    }
+}
+
+/// Asks for whatever's deserialized from it under `label`, once serde says what
+/// it needs. Only the value as a whole has no label.
+struct Field {
+    label: Option<String>,
+}
+
+impl Field {
+    fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or("Value")
+    }
+
+    fn ask<T: PromptField>(&self) -> Result<T, PromptError> {
+        T::builder(self.label()).ask()
+    }
+}
+
+macro_rules! ask_for {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+            visitor.$visit(self.ask()?)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Field {
+    type Error = PromptError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        visitor.visit_string(Prompt::text(self.label()).ask()?)
+    }
+
+    ask_for! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+        deserialize_string => visit_string,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        visitor.visit_byte_buf(self.ask::<String>()?.into_bytes())
+    }
+
+    /// An optional field is asked about first, and only asked for if it's wanted.
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        let label = self.label().to_lowercase();
+        let set = fill(&locale().set_value, &[("label", &label)]);
+        let question = format!("{} {}", set, confirm::hint(Some(false)));
+        match Prompt::confirm(&question).default(false).ask()? {
+            true => visitor.visit_some(self),
+            false => visitor.visit_none(),
+        }
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// A list is typed on one line, like [`Prompt::list`].
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        let items = Prompt::list(self.label()).ask()?;
+        visitor.visit_seq(SeqDeserializer::new(items.into_iter().map(Text)))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        Err(de::Error::custom(format!(
+            "{} is a tuple, which can't be asked for",
+            self.label()
+        )))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        len: usize,
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        self.deserialize_tuple(len, visitor)
+    }
+
+    /// A map is asked for an entry at a time, like [`Prompt::pairs`].
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        let entries = Prompt::pairs(self.label()).ask()?;
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| (Text(key), Text(value)));
+        visitor.visit_map(MapDeserializer::new(entries))
+    }
+
+    /// A struct is asked for a field at a time. The fields of a struct inside
+    /// another are labelled with the outer field's name first, like `Database host`.
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        visitor.visit_map(Fields {
+            prefix: self.label,
+            fields: fields.iter(),
+            current: None,
+        })
+    }
+
+    /// An enum is picked from a menu of its variants, which can't carry data.
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        let picked = Prompt::menu(self.label(), variants).ask()?;
+        visitor.visit_enum(variants[picked].into_deserializer())
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        self.deserialize_string(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        visitor.visit_unit()
+    }
+}
+
+/// The fields of a struct, each asked for as its value is wanted.
+struct Fields {
+    prefix: Option<String>,
+    fields: std::slice::Iter<'static, &'static str>,
+    current: Option<&'static str>,
+}
+
+impl<'de> MapAccess<'de> for Fields {
+    type Error = PromptError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, PromptError> {
+        self.current = self.fields.next().copied();
+        self.current
+            .map(|name| seed.deserialize(name.into_deserializer()))
+            .transpose()
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, PromptError> {
+        let name = self.current.take().expect("a value follows its key");
+        let label = match &self.prefix {
+            Some(prefix) => format!("{} {}", prefix, humanize(name).to_lowercase()),
+            None => humanize(name),
+        };
+        seed.deserialize(Field { label: Some(label) })
+    }
+}
+
+/// One value of a list or map that's already been typed, parsed into whatever's
+/// deserialized from it.
+struct Text(String);
+
+impl Text {
+    fn parse<T: FromStr>(&self, what: &str) -> Result<T, PromptError> {
+        self.0
+            .parse()
+            .map_err(|_| de::Error::custom(format!("{:?} isn't {}", self.0, what)))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, PromptError> for Text {
+    type Deserializer = Text;
+
+    fn into_deserializer(self) -> Text {
+        self
+    }
+}
+
+macro_rules! parse_as {
+    ($($method:ident => $visit:ident, $what:literal,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+            visitor.$visit(self.parse($what)?)
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for Text {
+    type Error = PromptError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        visitor.visit_string(self.0)
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        match confirm::yes_no().parse(&self.0) {
+            Some(answer) => visitor.visit_bool(answer),
+            None => visitor.visit_bool(self.parse("a yes or no")?),
+        }
+    }
+
+    parse_as! {
+        deserialize_i8 => visit_i8, "a whole number",
+        deserialize_i16 => visit_i16, "a whole number",
+        deserialize_i32 => visit_i32, "a whole number",
+        deserialize_i64 => visit_i64, "a whole number",
+        deserialize_i128 => visit_i128, "a whole number",
+        deserialize_u8 => visit_u8, "a whole number",
+        deserialize_u16 => visit_u16, "a whole number",
+        deserialize_u32 => visit_u32, "a whole number",
+        deserialize_u64 => visit_u64, "a whole number",
+        deserialize_u128 => visit_u128, "a whole number",
+        deserialize_f32 => visit_f32, "a number",
+        deserialize_f64 => visit_f64, "a number",
+        deserialize_char => visit_char, "a single character",
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PromptError> {
+        match self.0.is_empty() {
+            true => visitor.visit_none(),
+            false => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PromptError> {
+        visitor.visit_enum(self.0.into_deserializer())
+    }
+
+    forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Prompts for a value of `T`, asking for each field of a struct in the order
+/// they're declared, so a configuration can be filled in from whatever serde can
+/// deserialize. It's behind the `serde` feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// enum Level {
+///     Debug,
+///     Info,
+///     Warn,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Database {
+///     host: String,
+///     port: u16,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Config {
+///     name: String,
+///     log_level: Level,
+///     database: Database,
+///     tags: Vec<String>,
+///     nickname: Option<String>,
+/// }
+///
+/// let config: Config = prompt_deserialize();
+/// ```
+///
+/// Each field is asked for under its name, like `Log level: ` for `log_level`,
+/// and the fields of a struct inside another under both names, like
+/// `Database host: `. Numbers are asked for until one parses, an enum is picked
+/// from a menu of its variants like in [`prompt_menu`](crate::prompt_menu), a list
+/// is typed on one line like in [`prompt_list`](crate::prompt_list), and a map is
+/// typed an entry at a time like in [`prompt_pairs`](crate::prompt_pairs). An
+/// optional field is only asked for if `Set {name}? (y/N)` is answered yes.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if `T` has a
+/// part that can't be asked for, like a tuple, an enum variant with data in it, or
+/// a list item that doesn't parse. [`Prompt::deserialize`] returns a
+/// [`PromptError`] instead.
+pub fn prompt_deserialize<T: DeserializeOwned>() -> T {
+    Prompt::deserialize().expect("Failed to read value.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{Key, MockTerminal};
+    use serde::Deserialize;
+    use std::collections::BTreeMap;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    enum Level {
+        Debug,
+        Info,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Database {
+        host: String,
+        port: u16,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Config {
+        log_level: Level,
+        database: Database,
+        ports: Vec<u16>,
+        flags: Vec<bool>,
+        limits: BTreeMap<String, u32>,
+        nickname: Option<String>,
+    }
+
+    #[test]
+    fn asks_for_each_field() {
+        let mut terminal = MockTerminal::new()
+            .press(Key::Down)
+            .press(Key::Enter)
+            .type_text("db\nhigh\n5432\n80, 443\ny, false\ncpu=2\nmem=512\n\ny\nada\n");
+        let config: Config = terminal.run(Prompt::deserialize).unwrap();
+        let limits = BTreeMap::from([("cpu".to_string(), 2), ("mem".to_string(), 512)]);
+        assert_eq!(
+            config,
+            Config {
+                log_level: Level::Info,
+                database: Database {
+                    host: "db".to_string(),
+                    port: 5432,
+                },
+                ports: vec![80, 443],
+                flags: vec![true, false],
+                limits,
+                nickname: Some("ada".to_string()),
+            }
+        );
+        let screen = terminal.screen();
+        assert!(screen.starts_with("Log level: Info\n"), "{}", screen);
+        assert!(screen.contains("Database host: db\n"), "{}", screen);
+        assert!(screen.contains("Database port: 5432\n"), "{}", screen);
+        assert!(screen.contains("Set nickname? (y/N)"), "{}", screen);
+    }
+
+    #[test]
+    fn leaves_optional_fields_out_unless_wanted() {
+        let mut terminal = MockTerminal::new().type_text("\n");
+        let nickname: Option<String> = terminal.run(Prompt::deserialize).unwrap();
+        assert_eq!(nickname, None);
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn rejects_list_items_that_dont_parse() {
+        let mut terminal = MockTerminal::new().type_text("80, http\n");
+        let ports = terminal.run(Prompt::deserialize::<Vec<u16>>);
+        match ports {
+            Err(PromptError::Validation(message)) => {
+                assert_eq!(message, "\"http\" isn't a whole number")
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_what_it_cant_ask_for() {
+        let answer = Prompt::deserialize::<(u8, u8)>();
+        assert!(matches!(answer, Err(PromptError::Validation(_))));
+    }
+}
@@ -0,0 +1,161 @@
+//! Defaults for prompts that come from outside the program, like environment
+//! variables or a config file.
+
+#[cfg(feature = "toml")]
+use std::collections::BTreeMap;
+#[cfg(feature = "toml")]
+use std::io;
+#[cfg(feature = "toml")]
+use std::path::Path;
+use std::sync::{PoisonError, RwLock};
+
+/// Somewhere a prompt's default can come from, looked up by the prompt's key.
+///
+/// The key is the one given with [`PromptBuilder::key`](crate::PromptBuilder::key)
+/// or the menus' `key`, or else the prompt's text. It's implemented for
+/// [`EnvDefaults`], [`FileDefaults`], [`Defaults`] and any
+/// `Fn(&str) -> Option<String>`.
+pub trait DefaultsProvider {
+    /// The default for the prompt with `key`, as it would be typed, if there's one
+    /// here.
+    fn default_for(&self, key: &str) -> Option<String>;
+}
+
+impl<F: Fn(&str) -> Option<String>> DefaultsProvider for F {
+    fn default_for(&self, key: &str) -> Option<String> {
+        self(key)
+    }
+}
+
+/// Defaults from environment variables named after the key under a prefix, so
+/// `database.host` with the prefix `MYAPP` is read from `MYAPP_DATABASE_HOST`.
+/// Anything in the key that isn't a letter or a digit becomes `_`, and empty
+/// variables are passed over.
+pub struct EnvDefaults {
+    prefix: String,
+}
+
+impl EnvDefaults {
+    /// Reads the variables starting with `prefix` and `_`.
+    pub fn new(prefix: &str) -> Self {
+        EnvDefaults {
+            prefix: prefix.to_string(),
+        }
+    }
+
+    /// The variable the default for `key` is read from.
+    fn var(&self, key: &str) -> String {
+        let name: String = key
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c.to_ascii_uppercase(),
+                false => '_',
+            })
+            .collect();
+        match self.prefix.is_empty() {
+            true => name,
+            false => format!("{}_{}", self.prefix, name),
+        }
+    }
+}
+
+impl DefaultsProvider for EnvDefaults {
+    fn default_for(&self, key: &str) -> Option<String> {
+        std::env::var(self.var(key))
+            .ok()
+            .filter(|value| !value.is_empty())
+    }
+}
+
+/// Defaults from a TOML config file, behind the `toml` feature. Keys in tables
+/// are joined with dots, and values are read the same way as by
+/// [`load_answers`](crate::load_answers).
+#[cfg(feature = "toml")]
+pub struct FileDefaults {
+    values: BTreeMap<String, String>,
+}
+
+#[cfg(feature = "toml")]
+impl FileDefaults {
+    /// Reads the defaults in the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(FileDefaults {
+            values: crate::session::read_toml(path)?,
+        })
+    }
+}
+
+#[cfg(feature = "toml")]
+impl DefaultsProvider for FileDefaults {
+    fn default_for(&self, key: &str) -> Option<String> {
+        self.values.get(key).cloned()
+    }
+}
+
+/// Several [`DefaultsProvider`]s tried in the order they were added, with the
+/// default coming from the first one that has a value.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// set_defaults(
+///     Defaults::new()
+///         .then(EnvDefaults::new("MYAPP"))
+///         .then(|key: &str| (key == "user").then(whoami)),
+/// );
+/// let user = Prompt::text("User").key("user").ask()?;
+/// # Ok(())
+/// # }
+/// # fn whoami() -> String { String::new() }
+/// ```
+///
+/// `User` is shown with `MYAPP_USER` as its default if that's set, or else the
+/// current user. With the `toml` feature, a [`FileDefaults`] can go in between,
+/// for a config file that's overridden by the environment.
+#[derive(Default)]
+pub struct Defaults {
+    providers: Vec<Box<dyn DefaultsProvider + Send + Sync>>,
+}
+
+impl Defaults {
+    /// A chain with nothing in it yet.
+    pub fn new() -> Self {
+        Defaults::default()
+    }
+
+    /// Adds `provider`, to be tried after the ones added before it.
+    pub fn then(mut self, provider: impl DefaultsProvider + Send + Sync + 'static) -> Self {
+        self.providers.push(Box::new(provider));
+        self
+    }
+}
+
+impl DefaultsProvider for Defaults {
+    fn default_for(&self, key: &str) -> Option<String> {
+        self.providers
+            .iter()
+            .find_map(|provider| provider.default_for(key))
+    }
+}
+
+static DEFAULTS: RwLock<Option<Defaults>> = RwLock::new(None);
+
+/// Sets where every prompt from here on, on every thread, looks for its default
+/// first. The default set on the prompt itself is only used when none of
+/// `defaults` has one, and a default the prompt wouldn't accept as an answer is
+/// passed over. It applies to the [`PromptBuilder`](crate::PromptBuilder)s and to
+/// [`Menu`](crate::Menu), whose default is the item with the text given.
+pub fn set_defaults(defaults: Defaults) {
+    *DEFAULTS.write().unwrap_or_else(PoisonError::into_inner) = Some(defaults);
+}
+
+/// The default from the providers set with [`set_defaults`] for the prompt with
+/// `key`, or `prompt` if it has none.
+pub(crate) fn provided(prompt: &str, key: Option<&String>) -> Option<String> {
+    let defaults = DEFAULTS.read().unwrap_or_else(PoisonError::into_inner);
+    defaults
+        .as_ref()?
+        .default_for(key.map_or(prompt, String::as_str))
+}
@@ -0,0 +1,121 @@
+//! Human-friendly duration prompts, like `90s` or `1h30m`.
+
+use crate::builder::Layout;
+use crate::locale::locale;
+use crate::{Choice, Prompt, PromptBuilder};
+use std::time::Duration;
+
+impl Prompt {
+    /// A duration like `90s`, `1h30m` or `2d`, like
+    /// [`prompt_duration`](crate::prompt_duration).
+    pub fn duration(prompt: &str) -> PromptBuilder<Duration> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            parse_duration(input).ok_or_else(|| locale().duration.clone())
+        })
+    }
+}
+
+/// Parses a run of whole numbers with units, like `1h 30m`. The units are `ms`, `s`,
+/// `m`, `h`, `d` and `w`; a lone number counts as seconds.
+pub(crate) fn parse_duration(input: &str) -> Option<Duration> {
+    let input: String = input.chars().filter(|c| !c.is_whitespace()).collect();
+    if input.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = input.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = input.as_str();
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+        let unit_len = rest[digits..]
+            .find(|c: char| c.is_ascii_digit())
+            .unwrap_or(rest.len() - digits);
+        let amount: u64 = rest[..digits].parse().ok()?;
+        let unit = match &rest[digits..digits + unit_len] {
+            "ms" => Duration::from_millis(1),
+            "s" => Duration::from_secs(1),
+            "m" => Duration::from_secs(60),
+            "h" => Duration::from_secs(60 * 60),
+            "d" => Duration::from_secs(60 * 60 * 24),
+            "w" => Duration::from_secs(60 * 60 * 24 * 7),
+            _ => return None,
+        };
+        total = total.checked_add(unit.checked_mul(u32::try_from(amount).ok()?)?)?;
+        rest = &rest[digits + unit_len..];
+    }
+
+    Some(total)
+}
+
+/// Prompts for a duration given a prompt, color option, and optional default.
+/// Loops until the input is a run of whole numbers with units, like `90s`, `1h30m`
+/// or `2d 12h`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # use std::time::Duration;
+/// let timeout = prompt_duration("Timeout", None, Some(Duration::from_secs(30)));
+/// ```
+///
+/// The units are `ms`, `s`, `m`, `h`, `d` and `w`, and a lone number counts as
+/// seconds. The crate prints the prompt followed by a colon and a space.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::duration`] returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_duration(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<Duration>,
+) -> Duration {
+    let mut builder = Prompt::duration(prompt).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lone_numbers_are_seconds() {
+        assert_eq!(parse_duration("90"), Some(Duration::from_secs(90)));
+        assert_eq!(parse_duration(" 0 "), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn units_add_up() {
+        assert_eq!(parse_duration("1h 30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2d12h"), Some(Duration::from_secs(216_000)));
+        assert_eq!(parse_duration("1w"), Some(Duration::from_secs(604_800)));
+        assert_eq!(
+            parse_duration("1s 500ms"),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(parse_duration("10 m"), Some(Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn rejects_what_isnt_a_duration() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("h"), None);
+        assert_eq!(parse_duration("5x"), None);
+        assert_eq!(parse_duration("1h30"), None);
+        assert_eq!(parse_duration("-5s"), None);
+        assert_eq!(parse_duration("1.5h"), None);
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert_eq!(parse_duration("99999999999h"), None);
+        assert_eq!(parse_duration(&"4294967295w ".repeat(10_000)), None);
+    }
+}
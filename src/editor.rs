@@ -0,0 +1,754 @@
+//! A raw-mode line reader, for text prompts that need more than canonical-mode editing.
+
+use crate::ansi::{self, Queue};
+use crate::term::{self, outln, Buffer, Key, RawMode};
+use crate::theme::{self, Style};
+use crate::{print_error, read_line_before, PromptError};
+use std::fs;
+use std::path::{self, Path};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Suggests whole-line completions for what's been typed so far.
+pub(crate) type Completer = Box<dyn Fn(&str) -> Vec<String>>;
+
+/// Which keys edit the answer at a text prompt, set with [`set_edit_mode`] or
+/// [`PromptBuilder::edit_mode`](crate::PromptBuilder::edit_mode).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EditMode {
+    /// The keys of a shell's command line: the arrow keys, Home and End, and
+    /// Ctrl+A, Ctrl+E, Ctrl+W, Ctrl+U and Ctrl+K.
+    #[default]
+    Emacs,
+    /// Like vi: typing starts in insert mode, where the Emacs keys work too, and
+    /// Esc switches to normal mode. There `h` and `l` move the cursor, `w`, `b` and
+    /// `e` move by words, `0` and `$` jump to either end, `k` and `j` step through
+    /// the history, and `x`, `X`, `D`, `dd` and `dw` delete. `i`, `a`, `I`, `A`, `C`,
+    /// `S` and `cw` go back to inserting. Esc in normal mode skips a prompt that can
+    /// be skipped, or goes back a step in a wizard.
+    Vi,
+}
+
+static EDIT_MODE: Mutex<EditMode> = Mutex::new(EditMode::Emacs);
+
+/// Sets which keys edit the answers at text prompts from here on, on every thread,
+/// for prompts that don't set their own.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if std::env::var("MYAPP_VI").is_ok() {
+///     set_edit_mode(EditMode::Vi);
+/// }
+/// let name = prompt_text("Enter your", "name", None);
+/// ```
+pub fn set_edit_mode(mode: EditMode) {
+    *EDIT_MODE.lock().unwrap_or_else(PoisonError::into_inner) = mode;
+}
+
+/// The mode set with [`set_edit_mode`], or Emacs.
+pub(crate) fn edit_mode() -> EditMode {
+    *EDIT_MODE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// What a line is read with besides its prompt.
+#[derive(Default)]
+pub(crate) struct Extras<'a> {
+    /// Completes the line on Tab, and hints the rest of the first completion.
+    pub(crate) completer: Option<&'a Completer>,
+    /// The answers Up and Down step through, oldest first.
+    pub(crate) history: &'a [String],
+    /// Help shown dimmed under the line in a terminal, and wiped once it's
+    /// answered. Where lines aren't read in raw mode, it's printed on its own line
+    /// before the prompt.
+    pub(crate) below: Option<&'a str>,
+    /// Text shown dimmed in place of the line in a terminal until something's typed.
+    pub(crate) placeholder: Option<&'a str>,
+    /// The text the line starts out with in a terminal, like the earlier answer to
+    /// a wizard step that's being gone back to.
+    pub(crate) prefill: Option<&'a str>,
+}
+
+/// Prints `prompt` and reads a line, with the editing keys of a shell: Left and
+/// Right, Home and End, Ctrl+W to delete a word, Ctrl+U and Ctrl+K to delete to
+/// either end, and their Emacs-style equivalents. Tab completes it with the
+/// `extras`' completer. As it's typed, the rest of the first completion is hinted
+/// after the cursor, and Right at the end takes it. Up and Down step through its
+/// history. If `escape` is set, Esc gives up on the line and returns
+/// [`ESCAPE`](term::ESCAPE). In vi `mode` the keys are those of [`EditMode::Vi`]
+/// instead. The answer is trimmed, like [`read_input`](crate::read_input).
+pub(crate) fn read_line(
+    prompt: &str,
+    extras: &Extras,
+    escape: bool,
+    mode: EditMode,
+    deadline: Option<Instant>,
+    error: Option<&str>,
+) -> Result<String, PromptError> {
+    edit_line(prompt, extras, escape, mode, deadline, error).map(|line| line.trim().to_string())
+}
+
+/// Prints `prompt` and reads lines until a lone `.` or the end of input, which is
+/// Ctrl+D on an empty line in a terminal. Returns them joined with newlines, each
+/// as typed, indentation and all. `error` is printed above the prompt.
+pub(crate) fn read_lines(
+    prompt: &str,
+    mode: EditMode,
+    deadline: Option<Instant>,
+    error: Option<&str>,
+) -> Result<String, PromptError> {
+    if let Some(error) = error.filter(|error| !error.is_empty()) {
+        print_error(error)?;
+    }
+    term::outln!("{}", prompt)?;
+    let mut lines = Vec::new();
+    loop {
+        let line = match edit_line("", &Extras::default(), false, mode, deadline, None) {
+            Err(PromptError::Eof) => break,
+            line => line?,
+        };
+        if line.trim() == "." {
+            break;
+        }
+        lines.push(line);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Like [`read_line`], but the line is returned untrimmed. When stdin or stderr
+/// isn't a terminal, or in accessibility mode, it's read with [`read_line_before`]
+/// instead.
+///
+/// `error` is why the answer on the line above was rejected. In a terminal that
+/// answer is wiped and the prompt drawn again in its place, with `error` under it
+/// until Enter is pressed. Otherwise it's printed before the prompt.
+fn edit_line(
+    prompt: &str,
+    extras: &Extras,
+    escape: bool,
+    mode: EditMode,
+    deadline: Option<Instant>,
+    error: Option<&str>,
+) -> Result<String, PromptError> {
+    let error = error.filter(|error| !error.is_empty());
+    let Extras {
+        completer,
+        history,
+        below,
+        placeholder,
+        prefill,
+    } = *extras;
+    if !term::redraws() {
+        print_above(error, below)?;
+        term::out!("{}", prompt)?;
+        return read_line_before(deadline);
+    }
+
+    let raw = RawMode::enable()?;
+    let under = draw_under(error, below)?;
+    let mut line = Line::new(prefill.unwrap_or_default());
+    // Which history entry is showing, and what was typed before going through them.
+    let mut recalled = history.len();
+    let mut draft = String::new();
+    // In vi mode, whether it's in normal mode, and the `d` or `c` waiting for a motion.
+    let mut normal = false;
+    let mut pending = None;
+    loop {
+        let hint = match completer {
+            Some(completer) if line.at_end() && !normal => hint(&line.text, completer),
+            _ => String::new(),
+        };
+        match placeholder {
+            Some(placeholder) if line.text.is_empty() => redraw(prompt, &line, placeholder)?,
+            _ => redraw(prompt, &line, &hint)?,
+        }
+        let mut key = term::read_key_before(deadline)?;
+        if mode == EditMode::Vi {
+            match (normal, key) {
+                (false, Key::Esc) => {
+                    normal = true;
+                    line.left();
+                    continue;
+                }
+                (true, Key::Char(c)) => match vi_normal(&mut line, c, &mut pending) {
+                    Vi::Key(pressed) => key = pressed,
+                    Vi::Insert => {
+                        normal = false;
+                        continue;
+                    }
+                    Vi::Done => continue,
+                },
+                _ => pending = None,
+            }
+        }
+        match key {
+            Key::Enter => {
+                if !hint.is_empty() || placeholder.is_some() {
+                    redraw(prompt, &line, "")?;
+                }
+                break;
+            }
+            Key::Right | Key::Ctrl('f') if line.at_end() => line.insert_str(&hint),
+            Key::Right | Key::Ctrl('f') => line.right(),
+            Key::Left | Key::Ctrl('b') => line.left(),
+            Key::Home | Key::Ctrl('a') => line.cursor = 0,
+            Key::End | Key::Ctrl('e') => line.cursor = line.text.len(),
+            Key::Up if recalled > 0 => {
+                if recalled == history.len() {
+                    draft = line.text.clone();
+                }
+                recalled -= 1;
+                line = Line::new(&history[recalled]);
+            }
+            Key::Down if recalled < history.len() => {
+                recalled += 1;
+                line = match history.get(recalled) {
+                    Some(entry) => Line::new(entry),
+                    None => Line::new(&std::mem::take(&mut draft)),
+                };
+            }
+            Key::Esc if escape => {
+                line = Line::new(term::ESCAPE);
+                break;
+            }
+            Key::Ctrl('d') if line.text.is_empty() => return Err(PromptError::Eof),
+            Key::Delete | Key::Ctrl('d') => line.delete(),
+            Key::Backspace | Key::Ctrl('h') => line.backspace(),
+            Key::Ctrl('u') => line.delete_to_start(),
+            Key::Ctrl('k') => line.delete_to_end(),
+            Key::Ctrl('w') => line.delete_word(),
+            Key::Tab => {
+                if let Some(completer) = completer {
+                    complete(prompt, &mut line, completer)?;
+                }
+            }
+            Key::Char(c) => line.insert(c),
+            _ => {}
+        }
+    }
+
+    drop(raw);
+    outln!()?;
+    if under {
+        clear_under()?;
+    }
+    Ok(line.text)
+}
+
+/// Prints `prompt` and reads a secret with [`read_hidden`](term::read_hidden), with
+/// `error` and the help `below` shown the way [`edit_line`] shows them.
+pub(crate) fn read_secret(
+    prompt: &str,
+    below: Option<&str>,
+    escape: bool,
+    deadline: Option<Instant>,
+    error: Option<&str>,
+) -> Result<Buffer, PromptError> {
+    let error = error.filter(|error| !error.is_empty());
+    let under = match term::redraws() {
+        true => draw_under(error, below)?,
+        false => {
+            print_above(error, below)?;
+            false
+        }
+    };
+    term::out!("{}", prompt)?;
+    let input = term::read_hidden(escape, deadline);
+    if under {
+        clear_under()?;
+    }
+    input
+}
+
+/// Prints `error`, then `below` dimmed, on lines of their own, for a prompt that
+/// isn't redrawn.
+fn print_above(error: Option<&str>, below: Option<&str>) -> Result<(), PromptError> {
+    if let Some(error) = error {
+        print_error(error)?;
+    }
+    if let Some(below) = below {
+        outln!("{}", Style::new().dimmed().paint(below))?;
+    }
+    Ok(())
+}
+
+/// Wipes the rejected answer on the line above if there's an `error`, and shows
+/// `error`, or else `below` dimmed, on the line under the one the prompt's about to
+/// be drawn on. Returns whether anything's there.
+fn draw_under(error: Option<&str>, below: Option<&str>) -> Result<bool, PromptError> {
+    let under = match error {
+        Some(error) => Some(theme::theme().error_style.paint(error)),
+        None => below.map(|below| Style::new().dimmed().paint(below)),
+    };
+    term::output(|out| {
+        if error.is_some() {
+            out.queue(ansi::MoveUp(1))?;
+            out.queue(ansi::MoveToColumn(0))?;
+            out.queue(ansi::Clear(ansi::ClearType::FromCursorDown))?;
+        }
+        if let Some(under) = &under {
+            write!(out, "\r\n{}", under)?;
+            out.queue(ansi::MoveUp(1))?;
+            out.queue(ansi::MoveToColumn(0))?;
+        }
+        out.flush()
+    })?;
+    Ok(under.is_some())
+}
+
+/// Wipes what [`draw_under`] showed, once the answer's been read and the cursor
+/// has moved onto that line.
+fn clear_under() -> Result<(), PromptError> {
+    term::output(|out| {
+        out.queue(ansi::Clear(ansi::ClearType::FromCursorDown))?;
+        out.flush()
+    })?;
+    Ok(())
+}
+
+/// The line being edited, and where in it the cursor is, as a byte offset.
+#[derive(Default)]
+struct Line {
+    text: String,
+    cursor: usize,
+}
+
+impl Line {
+    /// `text`, with the cursor at the end.
+    fn new(text: &str) -> Self {
+        Line {
+            text: text.to_string(),
+            cursor: text.len(),
+        }
+    }
+
+    fn at_end(&self) -> bool {
+        self.cursor == self.text.len()
+    }
+
+    /// Where the character before the cursor starts, taking an emoji or a letter
+    /// with its accents as one.
+    fn previous(&self) -> usize {
+        self.text[..self.cursor]
+            .grapheme_indices(true)
+            .next_back()
+            .map_or(0, |(i, _)| i)
+    }
+
+    /// Where the character after the cursor ends.
+    fn next(&self) -> usize {
+        self.text[self.cursor..]
+            .graphemes(true)
+            .next()
+            .map_or(self.cursor, |grapheme| self.cursor + grapheme.len())
+    }
+
+    fn left(&mut self) {
+        self.cursor = self.previous();
+    }
+
+    fn right(&mut self) {
+        self.cursor = self.next();
+    }
+
+    fn insert(&mut self, c: char) {
+        self.text.insert(self.cursor, c);
+        self.cursor += c.len_utf8();
+    }
+
+    fn insert_str(&mut self, text: &str) {
+        self.text.insert_str(self.cursor, text);
+        self.cursor += text.len();
+    }
+
+    fn backspace(&mut self) {
+        let start = self.previous();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    fn delete(&mut self) {
+        let end = self.next();
+        self.text.replace_range(self.cursor..end, "");
+    }
+
+    fn delete_to_start(&mut self) {
+        self.text.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    fn delete_to_end(&mut self) {
+        self.text.truncate(self.cursor);
+    }
+
+    /// Moves to the start of the next word.
+    fn word_forward(&mut self) {
+        let rest = &self.text[self.cursor..];
+        let word = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        let space = rest[word..]
+            .find(|c: char| !c.is_whitespace())
+            .unwrap_or(rest.len() - word);
+        self.cursor += word + space;
+    }
+
+    /// Moves to the start of this word, or the one before if it's already there.
+    fn word_back(&mut self) {
+        let before = self.text[..self.cursor].trim_end();
+        self.cursor = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+    }
+
+    /// Moves to the last character of this word, or the next one if it's already
+    /// there.
+    fn word_end(&mut self) {
+        self.right();
+        let rest = &self.text[self.cursor..];
+        let space = rest.len() - rest.trim_start().len();
+        let word = rest[space..]
+            .find(char::is_whitespace)
+            .unwrap_or(rest.len() - space);
+        self.cursor += space + word;
+        self.left();
+    }
+
+    /// Deletes from the cursor to the start of the next word.
+    fn delete_word_forward(&mut self) {
+        let start = self.cursor;
+        self.word_forward();
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Deletes the word before the cursor, and the spaces after it, like Ctrl+W
+    /// in a shell.
+    fn delete_word(&mut self) {
+        let before = self.text[..self.cursor].trim_end();
+        let start = before.rfind(char::is_whitespace).map_or(0, |i| i + 1);
+        self.text.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+}
+
+/// What a vi normal-mode command comes to.
+enum Vi {
+    /// It's been carried out.
+    Done,
+    /// It's this key, which the Emacs mode handles too.
+    Key(Key),
+    /// It's been carried out, and typing goes back to inserting.
+    Insert,
+}
+
+/// Runs the vi normal-mode command `c` on `line`, with `pending` holding a `d` or
+/// `c` typed before it.
+fn vi_normal(line: &mut Line, c: char, pending: &mut Option<char>) -> Vi {
+    if let Some(operator) = pending.take() {
+        match (operator, c) {
+            ('d', 'd') => *line = Line::default(),
+            ('c', 'c') => {
+                *line = Line::default();
+                return Vi::Insert;
+            }
+            ('d', 'w') => line.delete_word_forward(),
+            ('c', 'w') => {
+                line.delete_word_forward();
+                return Vi::Insert;
+            }
+            _ => {}
+        }
+        return Vi::Done;
+    }
+    match c {
+        'h' => line.left(),
+        'l' => line.right(),
+        'w' => line.word_forward(),
+        'b' => line.word_back(),
+        'e' => line.word_end(),
+        '0' | '^' => line.cursor = 0,
+        '$' => {
+            line.cursor = line.text.len();
+            line.left();
+        }
+        'x' => return Vi::Key(Key::Delete),
+        'X' => return Vi::Key(Key::Backspace),
+        'D' => line.delete_to_end(),
+        'k' => return Vi::Key(Key::Up),
+        'j' => return Vi::Key(Key::Down),
+        'd' | 'c' => *pending = Some(c),
+        'i' => return Vi::Insert,
+        'a' => {
+            line.right();
+            return Vi::Insert;
+        }
+        'I' => {
+            line.cursor = 0;
+            return Vi::Insert;
+        }
+        'A' => {
+            line.cursor = line.text.len();
+            return Vi::Insert;
+        }
+        'C' => {
+            line.delete_to_end();
+            return Vi::Insert;
+        }
+        'S' => {
+            *line = Line::default();
+            return Vi::Insert;
+        }
+        _ => {}
+    }
+    Vi::Done
+}
+
+/// Redraws the prompt line, with `hint` dimmed after the cursor.
+fn redraw(prompt: &str, line: &Line, hint: &str) -> Result<(), PromptError> {
+    let text = theme::theme().answer_style.paint(&line.text);
+    let back = term::width(&line.text[line.cursor..]) + term::width(hint);
+    let hint = Style::new().dimmed().paint(hint);
+    #[cfg(feature = "bidi")]
+    if let Some(visual) = crate::bidi::Visual::of(&format!("{}{}{}", prompt, text, hint)) {
+        let codes = match text.starts_with('\x1b') {
+            true => term::code_len(&text),
+            false => 0,
+        };
+        let (drawn, column) = (
+            visual.drawn(),
+            visual.column(prompt.len() + codes + line.cursor),
+        );
+        term::output(|out| {
+            out.queue(ansi::MoveToColumn(0))?;
+            out.queue(ansi::Clear(ansi::ClearType::CurrentLine))?;
+            write!(out, "{}", drawn)?;
+            out.queue(ansi::MoveToColumn(column as u16))?;
+            out.flush()
+        })?;
+        return Ok(());
+    }
+    term::output(|out| {
+        out.queue(ansi::MoveToColumn(0))?;
+        out.queue(ansi::Clear(ansi::ClearType::CurrentLine))?;
+        write!(out, "{}{}{}", prompt, text, hint)?;
+        if back > 0 {
+            out.queue(ansi::MoveLeft(back as u16))?;
+        }
+        out.flush()
+    })?;
+    Ok(())
+}
+
+/// The rest of the first completion that carries on from `line`, if anything's
+/// been typed.
+fn hint(line: &str, completer: &Completer) -> String {
+    if line.is_empty() {
+        return String::new();
+    }
+    completer(line)
+        .into_iter()
+        .find_map(|candidate| Some(candidate.strip_prefix(line)?.to_string()))
+        .unwrap_or_default()
+}
+
+/// Completes `line` as far as all the candidates agree. If that doesn't add
+/// anything and there's more than one, they're listed under the prompt.
+fn complete(prompt: &str, line: &mut Line, completer: &Completer) -> Result<(), PromptError> {
+    let candidates = completer(&line.text);
+    let Some(first) = candidates.first() else {
+        return Ok(());
+    };
+
+    let common = candidates.iter().fold(first.as_str(), |common, candidate| {
+        let len = common
+            .char_indices()
+            .zip(candidate.chars())
+            .take_while(|((_, a), b)| a == b)
+            .last()
+            .map_or(0, |((i, a), _)| i + a.len_utf8());
+        &common[..len]
+    });
+    if common.len() > line.text.len() {
+        *line = Line::new(common);
+    } else if candidates.len() > 1 {
+        term::out!("\r\n{}\r\n", candidates.join("  "))?;
+        redraw(prompt, line, "")?;
+    }
+    Ok(())
+}
+
+/// Completes the last component of a path from the entries of its directory.
+/// Directories get a trailing separator, and hidden entries only show up once
+/// the component starts with a dot.
+pub(crate) fn complete_path(input: &str) -> Vec<String> {
+    let split = input.rfind(path::is_separator).map_or(0, |i| i + 1);
+    let (dir, prefix) = input.split_at(split);
+    let search = if dir.is_empty() {
+        Path::new(".")
+    } else {
+        Path::new(dir)
+    };
+    let Ok(entries) = fs::read_dir(search) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<String> = entries
+        .filter_map(Result::ok)
+        .filter_map(|entry| {
+            let name = entry.file_name().into_string().ok()?;
+            if !name.starts_with(prefix) || (name.starts_with('.') && !prefix.starts_with('.')) {
+                return None;
+            }
+            let mut candidate = format!("{}{}", dir, name);
+            if entry.path().is_dir() {
+                candidate.push(path::MAIN_SEPARATOR);
+            }
+            Some(candidate)
+        })
+        .collect();
+    candidates.sort();
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::MockTerminal;
+    use crate::Prompt;
+
+    #[test]
+    fn backspace_takes_a_whole_grapheme() {
+        let mut accented = Line::new("cafe\u{301}");
+        accented.backspace();
+        assert_eq!(accented.text, "caf");
+
+        let mut emoji = Line::new("hi \u{1f44b}\u{1f3fd}");
+        emoji.backspace();
+        assert_eq!((emoji.text.as_str(), emoji.cursor), ("hi ", 3));
+    }
+
+    #[test]
+    fn cursor_steps_over_graphemes() {
+        let mut line = Line::new("e\u{301}x");
+        line.left();
+        line.left();
+        assert_eq!(line.cursor, 0);
+        line.right();
+        assert_eq!(line.cursor, "e\u{301}".len());
+        line.delete();
+        assert_eq!(line.text, "e\u{301}");
+        assert!(line.at_end());
+    }
+
+    /// `text`, with the cursor at byte `cursor`.
+    fn line(text: &str, cursor: usize) -> Line {
+        Line {
+            text: text.to_string(),
+            cursor,
+        }
+    }
+
+    #[test]
+    fn insert_goes_at_the_cursor() {
+        let mut line = line("ac", 1);
+        line.insert('b');
+        line.insert_str("\u{e9}!");
+        assert_eq!((line.text.as_str(), line.cursor), ("ab\u{e9}!c", 5));
+    }
+
+    #[test]
+    fn deleting_to_either_end() {
+        let mut start = line("hello world", 6);
+        start.delete_to_start();
+        assert_eq!((start.text.as_str(), start.cursor), ("world", 0));
+
+        let mut end = line("hello world", 5);
+        end.delete_to_end();
+        assert_eq!(end.text, "hello");
+    }
+
+    #[test]
+    fn moving_by_words() {
+        let mut line = line("git commit  -m", 0);
+        line.word_forward();
+        assert_eq!(line.cursor, 4);
+        line.word_forward();
+        assert_eq!(line.cursor, 12);
+        line.word_back();
+        assert_eq!(line.cursor, 4);
+        line.word_end();
+        assert_eq!(line.cursor, 9);
+        line.word_end();
+        assert_eq!(line.cursor, 13);
+    }
+
+    #[test]
+    fn deleting_words() {
+        let mut back = Line::new("git commit  ");
+        back.delete_word();
+        assert_eq!((back.text.as_str(), back.cursor), ("git ", 4));
+
+        let mut forward = line("git commit -m", 4);
+        forward.delete_word_forward();
+        assert_eq!(forward.text, "git -m");
+    }
+
+    /// Runs the vi normal-mode commands `keys` on `line`, and what the last came to.
+    fn vi(line: &mut Line, keys: &str) -> Vi {
+        let mut pending = None;
+        let mut last = Vi::Done;
+        for c in keys.chars() {
+            last = vi_normal(line, c, &mut pending);
+        }
+        last
+    }
+
+    #[test]
+    fn vi_deletes_with_operators() {
+        let mut all = Line::new("git commit");
+        assert!(matches!(vi(&mut all, "dd"), Vi::Done));
+        assert_eq!(all.text, "");
+
+        let mut word = line("git commit -m", 4);
+        assert!(matches!(vi(&mut word, "dw"), Vi::Done));
+        assert_eq!(word.text, "git -m");
+
+        let mut change = line("git commit -m", 4);
+        assert!(matches!(vi(&mut change, "cw"), Vi::Insert));
+        assert_eq!((change.text.as_str(), change.cursor), ("git -m", 4));
+    }
+
+    #[test]
+    fn vi_motions() {
+        let mut line = line("git commit", 0);
+        assert!(matches!(vi(&mut line, "$"), Vi::Done));
+        assert_eq!(line.cursor, 9);
+        vi(&mut line, "b");
+        assert_eq!(line.cursor, 4);
+        vi(&mut line, "0");
+        assert_eq!(line.cursor, 0);
+        vi(&mut line, "ll");
+        assert_eq!(line.cursor, 2);
+        assert!(matches!(vi(&mut line, "A"), Vi::Insert));
+        assert!(line.at_end());
+    }
+
+    #[test]
+    fn vi_hands_some_keys_on() {
+        let mut line = Line::new("abc");
+        assert!(matches!(vi(&mut line, "x"), Vi::Key(Key::Delete)));
+        assert!(matches!(vi(&mut line, "X"), Vi::Key(Key::Backspace)));
+        assert!(matches!(vi(&mut line, "k"), Vi::Key(Key::Up)));
+        assert_eq!(line.text, "abc");
+    }
+
+    #[test]
+    fn vi_mode_edits_an_answer() {
+        let mut terminal = MockTerminal::new()
+            .type_text("git comit")
+            .press(Key::Esc)
+            .type_text("bcwcommit -m\n");
+        let answer = terminal.run(|| Prompt::text("Command").edit_mode(EditMode::Vi).ask());
+        assert_eq!(answer.unwrap(), "git commit -m");
+        assert_eq!(terminal.screen(), "Command: git commit -m");
+    }
+}
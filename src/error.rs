@@ -0,0 +1,73 @@
+//! Errors returned by the fallible prompt functions.
+
+use std::fmt;
+use std::io;
+
+/// Everything that can go wrong while prompting.
+///
+/// The panicking prompt functions (like [`prompt_tf_default`](crate::prompt_tf_default))
+/// turn these into panics; the `try_` variants hand them back to the caller.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum PromptError {
+    /// Reading from stdin or flushing stderr failed.
+    Io(io::Error),
+    /// Ctrl+C was pressed at the prompt, or the read was interrupted by a signal.
+    /// The terminal has been restored by the time this is returned.
+    Interrupted,
+    /// Stdin was closed before an answer was given.
+    Eof,
+    /// The answer was rejected. Carries the reason.
+    Validation(String),
+    /// Too many answers in a row were rejected, past the prompt's `max_attempts`.
+    TooManyAttempts,
+    /// No answer was given before the prompt's timeout ran out.
+    Timeout,
+    /// The prompt was given up on with a key bound to
+    /// [`Action::Cancel`](crate::Action::Cancel), or called off while it was asked
+    /// with `ask_async_until`. The terminal has been restored by the time this is
+    /// returned.
+    Cancelled,
+    /// Stdin or stderr isn't a terminal, and the prompt was set not to read from
+    /// it with [`NonInteractive`](crate::NonInteractive).
+    NotInteractive,
+    /// The key for going back was pressed at a wizard step, other than the first,
+    /// and the wizard returns to the step before it. It's only returned by the
+    /// prompts a [`Wizard`](crate::Wizard) asks.
+    Back,
+}
+
+impl fmt::Display for PromptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PromptError::Io(err) => write!(f, "I/O error while prompting: {}", err),
+            PromptError::Interrupted => write!(f, "prompt was interrupted"),
+            PromptError::Eof => write!(f, "reached end of input before an answer was given"),
+            PromptError::Validation(reason) => write!(f, "invalid input: {}", reason),
+            PromptError::TooManyAttempts => write!(f, "too many invalid answers"),
+            PromptError::Timeout => write!(f, "no answer was given in time"),
+            PromptError::Cancelled => write!(f, "prompt was cancelled"),
+            PromptError::NotInteractive => write!(f, "can't prompt without a terminal"),
+            PromptError::Back => write!(f, "went back to the previous question"),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PromptError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PromptError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::Interrupted => PromptError::Interrupted,
+            io::ErrorKind::UnexpectedEof => PromptError::Eof,
+            _ => PromptError::Io(err),
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Answers written in the user's own text editor, like a `git commit` message.
+
+use crate::locale::locale;
+use crate::term::{self, out, outln};
+use crate::{print_error, Choice, Prompt, PromptBuilder, PromptError};
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{self, Command};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+impl Prompt {
+    /// Text written in the user's editor, like [`prompt_editor`](crate::prompt_editor).
+    /// When stdin or stderr isn't a terminal, the text is read like
+    /// [`multiline`](Prompt::multiline) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let message = Prompt::editor("Commit message")
+    ///     .template("Summary\n\nDetails")
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn editor(prompt: &str) -> PromptBuilder<String> {
+        let mut builder = Prompt::multiline(prompt);
+        builder.editor = Some(String::new());
+        builder
+    }
+}
+
+impl PromptBuilder<String> {
+    /// Fills the file the editor opens with `template`, in place of leaving it
+    /// empty. Only [`Prompt::editor`] prompts open one.
+    pub fn template(mut self, template: &str) -> Self {
+        if self.editor.is_some() {
+            self.editor = Some(template.to_string());
+        }
+        self
+    }
+}
+
+/// What the editor is called under: `$VISUAL`, then `$EDITOR`, then the
+/// platform's usual one.
+fn editor_command() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(env::var_os)
+        .filter_map(|command| command.into_string().ok())
+        .find(|command| !command.trim().is_empty())
+        .unwrap_or_else(|| {
+            if cfg!(windows) {
+                "notepad".to_string()
+            } else {
+                "vi".to_string()
+            }
+        })
+}
+
+/// A file in the temp directory no other prompt is using.
+fn temp_file() -> PathBuf {
+    static COUNT: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNT.fetch_add(1, Ordering::Relaxed);
+    env::temp_dir().join(format!("cumaea-{}-{}.txt", process::id(), count))
+}
+
+/// Prints `prompt`, after `error` if the last answer was rejected, and opens the
+/// editor on a temp file holding `template`. Returns what the file holds once the
+/// editor exits, less trailing whitespace. The file is removed either way.
+pub(crate) fn edit(
+    prompt: &str,
+    template: &str,
+    error: Option<&str>,
+) -> Result<String, PromptError> {
+    if let Some(error) = error.filter(|error| !error.is_empty()) {
+        print_error(error)?;
+    }
+    out!("{} {}", prompt, locale().editor_hint)?;
+    term::output(|out| out.flush())?;
+
+    let path = temp_file();
+    let result = write_and_edit(&path, template);
+    let _ = fs::remove_file(&path);
+    outln!()?;
+    result
+}
+
+fn write_and_edit(path: &Path, template: &str) -> Result<String, PromptError> {
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(template.as_bytes())?;
+
+    // `$EDITOR` may carry arguments of its own, like `code --wait`.
+    let command = editor_command();
+    let mut words = command.split_whitespace();
+    let program = words.next().unwrap_or_default();
+    let status = Command::new(program).args(words).arg(path).status()?;
+    if !status.success() {
+        let message = format!("the editor `{}` exited with {}", command, status);
+        return Err(PromptError::Io(io::Error::other(message)));
+    }
+
+    Ok(fs::read_to_string(path)?.trim_end().to_string())
+}
+
+/// Opens the user's editor on a temp file given a prompt, color option, and
+/// template to fill the file with, and returns what's saved in it, like
+/// `git commit` does.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let message = prompt_editor("Commit message", None, "");
+/// ```
+///
+/// The editor is `$VISUAL`, then `$EDITOR`, then `vi` (`notepad` on Windows). The
+/// crate prints the prompt followed by ` (waiting for the editor to close the file)`
+/// while it's open, and trims trailing whitespace from the text. If stdin or stderr
+/// isn't a terminal, it reads lines like [`prompt_multiline`](crate::prompt_multiline)
+/// instead.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if the editor can't be started or exits with an error,
+/// or if Ctrl+C is pressed. [`Prompt::editor`] returns a
+/// [`PromptError`](crate::PromptError) instead.
+pub fn prompt_editor(prompt: &str, colored: Option<Choice>, template: &str) -> String {
+    Prompt::editor(prompt)
+        .colored(colored)
+        .template(template)
+        .ask()
+        .expect("Failed to read the editor's file.")
+}
@@ -0,0 +1,215 @@
+//! Asking for every field of a struct in turn, which is what `#[derive(Prompt)]`
+//! builds on.
+
+use crate::builder::Layout;
+use crate::locale::{fill, locale, number};
+use crate::{Prompt, PromptBuilder, PromptError};
+use std::path::PathBuf;
+
+/// A field or property name as a prompt, like `Listen port` for `listen_port`,
+/// `listen-port` or `listenPort`. Words in capitals, like `ID`, are kept as they
+/// are.
+#[cfg(any(feature = "serde", feature = "json"))]
+pub(crate) fn humanize(name: &str) -> String {
+    let mut words = vec![String::new()];
+    let mut after_lowercase = false;
+    for c in name.chars() {
+        if c == '_' || c == '-' || c.is_whitespace() {
+            words.push(String::new());
+            after_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && after_lowercase {
+            words.push(String::new());
+        }
+        after_lowercase = c.is_lowercase() || c.is_ascii_digit();
+        words.last_mut().expect("there's always a word").push(c);
+    }
+    let words: Vec<String> = words
+        .into_iter()
+        .filter(|word| !word.is_empty())
+        .enumerate()
+        .map(|(i, word)| {
+            let mut chars = word.chars();
+            let first = chars.next().expect("the word isn't empty");
+            match i {
+                0 => first.to_uppercase().chain(chars).collect(),
+                _ if chars.clone().any(char::is_uppercase) => word,
+                _ => first.to_lowercase().chain(chars).collect(),
+            }
+        })
+        .collect();
+    match words.is_empty() {
+        true => name.to_string(),
+        false => words.join(" "),
+    }
+}
+
+/// A struct that can be filled in by asking for each of its fields, usually
+/// through `#[derive(Prompt)]` with the `derive` feature.
+///
+/// The derive asks for the fields in the order they're declared, each with a
+/// prompt made from its name, like `Listen port` for `listen_port`. Every field's
+/// type has to be a [`PromptField`]. The `#[prompt(...)]` attribute on a field
+/// takes:
+///
+/// - `prompt = "..."`, the question to ask in place of the one made from the name.
+/// - `default = "..."`, the answer an empty line gives, parsed like a typed one.
+///   It's `y` or `n` for a `bool`.
+/// - `validate = "path::to::check"`, a `fn(&str) -> Result<(), String>` run on the
+///   answer like [`PromptBuilder::validate`]. It can be given more than once.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # #[cfg(feature = "derive")]
+/// # fn main() -> Result<(), cumaea::PromptError> {
+/// use cumaea::{Prompt, Promptable};
+///
+/// fn port(input: &str) -> Result<(), String> {
+///     match input.parse::<u16>() {
+///         Ok(1..) => Ok(()),
+///         _ => Err("Please enter a port from 1 to 65535.".to_string()),
+///     }
+/// }
+///
+/// #[derive(Prompt)]
+/// struct Config {
+///     host: String,
+///     #[prompt(default = "8080", validate = "port")]
+///     port: u16,
+///     #[prompt(prompt = "Serve over TLS? (y/n)", default = "n")]
+///     tls: bool,
+/// }
+///
+/// let config = Config::prompt()?;
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "derive"))]
+/// # fn main() {}
+/// ```
+pub trait Promptable: Sized {
+    /// Asks for each field in turn and returns the filled-in struct.
+    fn prompt() -> Result<Self, PromptError>;
+}
+
+/// A type that can be asked for on its own, as a field of a [`Promptable`] struct.
+///
+/// It's implemented for [`String`], [`bool`], the integer and floating-point
+/// types, [`char`] and [`PathBuf`]. Other types can implement it by returning the
+/// builder that asks for them:
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use std::str::FromStr;
+///
+/// #[derive(Clone)]
+/// struct Email(String);
+///
+/// impl FromStr for Email {
+///     type Err = ();
+///
+///     fn from_str(input: &str) -> Result<Self, ()> {
+///         match input.contains('@') {
+///             true => Ok(Email(input.to_string())),
+///             false => Err(()),
+///         }
+///     }
+/// }
+///
+/// impl PromptField for Email {
+///     fn builder(prompt: &str) -> PromptBuilder<Self> {
+///         Prompt::parse(prompt)
+///     }
+/// }
+/// ```
+pub trait PromptField: Clone + 'static {
+    /// The prompt asking for a value of this type, with `prompt` as its question.
+    fn builder(prompt: &str) -> PromptBuilder<Self>;
+}
+
+impl PromptField for String {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        Prompt::text(prompt)
+    }
+}
+
+impl PromptField for bool {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        Prompt::confirm(prompt)
+    }
+}
+
+impl PromptField for char {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            input.parse().map_err(|_| locale().character.clone())
+        })
+    }
+}
+
+impl PromptField for PathBuf {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        Prompt::path(prompt)
+    }
+}
+
+macro_rules! whole_numbers {
+    ($($ty:ty),*) => {$(
+        impl PromptField for $ty {
+            fn builder(prompt: &str) -> PromptBuilder<Self> {
+                PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+                    number(input).and_then(|normal| normal.parse().ok()).ok_or_else(|| {
+                        fill(
+                            &locale().whole_number_between,
+                            &[("min", &<$ty>::MIN), ("max", &<$ty>::MAX)],
+                        )
+                    })
+                })
+            }
+        }
+    )*};
+}
+
+whole_numbers!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl PromptField for f64 {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        Prompt::float(prompt)
+    }
+}
+
+impl PromptField for f32 {
+    fn builder(prompt: &str) -> PromptBuilder<Self> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            match number(input).map(|normal| normal.parse::<f32>()) {
+                Some(Ok(value)) if value.is_finite() => Ok(value),
+                _ => Err(locale().number.clone()),
+            }
+        })
+    }
+}
+
+/// A `validate = "..."` check on a field.
+type Check = fn(&str) -> Result<(), String>;
+
+/// Asks for one field, for the code `#[derive(Prompt)]` writes.
+#[doc(hidden)]
+pub fn __field<T: PromptField>(
+    prompt: &str,
+    default: Option<&str>,
+    validators: &[Check],
+) -> Result<T, PromptError> {
+    let mut builder = T::builder(prompt);
+    if let Some(text) = default {
+        let value = (builder.parser)(text).map_err(|message| {
+            let message = format!("the default {:?} for {:?}: {}", text, prompt, message);
+            PromptError::Validation(message)
+        })?;
+        builder = builder.default(value);
+    }
+    for &validator in validators {
+        builder = builder.validate(validator);
+    }
+    builder.ask()
+}
@@ -0,0 +1,132 @@
+//! A small fzf-style fuzzy matcher for the search prompts.
+
+/// Scores how well `pattern` matches `candidate`, or `None` if it doesn't match.
+///
+/// The pattern matches when its characters appear in the candidate in order,
+/// ignoring case. Runs of consecutive characters and matches at the start of a
+/// word score higher, and matches that start late score lower.
+pub(crate) fn score(pattern: &str, candidate: &str) -> Option<i64> {
+    let mut pattern = pattern.chars().filter(|c| !c.is_whitespace()).peekable();
+    if pattern.peek().is_none() {
+        return Some(0);
+    }
+
+    let mut score = 0;
+    let mut first = None;
+    let mut previous: Option<char> = None;
+    let mut streak = false;
+    for (i, c) in candidate.chars().enumerate() {
+        let Some(&wanted) = pattern.peek() else {
+            break;
+        };
+        if chars_match(wanted, c) {
+            score += 1;
+            if streak {
+                score += 4;
+            }
+            if is_word_start(previous, c) {
+                score += 3;
+            }
+            first.get_or_insert(i as i64);
+            streak = true;
+            pattern.next();
+        } else {
+            streak = false;
+        }
+        previous = Some(c);
+    }
+
+    if pattern.peek().is_some() {
+        return None;
+    }
+    Some(score - first.unwrap_or(0))
+}
+
+/// Returns the indices of the `items` matching `pattern`, best match first.
+/// Items that score the same keep their original order.
+pub(crate) fn filter<S: AsRef<str>>(pattern: &str, items: &[S]) -> Vec<usize> {
+    rank(pattern, items, 0..items.len())
+}
+
+/// Like [`filter`], but only looks at the items at `matches`, the indices `filter`
+/// returned for a pattern `pattern` starts with. Anything matching the longer
+/// pattern matches the shorter one too, so the rest needn't be scored again.
+pub(crate) fn refine<S: AsRef<str>>(pattern: &str, items: &[S], matches: &[usize]) -> Vec<usize> {
+    rank(pattern, items, matches.iter().copied())
+}
+
+fn rank<S: AsRef<str>>(
+    pattern: &str,
+    items: &[S],
+    candidates: impl Iterator<Item = usize>,
+) -> Vec<usize> {
+    let mut matches: Vec<(usize, i64)> = candidates
+        .filter_map(|i| score(pattern, items[i].as_ref()).map(|score| (i, score)))
+        .collect();
+    matches.sort_unstable_by_key(|&(i, score)| (std::cmp::Reverse(score), i));
+    matches.into_iter().map(|(i, _)| i).collect()
+}
+
+fn chars_match(wanted: char, c: char) -> bool {
+    wanted == c || wanted.to_lowercase().eq(c.to_lowercase())
+}
+
+fn is_word_start(previous: Option<char>, c: char) -> bool {
+    match previous {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric() || (previous.is_lowercase() && c.is_uppercase())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_characters_in_order_ignoring_case() {
+        assert_eq!(score("", "anything"), Some(0));
+        assert_eq!(score(" ", "anything"), Some(0));
+        assert!(score("cfg", "Config").is_some());
+        assert!(score("CFG", "config").is_some());
+        assert!(score("gfc", "config").is_none());
+        assert!(score("configs", "config").is_none());
+    }
+
+    #[test]
+    fn scores_runs_and_word_starts_higher() {
+        assert_eq!(score("ab", "ab"), Some(9));
+        assert!(score("ab", "ab") > score("ab", "a-b"));
+        assert!(score("nt", "new_thing") > score("nt", "mint"));
+        assert!(score("nt", "newThing") > score("nt", "newthing"));
+        assert!(score("ab", "ab---") > score("ab", "---ab"));
+    }
+
+    #[test]
+    fn puts_the_best_matches_first() {
+        let items = ["xab", "ab", "a-b", "b"];
+        assert_eq!(filter("ab", &items), [1, 2, 0]);
+        assert_eq!(filter("", &items), [0, 1, 2, 3]);
+        assert!(filter("z", &items).is_empty());
+    }
+
+    #[test]
+    fn keeps_the_order_of_matches_that_score_the_same() {
+        let items = ["ca", "ba", "da"];
+        assert_eq!(filter("a", &items), [0, 1, 2]);
+    }
+
+    #[test]
+    fn refining_matches_what_filtering_does() {
+        let items = ["apple", "apricot", "banana", "grape", "pineapple", "papaya"];
+        let mut matches = filter("", &items);
+        let mut pattern = String::new();
+        for c in "apl".chars() {
+            pattern.push(c);
+            matches = refine(&pattern, &items, &matches);
+            assert_eq!(matches, filter(&pattern, &items), "{}", pattern);
+        }
+        assert_eq!(matches, [0, 4]);
+    }
+}
@@ -0,0 +1,148 @@
+//! The answers typed at each prompt so far, for the line editor to bring back with
+//! the arrow keys. They're kept per prompt text, or per file for prompts given a
+//! [`history_file`](crate::PromptBuilder::history_file).
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+struct History {
+    prompts: BTreeMap<String, Vec<String>>,
+    files: BTreeMap<PathBuf, Vec<String>>,
+}
+
+static HISTORY: Mutex<History> = Mutex::new(History {
+    prompts: BTreeMap::new(),
+    files: BTreeMap::new(),
+});
+
+fn history() -> MutexGuard<'static, History> {
+    HISTORY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The answers typed at `prompt` in this process, oldest first.
+pub(crate) fn entries(prompt: &str) -> Vec<String> {
+    history().prompts.get(prompt).cloned().unwrap_or_default()
+}
+
+/// Adds `entry` to what's been typed at `prompt`.
+pub(crate) fn add(prompt: &str, entry: &str) {
+    let mut history = history();
+    push(
+        history.prompts.entry(prompt.to_string()).or_default(),
+        entry,
+        usize::MAX,
+    );
+}
+
+/// The entries in the history file at `path`, oldest first. A missing file has
+/// none. The file is only read the first time, and kept up to date after that.
+pub(crate) fn file_entries(path: &Path) -> io::Result<Vec<String>> {
+    let mut history = history();
+    if let Some(entries) = history.files.get(path) {
+        return Ok(entries.clone());
+    }
+    let entries: Vec<String> = match fs::read_to_string(path) {
+        Ok(contents) => contents.lines().map(str::to_string).collect(),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(err) => return Err(err),
+    };
+    history.files.insert(path.to_path_buf(), entries.clone());
+    Ok(entries)
+}
+
+/// Adds `entry` to the history file at `path`, keeping the newest `limit` entries,
+/// and writes the file out again.
+pub(crate) fn add_to_file(path: &Path, entry: &str, limit: usize) -> io::Result<()> {
+    file_entries(path)?;
+    let mut history = history();
+    let entries = history.files.entry(path.to_path_buf()).or_default();
+    push(entries, entry, limit);
+    let mut contents = entries.join("\n");
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    fs::write(path, contents)
+}
+
+/// Moves `entry` to the end of `entries`, dropping any earlier copy of it and the
+/// oldest entries past `limit`. Empty entries aren't kept.
+fn push(entries: &mut Vec<String>, entry: &str, limit: usize) {
+    if entry.is_empty() {
+        return;
+    }
+    entries.retain(|earlier| earlier != entry);
+    entries.push(entry.to_string());
+    if entries.len() > limit {
+        entries.drain(..entries.len() - limit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+
+    /// A history file of this process's own, with nothing in it yet.
+    fn file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("cumaea-{}-{}.history", process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn moves_entries_given_again_to_the_end() {
+        let mut entries = Vec::new();
+        for entry in ["ls", "cd", "", "ls"] {
+            push(&mut entries, entry, usize::MAX);
+        }
+        assert_eq!(entries, ["cd", "ls"]);
+    }
+
+    #[test]
+    fn keeps_entries_per_prompt() {
+        add("History, first prompt", "a");
+        add("History, second prompt", "b");
+        add("History, first prompt", "c");
+        assert_eq!(entries("History, first prompt"), ["a", "c"]);
+        assert_eq!(entries("History, second prompt"), ["b"]);
+        assert!(entries("History, no prompt").is_empty());
+    }
+
+    #[test]
+    fn writes_files_without_copies() {
+        let path = file("copies");
+        assert!(file_entries(&path).unwrap().is_empty());
+        for entry in ["ls", "cd", "ls"] {
+            add_to_file(&path, entry, 500).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "cd\nls\n");
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn keeps_the_newest_entries_up_to_the_limit() {
+        let path = file("limit");
+        for entry in ["a", "b", "c", "d"] {
+            add_to_file(&path, entry, 2).unwrap();
+        }
+        assert_eq!(fs::read_to_string(&path).unwrap(), "c\nd\n");
+        assert_eq!(file_entries(&path).unwrap(), ["c", "d"]);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reads_files_once() {
+        let path = file("cache");
+        fs::write(&path, "a\nb\n").unwrap();
+        assert_eq!(file_entries(&path).unwrap(), ["a", "b"]);
+        // What's kept isn't read again, so it outlasts changes to the file.
+        fs::write(&path, "x\n").unwrap();
+        assert_eq!(file_entries(&path).unwrap(), ["a", "b"]);
+        add_to_file(&path, "c", 500).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "a\nb\nc\n");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,138 @@
+//! Which keys do what in the menus and lists.
+
+use crate::term::Key;
+use std::sync::{PoisonError, RwLock};
+
+/// Something a key can be bound to in a [`Keymap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Action {
+    /// Moves the highlight up an item.
+    Up,
+    /// Moves the highlight down an item.
+    Down,
+    /// Moves the highlight a column left, in lists laid out in columns.
+    Left,
+    /// Moves the highlight a column right, in lists laid out in columns.
+    Right,
+    /// Moves the highlight to the first item.
+    First,
+    /// Moves the highlight to the last item.
+    Last,
+    /// Moves the highlight up a page, in lists too long for the terminal.
+    PageUp,
+    /// Moves the highlight down a page, in lists too long for the terminal.
+    PageDown,
+    /// Moves the highlighted item itself up a place in a ranking.
+    MoveUp,
+    /// Moves the highlighted item itself down a place in a ranking.
+    MoveDown,
+    /// Checks or unchecks the highlighted item in a checkbox list, or picks it up
+    /// or puts it down in a ranking.
+    Toggle,
+    /// Picks the highlighted item, or the checked ones in a checkbox list.
+    Pick,
+    /// Gives up on the prompt, which returns
+    /// [`PromptError::Cancelled`](crate::PromptError::Cancelled).
+    Cancel,
+    /// Goes back to the previous step of a [`Wizard`](crate::Wizard). It does nothing
+    /// elsewhere, or at a wizard's first step.
+    Back,
+}
+
+/// The keys the menus and lists respond to, like [`Prompt::menu`](crate::Prompt::menu)
+/// and [`Prompt::multi_select`](crate::Prompt::multi_select). Set one for every
+/// prompt with [`set_keymap`], or for a single one with its `keymap` method.
+///
+/// The default binds Up and `k`, Down and `j`, Left and `h`, Right and `l`, Home,
+/// End, PageUp, PageDown, `K` and `J` to move an item, Space to toggle, Enter to
+/// pick, and Esc to go back a step in a wizard. Nothing cancels. In
+/// [`Prompt::fuzzy_select`](crate::Prompt::fuzzy_select), typed characters always go
+/// to the search, so only bindings for other keys apply.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_keymap(
+///     Keymap::default()
+///         .bind(Key::Char('q'), Action::Cancel)
+///         .bind(Key::Esc, Action::Cancel)
+///         .bind(Key::Char('g'), Action::First)
+///         .bind(Key::Char('G'), Action::Last),
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Keymap {
+    bindings: Vec<(Key, Action)>,
+}
+
+impl Keymap {
+    /// A keymap with nothing bound.
+    pub fn new() -> Self {
+        Keymap {
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Binds `key` to `action`, in place of whatever it was bound to.
+    pub fn bind(mut self, key: Key, action: Action) -> Self {
+        self = self.unbind(key);
+        self.bindings.push((key, action));
+        self
+    }
+
+    /// Leaves `key` unbound.
+    pub fn unbind(mut self, key: Key) -> Self {
+        self.bindings.retain(|(bound, _)| *bound != key);
+        self
+    }
+
+    /// What `key` is bound to, if anything.
+    pub(crate) fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == key)
+            .map(|(_, action)| *action)
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new()
+            .bind(Key::Up, Action::Up)
+            .bind(Key::Char('k'), Action::Up)
+            .bind(Key::Down, Action::Down)
+            .bind(Key::Char('j'), Action::Down)
+            .bind(Key::Left, Action::Left)
+            .bind(Key::Char('h'), Action::Left)
+            .bind(Key::Right, Action::Right)
+            .bind(Key::Char('l'), Action::Right)
+            .bind(Key::Home, Action::First)
+            .bind(Key::End, Action::Last)
+            .bind(Key::PageUp, Action::PageUp)
+            .bind(Key::PageDown, Action::PageDown)
+            .bind(Key::Char('K'), Action::MoveUp)
+            .bind(Key::Char('J'), Action::MoveDown)
+            .bind(Key::Char(' '), Action::Toggle)
+            .bind(Key::Enter, Action::Pick)
+            .bind(Key::Esc, Action::Back)
+    }
+}
+
+static KEYMAP: RwLock<Option<Keymap>> = RwLock::new(None);
+
+/// Sets the keymap every menu and list uses from here on, on every thread, unless
+/// it's given its own.
+pub fn set_keymap(keymap: Keymap) {
+    *KEYMAP.write().unwrap_or_else(PoisonError::into_inner) = Some(keymap);
+}
+
+/// The keymap set with [`set_keymap`], or the default one.
+pub(crate) fn keymap() -> Keymap {
+    KEYMAP
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default()
+}
@@ -3,11 +3,161 @@
 //! `cumaea` is a crate which handles prompting for user input.
 //! It's named after the Cumaean Sibyl, who sold the Sibylline
 //! books to the last king of Rome.
+//!
+//! ## Environment
+//!
+//! Whoever runs a tool built on this crate can change how it prompts with these
+//! variables, read when the first prompt is shown:
+//!
+//! - `CUMAEA_NO_COLOR` draws prompts without color, unless the tool sets
+//!   [`ColorMode::Always`].
+//! - `CUMAEA_ASSUME_DEFAULTS` answers every prompt that has a default with it,
+//!   like [`set_assume_defaults`].
+//! - `CUMAEA_ACCESSIBLE` keeps prompts from moving the cursor or redrawing, for
+//!   screen readers, like [`set_accessible`].
+//! - `CUMAEA_PLAIN` keeps escape sequences out of what prompts print, like
+//!   [`set_plain`].
+//! - `CUMAEA_ANSWERS_FILE` answers prompts by key from a TOML file, like
+//!   `load_answers` behind the `toml` feature.
+//! - `CUMAEA_REPLAY_FILE` replays a recorded session, like [`replay_answers`].
+//! - `CUMAEA_RECORD_FILE` records the session, like [`record_answers`].
+//!
+//! The switches are on when set to anything but nothing, `0` or `false`. A file
+//! that can't be used is reported and left out.
+
+use colored::Color;
+use std::borrow::Cow;
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+use std::time::Instant;
+
+mod ansi;
+#[cfg(feature = "async")]
+mod asynchronous;
+#[cfg(feature = "bidi")]
+mod bidi;
+mod builder;
+mod config;
+mod confirm;
+#[cfg(feature = "chrono")]
+mod date;
+#[cfg(feature = "serde")]
+mod de;
+mod defaults;
+mod duration;
+mod editor;
+mod error;
+mod external;
+mod fields;
+mod fuzzy;
+mod history;
+mod keymap;
+mod list;
+mod locale;
+#[cfg(feature = "log")]
+mod logging;
+mod net;
+mod option;
+mod pairs;
+mod path;
+#[cfg(feature = "regex")]
+mod pattern;
+#[cfg(feature = "indicatif")]
+mod progress;
+mod prompter;
+mod scale;
+#[cfg(feature = "json")]
+mod schema;
+mod select;
+mod session;
+#[cfg(feature = "tracing")]
+mod spans;
+mod term;
+pub mod test;
+mod theme;
+mod tree;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod wizard;
 
-use colored::*;
-use std::io::{stdout, Write};
+#[cfg(feature = "async")]
+pub use asynchronous::{ask_async, ask_async_until};
+#[cfg(feature = "async")]
+pub use asynchronous::{prompt_float_async, prompt_multi_select_async, prompt_ranking_async};
+#[cfg(feature = "async")]
+pub use asynchronous::{prompt_fuzzy_select_async, prompt_int_async, prompt_menu_async};
+#[cfg(feature = "async")]
+pub use asynchronous::{prompt_multiline_async, prompt_parse_async, prompt_password_async};
+#[cfg(feature = "async")]
+pub use asynchronous::{prompt_selection_async, prompt_text_async, prompt_tf_default_async};
+#[cfg(feature = "bidi")]
+pub use bidi::{set_text_direction, TextDirection};
+pub use builder::{set_assume_defaults, NonInteractive, Prompt, PromptBuilder, Skippable};
+pub use confirm::{forget_batch_answer, set_yes_no};
+pub use confirm::{prompt_confirm_phrase, prompt_tf_all, prompt_tf_cancel, prompt_tf_countdown};
+pub use confirm::{BatchAnswer, Confirmation, Remembered, YesNo};
+#[cfg(feature = "derive")]
+pub use cumaea_derive::Prompt;
+#[cfg(feature = "chrono")]
+pub use date::{prompt_date, prompt_time, ISO_DATE};
+#[cfg(feature = "serde")]
+pub use de::prompt_deserialize;
+#[cfg(feature = "toml")]
+pub use defaults::FileDefaults;
+pub use defaults::{set_defaults, Defaults, DefaultsProvider, EnvDefaults};
+pub use duration::prompt_duration;
+pub use editor::{set_edit_mode, EditMode};
+pub use error::PromptError;
+pub use external::prompt_editor;
+#[doc(hidden)]
+pub use fields::__field;
+pub use fields::{PromptField, Promptable};
+pub use keymap::{set_keymap, Action, Keymap};
+pub use list::prompt_list;
+pub use locale::{set_locale, Locale};
+#[cfg(feature = "url")]
+pub use net::prompt_url;
+pub use net::{prompt_cidr, prompt_ip, Cidr, CidrParseError, IpVersion};
+pub use option::{prompt_select, Select, SelectOption};
+pub use pairs::{prompt_pairs, Pairs};
+pub use path::{prompt_path, PathOptions};
+#[cfg(feature = "regex")]
+pub use pattern::prompt_matching;
+#[cfg(feature = "indicatif")]
+pub use progress::set_progress;
+pub use prompter::Prompter;
+pub use scale::{prompt_rating, prompt_slider, Slider};
+#[cfg(feature = "json")]
+pub use schema::prompt_json_schema;
+pub use select::{FuzzySelect, Menu, MultiSelect, Ranking};
+#[cfg(feature = "toml")]
+pub use session::load_answers;
+#[cfg(feature = "clap")]
+pub use session::load_args;
+pub use session::{record_answers, replay_answers};
+#[cfg(not(target_arch = "wasm32"))]
+pub use term::CrosstermBackend;
+pub use term::{
+    set_accessible, set_output, set_plain, set_tty_input, with_backend, Backend, Key, Output,
+};
+pub use theme::{set_color_mode, set_theme, ColorMode, Style, Symbols, Theme};
+pub use tree::{prompt_tree_select, Node, TreeSelect};
+#[cfg(feature = "wasm")]
+pub use wasm::JsBackend;
+pub use wizard::{Answer, Answers, Wizard, WizardStep};
+#[cfg(feature = "zeroize")]
+pub use zeroize::Zeroizing;
+
+use builder::Layout;
 
 /// An enum that represents colors from the `colored` crate.
+///
+/// [`Rgb`](ChoiceColor::Rgb) colors are drawn in truecolor when `COLORTERM` says
+/// the terminal supports it, and as the closest of the sixteen basic colors
+/// otherwise. [`Ansi256`](ChoiceColor::Ansi256) colors are sent by their index in
+/// the palette, which 256-color terminals show as it is. Making either
+/// [`Bright`](Choice::Bright) doesn't change it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ChoiceColor {
     Black,
     Red,
@@ -17,9 +167,49 @@ pub enum ChoiceColor {
     Magenta,
     Cyan,
     White,
+    /// Any color, given as red, green and blue.
+    Rgb(u8, u8, u8),
+    /// One of the 256 colors of the xterm palette.
+    Ansi256(u8),
+}
+
+impl ChoiceColor {
+    /// The SGR parameters for this color, as the background if `background` is set,
+    /// in its bright shade if `bright` is and it has one.
+    fn code(self, background: bool, bright: bool) -> Cow<'static, str> {
+        let color = match (self, bright) {
+            // Sent by index, so a 256-color terminal shows the very color asked for.
+            (ChoiceColor::Ansi256(index), _) => {
+                let layer = if background { 48 } else { 38 };
+                return format!("{};5;{}", layer, index).into();
+            }
+            (ChoiceColor::Rgb(r, g, b), _) => Color::TrueColor { r, g, b },
+            (ChoiceColor::Black, false) => Color::Black,
+            (ChoiceColor::Red, false) => Color::Red,
+            (ChoiceColor::Green, false) => Color::Green,
+            (ChoiceColor::Yellow, false) => Color::Yellow,
+            (ChoiceColor::Blue, false) => Color::Blue,
+            (ChoiceColor::Magenta, false) => Color::Magenta,
+            (ChoiceColor::Cyan, false) => Color::Cyan,
+            (ChoiceColor::White, false) => Color::White,
+            (ChoiceColor::Black, true) => Color::BrightBlack,
+            (ChoiceColor::Red, true) => Color::BrightRed,
+            (ChoiceColor::Green, true) => Color::BrightGreen,
+            (ChoiceColor::Yellow, true) => Color::BrightYellow,
+            (ChoiceColor::Blue, true) => Color::BrightBlue,
+            (ChoiceColor::Magenta, true) => Color::BrightMagenta,
+            (ChoiceColor::Cyan, true) => Color::BrightCyan,
+            (ChoiceColor::White, true) => Color::BrightWhite,
+        };
+        match background {
+            true => color.to_bg_str(),
+            false => color.to_fg_str(),
+        }
+    }
 }
 
 /// An enum that represents stylings from the `colored` crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum Choice {
     Normal(ChoiceColor),
     On(ChoiceColor),
@@ -27,10 +217,57 @@ pub enum Choice {
     OnBright(ChoiceColor),
 }
 
+impl Choice {
+    /// The SGR parameters for this styling.
+    fn code(&self) -> Cow<'static, str> {
+        match *self {
+            Choice::Normal(color) => color.code(false, false),
+            Choice::On(color) => color.code(true, false),
+            Choice::Bright(color) => color.code(false, true),
+            Choice::OnBright(color) => color.code(true, true),
+        }
+    }
+}
+
+/// Styles `text` with `own`, or with `themed` from the theme if there's no style of
+/// its own.
+fn paint(text: &str, own: &Option<Style>, themed: &Style) -> String {
+    own.as_ref().unwrap_or(themed).paint(text)
+}
+
+/// Prints why an answer was rejected, in the theme's error style. An empty message
+/// prints nothing.
+fn print_error(message: &str) -> Result<(), PromptError> {
+    if !message.is_empty() {
+        term::outln!("{}", theme::theme().error_style.paint(message))?;
+    }
+    Ok(())
+}
+
+/// Flushes the output and reads one line of input, trimmed.
+///
+/// Returns [`PromptError::Eof`] if stdin has been closed.
+fn read_input() -> Result<String, PromptError> {
+    read_line_before(None).map(|input| input.trim().to_owned())
+}
+
+/// Like [`read_input`], but only the line ending is removed, and it returns
+/// [`PromptError::Timeout`] if `deadline` passes before a whole line has been read.
+fn read_line_before(deadline: Option<Instant>) -> Result<String, PromptError> {
+    term::output(|out| out.flush())?;
+    let mut input = term::read_stdin_line(deadline)?;
+    if input.is_empty() {
+        return Err(PromptError::Eof);
+    }
+
+    input.truncate(input.trim_end_matches(['\r', '\n']).len());
+    Ok(input)
+}
+
 /// Prompts for input text given a plain &str, a colored &str,
 /// and the [`Option<Choice>`] to use for the colored prompt.
 /// It looks like this:
-/// ```
+/// ```text
 /// {Enter a} {NAME}:
 /// ```
 /// where the first part is plain_prompt, the second, prompt,
@@ -38,7 +275,8 @@ pub enum Choice {
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use cumaea::*;
 /// let the_text = prompt_text(
 ///        "Enter your",
 ///        "name",
@@ -51,124 +289,112 @@ pub enum Choice {
 ///
 /// # Panics
 ///
-/// Panics on failure of `stdin().read_line()` or `stdout().flush()`.
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. See
+/// [`try_prompt_text`] for a version that doesn't.
 pub fn prompt_text(plain_prompt: &str, prompt: &str, colored: Option<Choice>) -> String {
-    let mut input = String::new();
-    match colored {
-        Some(ref color_choice) => match color_choice {
-            Choice::Normal(color) => match color {
-                ChoiceColor::Black => print!("{} {}: ", plain_prompt, prompt.to_string().black()),
-                ChoiceColor::Red => print!("{} {}: ", plain_prompt, prompt.to_string().red()),
-                ChoiceColor::Green => print!("{} {}: ", plain_prompt, prompt.to_string().green()),
-                ChoiceColor::Yellow => print!("{} {}: ", plain_prompt, prompt.to_string().yellow()),
-                ChoiceColor::Blue => print!("{} {}: ", plain_prompt, prompt.to_string().blue()),
-                ChoiceColor::Magenta => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().magenta())
-                }
-                ChoiceColor::Cyan => print!("{} {}: ", plain_prompt, prompt.to_string().cyan()),
-                ChoiceColor::White => print!("{} {}: ", plain_prompt, prompt.to_string().white()),
-            },
-            Choice::On(color) => match color {
-                ChoiceColor::Black => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_black())
-                }
-                ChoiceColor::Red => print!("{} {}: ", plain_prompt, prompt.to_string().on_red()),
-                ChoiceColor::Green => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_green())
-                }
-                ChoiceColor::Yellow => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_yellow())
-                }
-                ChoiceColor::Blue => print!("{} {}: ", plain_prompt, prompt.to_string().on_blue()),
-                ChoiceColor::Magenta => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_magenta())
-                }
-                ChoiceColor::Cyan => print!("{} {}: ", plain_prompt, prompt.to_string().on_cyan()),
-                ChoiceColor::White => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_white())
-                }
-            },
-            Choice::Bright(color) => match color {
-                ChoiceColor::Black => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_black())
-                }
-                ChoiceColor::Red => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_red())
-                }
-                ChoiceColor::Green => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_green())
-                }
-                ChoiceColor::Yellow => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_yellow())
-                }
-                ChoiceColor::Blue => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_blue())
-                }
-                ChoiceColor::Magenta => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_magenta())
-                }
-                ChoiceColor::Cyan => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_cyan())
-                }
-                ChoiceColor::White => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().bright_white())
-                }
-            },
-            Choice::OnBright(color) => match color {
-                ChoiceColor::Black => print!(
-                    "{} {}: ",
-                    plain_prompt,
-                    prompt.to_string().on_bright_black()
-                ),
-                ChoiceColor::Red => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_bright_red())
-                }
-                ChoiceColor::Green => print!(
-                    "{} {}: ",
-                    plain_prompt,
-                    prompt.to_string().on_bright_green()
-                ),
-                ChoiceColor::Yellow => print!(
-                    "{} {}: ",
-                    plain_prompt,
-                    prompt.to_string().on_bright_yellow()
-                ),
-                ChoiceColor::Blue => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_bright_blue())
-                }
-                ChoiceColor::Magenta => print!(
-                    "{} {}: ",
-                    plain_prompt,
-                    prompt.to_string().on_bright_magenta()
-                ),
-                ChoiceColor::Cyan => {
-                    print!("{} {}: ", plain_prompt, prompt.to_string().on_bright_cyan())
-                }
-                ChoiceColor::White => print!(
-                    "{} {}: ",
-                    plain_prompt,
-                    prompt.to_string().on_bright_white()
-                ),
-            },
-        },
-        None => todo!(),
-    }
+    try_prompt_text(plain_prompt, prompt, colored).expect("Failed to read line.")
+}
 
-    stdout().flush().expect("Flushing line failed.");
-    input.clear();
-    std::io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line.");
+/// Like [`prompt_text`], but returns a [`PromptError`] instead of panicking.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// match try_prompt_text("Enter your", "name", None) {
+///     Ok(name) => println!("Hello, {}!", name),
+///     Err(PromptError::Eof) => println!("Nobody's there."),
+///     Err(err) => eprintln!("{}", err),
+/// }
+/// ```
+pub fn try_prompt_text(
+    plain_prompt: &str,
+    prompt: &str,
+    colored: Option<Choice>,
+) -> Result<String, PromptError> {
+    PromptBuilder::new(
+        prompt,
+        Layout::Labelled(plain_prompt.to_string()),
+        builder::parse_string,
+    )
+    .colored(colored)
+    .ask()
+}
 
-    input.trim().to_owned()
+/// Prompts for a line of text given a prompt, color option, and a function that
+/// suggests completions for what's been typed so far.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let users = ["ada", "alan", "grace"];
+/// let user = prompt_autocomplete("User", None, move |input| {
+///     users
+///         .iter()
+///         .filter(|user| user.starts_with(input))
+///         .map(|user| user.to_string())
+///         .collect()
+/// });
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. As the answer is
+/// typed, the rest of the first suggestion is shown dimmed after the cursor, and
+/// Right takes it. Tab fills in as much as the suggestions agree on, or lists them
+/// if that adds nothing. Any answer is accepted, suggested or not.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`PromptBuilder::completions`] returns a [`PromptError`] instead.
+pub fn prompt_autocomplete(
+    prompt: &str,
+    colored: Option<Choice>,
+    completions: impl Fn(&str) -> Vec<String> + 'static,
+) -> String {
+    Prompt::text(prompt)
+        .colored(colored)
+        .completions(completions)
+        .ask()
+        .expect("Failed to read line.")
+}
+
+/// Prompts for several lines of text given a prompt and color option, like a
+/// commit message. Lines are read until one that's just `.`, or until Ctrl+D is
+/// pressed on an empty line, and returned joined with newlines.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let notes = prompt_multiline("Release notes", None);
+/// ```
+///
+/// The crate prints the prompt followed by ` (end with a lone . or Ctrl+D):` on a
+/// line of its own. Each line is kept as typed, indentation included, and the
+/// closing `.` is left out. If stdin isn't a terminal, lines are read until a `.`
+/// or the end of input.
+///
+/// # Panics
+///
+/// Panics on I/O failure, or if Ctrl+C is pressed. [`Prompt::multiline`] returns a
+/// [`PromptError`] instead.
+pub fn prompt_multiline(prompt: &str, colored: Option<Choice>) -> String {
+    Prompt::multiline(prompt)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read lines.")
 }
 
 /// Prompts for a true/false value given a prompt, color option, and default value.
-/// Loops until the input is valid.
+/// Loops until the input is `y` or `n`, printing `Please answer y or n.` in the
+/// theme's error style after anything else. Other words can be set with
+/// [`set_yes_no`].
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use cumaea::*;
 /// let the_bool = prompt_tf_default(
 ///        "Approved? (Y/n) >>> ",
 ///        Some(Choice::Normal(ChoiceColor::Green)),
@@ -182,75 +408,38 @@ pub fn prompt_text(plain_prompt: &str, prompt: &str, colored: Option<Choice>) ->
 ///
 /// # Panics
 ///
-/// Panics on failure of `stdin().read_line()` or `stdout().flush()`.
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. See
+/// [`try_prompt_tf_default`] for a version that doesn't.
 pub fn prompt_tf_default(prompt: &str, colored: Option<Choice>, default: bool) -> bool {
-    let mut input = String::new();
-    loop {
-        match colored {
-            Some(ref color_choice) => match color_choice {
-                Choice::Normal(color) => match color {
-                    ChoiceColor::Black => print!("{}", prompt.to_string().black()),
-                    ChoiceColor::Red => print!("{}", prompt.to_string().red()),
-                    ChoiceColor::Green => print!("{}", prompt.to_string().green()),
-                    ChoiceColor::Yellow => print!("{}", prompt.to_string().yellow()),
-                    ChoiceColor::Blue => print!("{}", prompt.to_string().blue()),
-                    ChoiceColor::Magenta => print!("{}", prompt.to_string().magenta()),
-                    ChoiceColor::Cyan => print!("{}", prompt.to_string().cyan()),
-                    ChoiceColor::White => print!("{}", prompt.to_string().white()),
-                },
-                Choice::On(color) => match color {
-                    ChoiceColor::Black => print!("{}", prompt.to_string().on_black()),
-                    ChoiceColor::Red => print!("{}", prompt.to_string().on_red()),
-                    ChoiceColor::Green => print!("{}", prompt.to_string().on_green()),
-                    ChoiceColor::Yellow => print!("{}", prompt.to_string().on_yellow()),
-                    ChoiceColor::Blue => print!("{}", prompt.to_string().on_blue()),
-                    ChoiceColor::Magenta => print!("{}", prompt.to_string().on_magenta()),
-                    ChoiceColor::Cyan => print!("{}", prompt.to_string().on_cyan()),
-                    ChoiceColor::White => print!("{}", prompt.to_string().on_white()),
-                },
-                Choice::Bright(color) => match color {
-                    ChoiceColor::Black => print!("{}", prompt.to_string().bright_black()),
-                    ChoiceColor::Red => print!("{}", prompt.to_string().bright_red()),
-                    ChoiceColor::Green => print!("{}", prompt.to_string().bright_green()),
-                    ChoiceColor::Yellow => print!("{}", prompt.to_string().bright_yellow()),
-                    ChoiceColor::Blue => print!("{}", prompt.to_string().bright_blue()),
-                    ChoiceColor::Magenta => print!("{}", prompt.to_string().bright_magenta()),
-                    ChoiceColor::Cyan => print!("{}", prompt.to_string().bright_cyan()),
-                    ChoiceColor::White => print!("{}", prompt.to_string().bright_white()),
-                },
-                Choice::OnBright(color) => match color {
-                    ChoiceColor::Black => print!("{}", prompt.to_string().on_bright_black()),
-                    ChoiceColor::Red => print!("{}", prompt.to_string().on_bright_red()),
-                    ChoiceColor::Green => print!("{}", prompt.to_string().on_bright_green()),
-                    ChoiceColor::Yellow => print!("{}", prompt.to_string().on_bright_yellow()),
-                    ChoiceColor::Blue => print!("{}", prompt.to_string().on_bright_blue()),
-                    ChoiceColor::Magenta => print!("{}", prompt.to_string().on_bright_magenta()),
-                    ChoiceColor::Cyan => print!("{}", prompt.to_string().on_bright_cyan()),
-                    ChoiceColor::White => print!("{}", prompt.to_string().on_bright_white()),
-                },
-            },
-            None => {
-                print!("{}", prompt.trim())
-            }
-        }
-
-        stdout().flush().expect("Flushing line failed.");
-        input.clear();
-        std::io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line.");
-        input = input.trim().to_string();
-        if input.eq_ignore_ascii_case("y") || input.eq_ignore_ascii_case("n") || input.is_empty() {
-            break;
-        }
-    }
+    try_prompt_tf_default(prompt, colored, default).expect("Failed to read line.")
+}
 
-    // Loop cannot have exited w/o input being valid.
-    match input.as_str() {
-        "Y" | "y" => true,
-        "N" | "n" => false,
-        _ => default,
-    }
+/// Like [`prompt_tf_default`], but returns a [`PromptError`] instead of panicking.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// if try_prompt_tf_default("Delete everything? (y/N) >>> ", None, false)? {
+///     println!("Okay, deleting everything.");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn try_prompt_tf_default(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: bool,
+) -> Result<bool, PromptError> {
+    let prompt = match colored {
+        Some(_) => prompt,
+        None => prompt.trim(),
+    };
+    PromptBuilder::new(prompt, Layout::Verbatim, builder::parse_tf)
+        .colored(colored)
+        .default(default)
+        .ask()
 }
 
 /// Prompts for a selection given a prompt, list of choices, color option, and default value.
@@ -258,7 +447,8 @@ pub fn prompt_tf_default(prompt: &str, colored: Option<Choice>, default: bool) -
 ///
 /// # Examples
 ///
-/// ```rust
+/// ```rust,no_run
+/// # use cumaea::*;
 /// let the_string = prompt_selection(
 ///     "Choose something",
 ///     "(a)pples, (b)ananas, (c)arrots, (D)oughnuts",
@@ -279,111 +469,333 @@ pub fn prompt_tf_default(prompt: &str, colored: Option<Choice>, default: bool) -
 ///
 /// # Panics
 ///
-/// Panics on failure of `stdin().read_line()` or `stdout().flush()`.
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. See
+/// [`try_prompt_selection`] for a version that doesn't.
 pub fn prompt_selection(
     prompt: &str,
     list: &str,
     colored: Option<Choice>,
     default: &str,
 ) -> String {
-    let mut input = String::new();
-    match colored {
-        Some(ref color_choice) => match color_choice {
-            Choice::Normal(color) => match color {
-                ChoiceColor::Black => {
-                    print!("{}: [{}]: ", prompt, list.to_string().black())
-                }
-                ChoiceColor::Red => {
-                    print!("{}: [{}]: ", prompt, list.to_string().red())
-                }
-                ChoiceColor::Green => {
-                    print!("{}: [{}]: ", prompt, list.to_string().green())
-                }
-                ChoiceColor::Yellow => {
-                    print!("{}: [{}]: ", prompt, list.to_string().yellow())
-                }
-                ChoiceColor::Blue => {
-                    print!("{}: [{}]: ", prompt, list.to_string().blue())
-                }
-                ChoiceColor::Magenta => {
-                    print!("{}: [{}]: ", prompt, list.to_string().magenta())
-                }
-                ChoiceColor::Cyan => {
-                    print!("{}: [{}]: ", prompt, list.to_string().cyan())
-                }
-                ChoiceColor::White => {
-                    print!("{}: [{}]: ", prompt, list.to_string().white())
-                }
-            },
-            Choice::On(color) => match color {
-                ChoiceColor::Black => print!("{}: [{}]: ", prompt, list.to_string().on_black()),
-                ChoiceColor::Red => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_red())
-                }
-                ChoiceColor::Green => print!("{}: [{}]: ", prompt, list.to_string().on_green()),
-                ChoiceColor::Yellow => print!("{}: [{}]: ", prompt, list.to_string().on_yellow()),
-                ChoiceColor::Blue => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_blue())
-                }
-                ChoiceColor::Magenta => print!("{}: [{}]: ", prompt, list.to_string().on_magenta()),
-                ChoiceColor::Cyan => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_cyan())
-                }
-                ChoiceColor::White => print!("{}: [{}]: ", prompt, list.to_string().on_white()),
-            },
-            Choice::Bright(color) => match color {
-                ChoiceColor::Black => print!("{}: [{}]: ", prompt, list.to_string().bright_black()),
-                ChoiceColor::Red => print!("{}: [{}]: ", prompt, list.to_string().bright_red()),
-                ChoiceColor::Green => print!("{}: [{}]: ", prompt, list.to_string().bright_green()),
-                ChoiceColor::Yellow => {
-                    print!("{}: [{}]: ", prompt, list.to_string().bright_yellow())
-                }
-                ChoiceColor::Blue => print!("{}: [{}]: ", prompt, list.to_string().bright_blue()),
-                ChoiceColor::Magenta => {
-                    print!("{}: [{}]: ", prompt, list.to_string().bright_magenta())
-                }
-                ChoiceColor::Cyan => print!("{}: [{}]: ", prompt, list.to_string().bright_cyan()),
-                ChoiceColor::White => print!("{}: [{}]: ", prompt, list.to_string().bright_white()),
-            },
-            Choice::OnBright(color) => match color {
-                ChoiceColor::Black => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_black())
-                }
-                ChoiceColor::Red => print!("{}: [{}]: ", prompt, list.to_string().on_bright_red()),
-                ChoiceColor::Green => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_green())
-                }
-                ChoiceColor::Yellow => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_yellow())
-                }
-                ChoiceColor::Blue => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_blue())
-                }
-                ChoiceColor::Magenta => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_magenta())
-                }
-                ChoiceColor::Cyan => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_cyan())
-                }
-                ChoiceColor::White => {
-                    print!("{}: [{}]: ", prompt, list.to_string().on_bright_white())
-                }
-            },
-        },
-        None => {
-            print!("{}: [{}]: ", prompt.trim(), list.trim())
-        }
+    try_prompt_selection(prompt, list, colored, default).expect("Failed to read line.")
+}
+
+/// Like [`prompt_selection`], but returns a [`PromptError`] instead of panicking.
+pub fn try_prompt_selection(
+    prompt: &str,
+    list: &str,
+    colored: Option<Choice>,
+    default: &str,
+) -> Result<String, PromptError> {
+    let (prompt, list) = match colored {
+        Some(_) => (prompt, list),
+        None => (prompt.trim(), list.trim()),
+    };
+    Prompt::selection(prompt, list)
+        .colored(colored)
+        .default(default.to_string())
+        .ask()
+}
+
+/// Prompts for any value that implements [`FromStr`], given a prompt, color option,
+/// and an optional default. Loops until the input parses, printing
+/// `Invalid input, please try again.` after each failure.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # use std::net::IpAddr;
+/// let port: u16 = prompt_parse("Port", Some(Choice::Normal(ChoiceColor::Cyan)), Some(8080));
+/// let addr: IpAddr = prompt_parse("Address", None, None);
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. To change the
+/// error message, use [`Prompt::parse`] with
+/// [`error_message`](PromptBuilder::error_message).
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::parse`] returns a [`PromptError`] instead.
+pub fn prompt_parse<T: FromStr + Clone + 'static>(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<T>,
+) -> T {
+    let mut builder = Prompt::parse(prompt).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
     }
+    builder.ask().expect("Failed to read line.")
+}
 
-    stdout().flush().expect("Flushing line failed.");
-    input.clear();
-    std::io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read line.");
+/// Prompts for a secret given a prompt and color option. Terminal echo is turned off
+/// while typing, so nothing is displayed; Backspace and Ctrl+U still work.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let password = prompt_password("Password", Some(Choice::Normal(ChoiceColor::Yellow)));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. The answer is
+/// returned exactly as typed, without trimming. If stdin isn't a terminal, a line
+/// is read from it as usual.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::password`] returns a [`PromptError`] instead.
+pub fn prompt_password(prompt: &str, colored: Option<Choice>) -> String {
+    Prompt::password(prompt)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read password.")
+}
+
+/// Like [`prompt_password`], but the secret comes back in a [`Zeroizing`] wrapper
+/// that scrubs it from memory when dropped.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let token = prompt_secret("API token", None);
+/// send_token(&token);
+/// // `token` is zeroed here.
+/// # fn send_token(_: &str) {}
+/// ```
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::secret`] returns a [`PromptError`] instead.
+#[cfg(feature = "zeroize")]
+pub fn prompt_secret(prompt: &str, colored: Option<Choice>) -> Zeroizing<String> {
+    Prompt::secret(prompt)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read password.")
+}
+
+/// Prompts for any number of items from a list, given a prompt, the items, and
+/// which of them start out checked. Returns the indices of the checked items.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let toppings = ["cheese", "mushrooms", "olives", "pineapple"];
+/// let picked = prompt_multi_select(
+///     "Pick your toppings",
+///     &toppings,
+///     &[true, false, false, false],
+/// );
+/// for i in picked {
+///     println!("Adding {}", toppings[i]);
+/// }
+/// ```
+///
+/// Move with the arrow keys, toggle with Space, and confirm with Enter. If stdin
+/// or stderr isn't a terminal, a numbered list is printed and the answer is read
+/// as a line like `1, 3`.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::multi_select`] returns a [`PromptError`] instead.
+pub fn prompt_multi_select(prompt: &str, items: &[&str], defaults: &[bool]) -> Vec<usize> {
+    Prompt::multi_select(prompt, items)
+        .defaults(defaults)
+        .ask()
+        .expect("Failed to read selection.")
+}
+
+/// Prompts for the order of a list of items, given a prompt and the items, like for
+/// setting priorities. Returns the indices of the items, first to last.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let goals = ["speed", "cost", "reliability"];
+/// let order = prompt_ranking("Rank these by importance", &goals);
+/// println!("Most important: {}", goals[order[0]]);
+/// ```
+///
+/// Move with the arrow keys, pick an item up and put it down with Space (or move it
+/// straight away with Shift+K and Shift+J), and confirm with Enter. If stdin or
+/// stderr isn't a terminal, a numbered list is printed and the order is read as a
+/// line like `3, 1`, with any items left out kept after the rest.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::ranking`] returns a [`PromptError`] instead.
+pub fn prompt_ranking(prompt: &str, items: &[&str]) -> Vec<usize> {
+    Prompt::ranking(prompt, items)
+        .ask()
+        .expect("Failed to read ranking.")
+}
+
+/// Prompts for one item from a list, given a prompt, the items, color option, and
+/// the index highlighted to start with. Returns the index of the picked item.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let fruits = ["apples", "bananas", "carrots", "doughnuts"];
+/// let picked = prompt_menu(
+///     "Choose something",
+///     &fruits,
+///     Some(Choice::Normal(ChoiceColor::Cyan)),
+///     3,
+/// );
+/// println!("You chose {}", fruits[picked]);
+/// ```
+///
+/// Move the highlight with the arrow keys and pick with Enter. This is an
+/// alternative to the letter-based [`prompt_selection`]. If stdin or stderr isn't
+/// a terminal, a numbered list is printed and the answer is read as a number.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if `items`
+/// is empty. [`Prompt::menu`] returns a [`PromptError`] instead.
+pub fn prompt_menu(prompt: &str, items: &[&str], colored: Option<Choice>, default: usize) -> usize {
+    let mut menu = Prompt::menu(prompt, items).default(default);
+    if let Some(color) = colored {
+        menu = menu.color(color);
+    }
+    menu.ask().expect("Failed to read selection.")
+}
+
+/// Prompts for one item from a long list, given a prompt, the items, and color
+/// option. Typing filters the list with fuzzy matching. Returns the index of the
+/// picked item.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let branches = ["main", "feature/login-page", "fix/login-timeout", "release/1.2"];
+/// let picked = prompt_fuzzy_select("Branch", &branches, None);
+/// println!("Checking out {}", branches[picked]);
+/// ```
+///
+/// Typing `lgn` would narrow the list down to `feature/login-page` and
+/// `fix/login-timeout`. At most ten matches are shown at once; the arrow keys
+/// scroll through the rest. If stdin or stderr isn't a terminal, a line is read
+/// as the search and the best match is picked.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if `items`
+/// is empty. [`Prompt::fuzzy_select`] returns a [`PromptError`] instead.
+pub fn prompt_fuzzy_select(prompt: &str, items: &[&str], colored: Option<Choice>) -> usize {
+    let mut select = Prompt::fuzzy_select(prompt, items);
+    if let Some(color) = colored {
+        select = select.color(color);
+    }
+    select.ask().expect("Failed to read selection.")
+}
+
+/// Prompts for a whole number given a prompt, color option, optional default, and
+/// optional range. Loops until the input is an integer inside the range, saying
+/// which of the two it wasn't.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let port = prompt_int("Port", None, Some(8080), Some(1..=65535));
+/// let offset = prompt_int("Offset", None, None, None);
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. An answer of
+/// `70000` for the port above prints `Please enter a value from 1 to 65535.`
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::int`]
+/// returns a [`PromptError`] instead.
+pub fn prompt_int(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<i64>,
+    range: Option<RangeInclusive<i64>>,
+) -> i64 {
+    let mut builder = Prompt::int(prompt).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    if let Some(range) = range {
+        builder = builder.range(range);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+/// Prompts for a floating-point number given a prompt, color option, optional
+/// default, optional range, and optional number of decimal places to round to.
+/// Loops until the input is a finite number inside the range.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let ratio = prompt_float("Ratio", None, Some(0.5), Some(0.0..=1.0), None);
+/// let price = prompt_float("Price", None, None, None, Some(2));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. Rounding happens
+/// before the range is checked. For a minimum or maximum alone, or to reject extra
+/// decimal places instead of rounding them, use [`Prompt::float`].
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::float`] returns a [`PromptError`] instead.
+pub fn prompt_float(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<f64>,
+    range: Option<RangeInclusive<f64>>,
+    decimals: Option<u32>,
+) -> f64 {
+    let mut builder = Prompt::float(prompt).colored(colored);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    if let Some(decimals) = decimals {
+        builder = builder.round_to(decimals);
+    }
+    if let Some(range) = range {
+        builder = builder.range(range);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn basic_colors_have_their_own_codes() {
+        assert_eq!(Choice::Normal(ChoiceColor::Red).code(), "31");
+        assert_eq!(Choice::On(ChoiceColor::Red).code(), "41");
+        assert_eq!(Choice::Bright(ChoiceColor::Black).code(), "90");
+        assert_eq!(Choice::OnBright(ChoiceColor::Red).code(), "101");
+    }
 
-    if input.trim().is_empty() {
-        default.to_string()
-    } else {
-        input.trim().to_string()
+    #[test]
+    fn ansi256_colors_are_sent_by_index() {
+        let orange = ChoiceColor::Ansi256(208);
+        assert_eq!(Choice::Normal(orange).code(), "38;5;208");
+        assert_eq!(Choice::On(orange).code(), "48;5;208");
+        assert_eq!(Choice::Bright(orange).code(), "38;5;208");
+        assert_eq!(Choice::OnBright(ChoiceColor::Ansi256(0)).code(), "48;5;0");
     }
 }
@@ -0,0 +1,95 @@
+//! Prompts for several values typed on one line, like tags or host names.
+
+use crate::builder::Layout;
+use crate::{Choice, Prompt, PromptBuilder};
+
+impl Prompt {
+    /// Values typed on one line, like [`prompt_list`](crate::prompt_list). They're
+    /// separated by commas or whitespace unless [`delimiter`](PromptBuilder::delimiter)
+    /// says otherwise, and an empty answer is an empty list.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let emails = Prompt::list("Reviewers' emails")
+    ///     .validate_each(|email| match email.contains('@') {
+    ///         true => Ok(()),
+    ///         false => Err(format!("{} isn't an email address.", email)),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list(prompt: &str) -> PromptBuilder<Vec<String>> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            Ok(split(input, |c| c == ',' || c.is_whitespace()))
+        })
+    }
+}
+
+impl PromptBuilder<Vec<String>> {
+    /// Separates the values by `delimiter` alone, in place of commas and
+    /// whitespace, so they can hold spaces. Space around each value is trimmed.
+    /// This replaces the splitting along with any checks added with
+    /// [`validate_each`](PromptBuilder::validate_each) before it.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.parser = Box::new(move |input| Ok(split(input, |c| c == delimiter)));
+        self
+    }
+
+    /// Adds a check on each value in turn. If it returns an error for any of them,
+    /// the message is printed and the prompt asks again.
+    pub fn validate_each(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let values = parser(input)?;
+            values.iter().try_for_each(|value| validator(value))?;
+            Ok(values)
+        });
+        self
+    }
+}
+
+/// The values in `input` between the characters matching `delimiter`, trimmed,
+/// leaving out empty ones.
+fn split(input: &str, delimiter: impl Fn(char) -> bool) -> Vec<String> {
+    input
+        .split(delimiter)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Prompts for a list of values on one line given a prompt, color option, and
+/// optional delimiter, like for labels, email addresses or hosts.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let hosts = prompt_list("Hosts to deploy to", None, None);
+/// let names = prompt_list("Full names", None, Some(';'));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. Without a delimiter,
+/// values are separated by commas or whitespace, so `web1, web2 web3` is three
+/// hosts. With one, only it separates them. Empty values are dropped, and an empty
+/// answer returns an empty list. [`Prompt::list`] can also check each value.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::list`] returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_list(prompt: &str, colored: Option<Choice>, delimiter: Option<char>) -> Vec<String> {
+    let mut builder = Prompt::list(prompt).colored(colored);
+    if let Some(delimiter) = delimiter {
+        builder = builder.delimiter(delimiter);
+    }
+    builder.ask().expect("Failed to read line.")
+}
@@ -0,0 +1,630 @@
+//! The text prompts show around what the caller gives them, like the messages
+//! for rejected answers and the hints after a menu's question, in one table per
+//! language.
+
+use crate::YesNo;
+use std::fmt::Display;
+use std::sync::{Arc, OnceLock, PoisonError, RwLock};
+
+/// The text prompts show on their own, in one language.
+///
+/// Each field is a template, where a name in braces, like `{min}`, is filled in
+/// with the value it names. The questions, items and messages given to a prompt
+/// are shown as they are, and [`PromptError`](crate::PromptError)s, which are for
+/// the program rather than the person answering, stay in English.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_locale(Locale::german());
+/// // Anything but a number is answered with "Bitte gib eine ganze Zahl ein."
+/// let port = Prompt::int("Port").ask();
+///
+/// set_locale(Locale {
+///     summary: "Overview".to_string(),
+///     ..Locale::english()
+/// });
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Locale {
+    /// A number was expected.
+    pub number: String,
+    /// A whole number was expected.
+    pub whole_number: String,
+    /// A whole number from `{min}` to `{max}` was expected.
+    pub whole_number_between: String,
+    /// The answer couldn't be read as what was asked for.
+    pub invalid: String,
+    /// The answer is outside `{min}` to `{max}`.
+    pub between: String,
+    /// The answer is under `{min}`.
+    pub at_least: String,
+    /// The answer is over `{max}`.
+    pub at_most: String,
+    /// The answer has more than `{count}` decimals.
+    pub decimals: String,
+    /// The answer and its confirmation differ.
+    pub mismatch: String,
+    /// A single character was expected.
+    pub character: String,
+    /// The answer is shorter than `{count}` characters.
+    pub min_length: String,
+    /// The answer is longer than `{count}` characters.
+    pub max_length: String,
+    /// The answer doesn't match `{pattern}`.
+    pub pattern: String,
+    /// A numbered menu's answer isn't from 1 to `{count}`.
+    pub pick_number: String,
+    /// A numbered list's answer isn't numbers from 1 to `{count}`.
+    pub pick_numbers: String,
+    /// A search for `{query}` found nothing.
+    pub nothing_matches: String,
+    /// A yes/no question's answer is neither `{yes}` nor `{no}`.
+    pub yes_or_no: String,
+    /// The answer is none of `{yes}`, `{no}` and `{cancel}`.
+    pub yes_no_or_cancel: String,
+    /// The answer is none of `{yes}`, `{no}`, `{all}` and `{never}`.
+    pub yes_no_all_never: String,
+    /// A path was expected.
+    pub path: String,
+    /// `{path}` doesn't exist.
+    pub missing: String,
+    /// `{path}` isn't a directory.
+    pub not_directory: String,
+    /// `{path}` isn't a file.
+    pub not_file: String,
+    /// `{path}` couldn't be created, because of `{error}`.
+    pub create_failed: String,
+    /// A duration was expected.
+    pub duration: String,
+    /// A date like `{example}` was expected.
+    pub date: String,
+    /// A time of day was expected.
+    pub time: String,
+    /// An IP address was expected.
+    pub ip: String,
+    /// An address of `{version}`, `IPv4` or `IPv6`, was expected.
+    pub ip_version: String,
+    /// A network was expected.
+    pub network: String,
+    /// A network of `{version}`, `IPv4` or `IPv6`, was expected.
+    pub network_version: String,
+    /// The answer isn't a URL, because of `{error}`.
+    pub url: String,
+    /// A URL starting with one of `{schemes}` was expected.
+    pub url_scheme: String,
+    /// The word between the last two things in a list of options.
+    pub or: String,
+    /// A `key{separator}value` entry was expected.
+    pub pair: String,
+    /// An item of a list isn't a `{kind}` from a JSON Schema.
+    pub invalid_item: String,
+    /// After the question asking for `key{separator}value` entries.
+    pub pairs_hint: String,
+    /// After the question asking for several lines.
+    pub lines_hint: String,
+    /// After the question while an external editor is open.
+    pub editor_hint: String,
+    /// After the question asking for `{phrase}` to be typed.
+    pub phrase_hint: String,
+    /// After the question while `{seconds}` count down.
+    pub countdown: String,
+    /// Next to the spinner while a slow check runs on an answer.
+    pub checking: String,
+    /// After a menu's question.
+    pub menu_hint: String,
+    /// After a checkbox list's question.
+    pub multi_select_hint: String,
+    /// After a ranking's question.
+    pub ranking_hint: String,
+    /// After the item being moved in a ranking.
+    pub moving: String,
+    /// After a tree's question.
+    pub tree_hint: String,
+    /// Asks for an item's number in a numbered menu.
+    pub enter_number: String,
+    /// Asks for the numbers of the checked items in a numbered list.
+    pub enter_numbers: String,
+    /// Asks for a numbered ranking's order.
+    pub enter_order: String,
+    /// Under a long menu, on page `{page}` of `{pages}`.
+    pub page: String,
+    /// In place of the items when a search finds none.
+    pub no_matches: String,
+    /// Over a wizard's step `{number}` of `{total}`.
+    pub step: String,
+    /// Over the answers a wizard reviews.
+    pub summary: String,
+    /// In place of a secret in a wizard's summary.
+    pub hidden: String,
+    /// Asks whether a wizard's answers are right.
+    pub answers_right: String,
+    /// Asks which of a wizard's answers to change.
+    pub change_answer: String,
+    /// A yes in a wizard's summary.
+    pub yes: String,
+    /// A no in a wizard's summary.
+    pub no: String,
+    /// After the question for something that can be left out.
+    pub optional: String,
+    /// The item that leaves out a pick that's optional.
+    pub none: String,
+    /// Asks whether to fill in `{label}`, which is optional.
+    pub set_value: String,
+    /// Asks whether to add another item to `{label}`.
+    pub add_item: String,
+    /// What number prompts take between the whole part of a number and its
+    /// fraction, in place of `.`.
+    pub decimal_separator: char,
+    /// What number prompts take between groups of three digits, like the `,` in
+    /// `1,234`, if anything. A space here takes no-break spaces too.
+    pub group_separator: Option<char>,
+    /// The words yes/no questions take, unless others are set with
+    /// [`set_yes_no`](crate::set_yes_no).
+    pub yes_no: YesNo,
+}
+
+impl Default for Locale {
+    fn default() -> Self {
+        Locale::english()
+    }
+}
+
+impl Locale {
+    /// English, the default.
+    pub fn english() -> Self {
+        Locale {
+            number: "Please enter a number.".to_string(),
+            whole_number: "Please enter a whole number.".to_string(),
+            whole_number_between: "Please enter a whole number from {min} to {max}.".to_string(),
+            invalid: "Invalid input, please try again.".to_string(),
+            between: "Please enter a value from {min} to {max}.".to_string(),
+            at_least: "Please enter a value of at least {min}.".to_string(),
+            at_most: "Please enter a value of at most {max}.".to_string(),
+            decimals: "Please enter at most {count} digits after the decimal point.".to_string(),
+            mismatch: "The entries didn't match, please try again.".to_string(),
+            character: "Please enter a single character.".to_string(),
+            min_length: "Please enter at least {count} characters.".to_string(),
+            max_length: "Please enter at most {count} characters.".to_string(),
+            pattern: "Please enter something matching {pattern}.".to_string(),
+            pick_number: "Please enter a number from 1 to {count}.".to_string(),
+            pick_numbers: "Please enter numbers from 1 to {count}.".to_string(),
+            nothing_matches: "Nothing matches \"{query}\", please try again.".to_string(),
+            yes_or_no: "Please answer {yes} or {no}.".to_string(),
+            yes_no_or_cancel: "Please answer {yes}, {no} or {cancel}.".to_string(),
+            yes_no_all_never: "Please answer {yes}, {no}, {all} (all) or {never} (never)."
+                .to_string(),
+            path: "Please enter a path.".to_string(),
+            missing: "{path} doesn't exist.".to_string(),
+            not_directory: "{path} isn't a directory.".to_string(),
+            not_file: "{path} isn't a file.".to_string(),
+            create_failed: "Couldn't create {path}: {error}.".to_string(),
+            duration: "Please enter a duration like 90s, 1h30m or 2d.".to_string(),
+            date: "Please enter a date like {example}.".to_string(),
+            time: "Please enter a time like 09:30 or 17:45:00.".to_string(),
+            ip: "Please enter an IP address, like 192.168.1.10 or ::1.".to_string(),
+            ip_version: "Please enter an {version} address.".to_string(),
+            network: "Please enter a network, like 10.0.0.0/8 or fd00::/64.".to_string(),
+            network_version: "Please enter an {version} network.".to_string(),
+            url: "That isn't a valid URL: {error}.".to_string(),
+            url_scheme: "Please enter a URL starting with {schemes}.".to_string(),
+            or: "or".to_string(),
+            pair: "Please enter an entry like key{separator}value.".to_string(),
+            invalid_item: "{item} isn't a valid {kind}.".to_string(),
+            pairs_hint: "(key{separator}value, empty to finish)".to_string(),
+            lines_hint: "(end with a lone . or Ctrl+D):".to_string(),
+            editor_hint: "(waiting for the editor to close the file)".to_string(),
+            phrase_hint: "(type {phrase} to confirm)".to_string(),
+            countdown: "(continuing in {seconds})".to_string(),
+            checking: "Checking…".to_string(),
+            menu_hint: "(Enter to pick)".to_string(),
+            multi_select_hint: "(Space to toggle, Enter to confirm)".to_string(),
+            ranking_hint: "(Space to move, Enter to confirm)".to_string(),
+            moving: "(moving)".to_string(),
+            tree_hint: "(Enter to open or pick)".to_string(),
+            enter_number: "Enter a number".to_string(),
+            enter_numbers: "Enter numbers separated by spaces or commas".to_string(),
+            enter_order: "Enter the numbers in order, first to last".to_string(),
+            page: "page {page}/{pages}".to_string(),
+            no_matches: "(no matches)".to_string(),
+            step: "Step {number} of {total}".to_string(),
+            summary: "Summary".to_string(),
+            hidden: "(hidden)".to_string(),
+            answers_right: "Are these answers right?".to_string(),
+            change_answer: "Which answer do you want to change?".to_string(),
+            yes: "yes".to_string(),
+            no: "no".to_string(),
+            optional: "(optional)".to_string(),
+            none: "(none)".to_string(),
+            set_value: "Set {label}?".to_string(),
+            add_item: "Add an item to {label}?".to_string(),
+            decimal_separator: '.',
+            group_separator: Some(','),
+            yes_no: YesNo::new(&["y"], &["n"]),
+        }
+    }
+
+    /// German, answering yes/no questions with `j` or `n`.
+    pub fn german() -> Self {
+        Locale {
+            number: "Bitte gib eine Zahl ein.".to_string(),
+            whole_number: "Bitte gib eine ganze Zahl ein.".to_string(),
+            whole_number_between: "Bitte gib eine ganze Zahl von {min} bis {max} ein.".to_string(),
+            invalid: "Ungültige Eingabe, bitte versuche es noch einmal.".to_string(),
+            between: "Bitte gib einen Wert von {min} bis {max} ein.".to_string(),
+            at_least: "Bitte gib einen Wert von mindestens {min} ein.".to_string(),
+            at_most: "Bitte gib einen Wert von höchstens {max} ein.".to_string(),
+            decimals: "Bitte gib höchstens {count} Nachkommastellen ein.".to_string(),
+            mismatch: "Die Eingaben stimmen nicht überein, bitte versuche es noch einmal."
+                .to_string(),
+            character: "Bitte gib ein einzelnes Zeichen ein.".to_string(),
+            min_length: "Bitte gib mindestens {count} Zeichen ein.".to_string(),
+            max_length: "Bitte gib höchstens {count} Zeichen ein.".to_string(),
+            pattern: "Bitte gib etwas ein, das zu {pattern} passt.".to_string(),
+            pick_number: "Bitte gib eine Zahl von 1 bis {count} ein.".to_string(),
+            pick_numbers: "Bitte gib Zahlen von 1 bis {count} ein.".to_string(),
+            nothing_matches: "Nichts passt zu \"{query}\", bitte versuche es noch einmal."
+                .to_string(),
+            yes_or_no: "Bitte antworte mit {yes} oder {no}.".to_string(),
+            yes_no_or_cancel: "Bitte antworte mit {yes}, {no} oder {cancel}.".to_string(),
+            yes_no_all_never: "Bitte antworte mit {yes}, {no}, {all} (alle) oder {never} (nie)."
+                .to_string(),
+            path: "Bitte gib einen Pfad ein.".to_string(),
+            missing: "{path} existiert nicht.".to_string(),
+            not_directory: "{path} ist kein Verzeichnis.".to_string(),
+            not_file: "{path} ist keine Datei.".to_string(),
+            create_failed: "{path} konnte nicht erstellt werden: {error}.".to_string(),
+            duration: "Bitte gib eine Dauer wie 90s, 1h30m oder 2d ein.".to_string(),
+            date: "Bitte gib ein Datum wie {example} ein.".to_string(),
+            time: "Bitte gib eine Uhrzeit wie 09:30 oder 17:45:00 ein.".to_string(),
+            ip: "Bitte gib eine IP-Adresse ein, z. B. 192.168.1.10 oder ::1.".to_string(),
+            ip_version: "Bitte gib eine {version}-Adresse ein.".to_string(),
+            network: "Bitte gib ein Netzwerk ein, z. B. 10.0.0.0/8 oder fd00::/64.".to_string(),
+            network_version: "Bitte gib ein {version}-Netzwerk ein.".to_string(),
+            url: "Das ist keine gültige URL: {error}.".to_string(),
+            url_scheme: "Bitte gib eine URL ein, die mit {schemes} beginnt.".to_string(),
+            or: "oder".to_string(),
+            pair: "Bitte gib einen Eintrag wie Schlüssel{separator}Wert ein.".to_string(),
+            invalid_item: "{item} ist kein gültiger Wert vom Typ {kind}.".to_string(),
+            pairs_hint: "(Schlüssel{separator}Wert, leer zum Beenden)".to_string(),
+            lines_hint: "(mit einem einzelnen . oder Strg+D beenden):".to_string(),
+            editor_hint: "(warte, bis der Editor die Datei schließt)".to_string(),
+            phrase_hint: "(zum Bestätigen {phrase} eingeben)".to_string(),
+            countdown: "(weiter in {seconds})".to_string(),
+            checking: "Wird geprüft …".to_string(),
+            menu_hint: "(Enter zum Auswählen)".to_string(),
+            multi_select_hint: "(Leertaste zum Umschalten, Enter zum Bestätigen)".to_string(),
+            ranking_hint: "(Leertaste zum Verschieben, Enter zum Bestätigen)".to_string(),
+            moving: "(wird verschoben)".to_string(),
+            tree_hint: "(Enter zum Öffnen oder Auswählen)".to_string(),
+            enter_number: "Gib eine Zahl ein".to_string(),
+            enter_numbers: "Gib Zahlen ein, getrennt durch Leerzeichen oder Kommas".to_string(),
+            enter_order: "Gib die Zahlen der Reihe nach ein, von der ersten bis zur letzten"
+                .to_string(),
+            page: "Seite {page}/{pages}".to_string(),
+            no_matches: "(keine Treffer)".to_string(),
+            step: "Schritt {number} von {total}".to_string(),
+            summary: "Zusammenfassung".to_string(),
+            hidden: "(verborgen)".to_string(),
+            answers_right: "Sind diese Antworten richtig?".to_string(),
+            change_answer: "Welche Antwort möchtest du ändern?".to_string(),
+            yes: "ja".to_string(),
+            no: "nein".to_string(),
+            optional: "(optional)".to_string(),
+            none: "(keine)".to_string(),
+            set_value: "{label} festlegen?".to_string(),
+            add_item: "Einen Eintrag zu {label} hinzufügen?".to_string(),
+            decimal_separator: ',',
+            group_separator: Some('.'),
+            yes_no: YesNo::new(&["j", "ja"], &["n", "nein"]).batch(&["a", "alle"], &["v", "nie"]),
+        }
+    }
+
+    /// French, answering yes/no questions with `o` or `n`.
+    pub fn french() -> Self {
+        Locale {
+            number: "Veuillez saisir un nombre.".to_string(),
+            whole_number: "Veuillez saisir un nombre entier.".to_string(),
+            whole_number_between: "Veuillez saisir un nombre entier entre {min} et {max}."
+                .to_string(),
+            invalid: "Saisie invalide, veuillez réessayer.".to_string(),
+            between: "Veuillez saisir une valeur entre {min} et {max}.".to_string(),
+            at_least: "Veuillez saisir une valeur d'au moins {min}.".to_string(),
+            at_most: "Veuillez saisir une valeur d'au plus {max}.".to_string(),
+            decimals: "Veuillez saisir au plus {count} chiffres après la virgule.".to_string(),
+            mismatch: "Les saisies ne correspondent pas, veuillez réessayer.".to_string(),
+            character: "Veuillez saisir un seul caractère.".to_string(),
+            min_length: "Veuillez saisir au moins {count} caractères.".to_string(),
+            max_length: "Veuillez saisir au plus {count} caractères.".to_string(),
+            pattern: "Veuillez saisir une valeur correspondant à {pattern}.".to_string(),
+            pick_number: "Veuillez saisir un nombre entre 1 et {count}.".to_string(),
+            pick_numbers: "Veuillez saisir des nombres entre 1 et {count}.".to_string(),
+            nothing_matches: "Rien ne correspond à « {query} », veuillez réessayer.".to_string(),
+            yes_or_no: "Veuillez répondre {yes} ou {no}.".to_string(),
+            yes_no_or_cancel: "Veuillez répondre {yes}, {no} ou {cancel}.".to_string(),
+            yes_no_all_never: "Veuillez répondre {yes}, {no}, {all} (tous) ou {never} (jamais)."
+                .to_string(),
+            path: "Veuillez saisir un chemin.".to_string(),
+            missing: "{path} n'existe pas.".to_string(),
+            not_directory: "{path} n'est pas un dossier.".to_string(),
+            not_file: "{path} n'est pas un fichier.".to_string(),
+            create_failed: "Impossible de créer {path} : {error}.".to_string(),
+            duration: "Veuillez saisir une durée comme 90s, 1h30m ou 2d.".to_string(),
+            date: "Veuillez saisir une date comme {example}.".to_string(),
+            time: "Veuillez saisir une heure comme 09:30 ou 17:45:00.".to_string(),
+            ip: "Veuillez saisir une adresse IP, comme 192.168.1.10 ou ::1.".to_string(),
+            ip_version: "Veuillez saisir une adresse {version}.".to_string(),
+            network: "Veuillez saisir un réseau, comme 10.0.0.0/8 ou fd00::/64.".to_string(),
+            network_version: "Veuillez saisir un réseau {version}.".to_string(),
+            url: "Ce n'est pas une URL valide : {error}.".to_string(),
+            url_scheme: "Veuillez saisir une URL commençant par {schemes}.".to_string(),
+            or: "ou".to_string(),
+            pair: "Veuillez saisir une entrée comme clé{separator}valeur.".to_string(),
+            invalid_item: "{item} n'est pas un {kind} valide.".to_string(),
+            pairs_hint: "(clé{separator}valeur, vide pour terminer)".to_string(),
+            lines_hint: "(terminez par un . seul ou Ctrl+D) :".to_string(),
+            editor_hint: "(en attente de la fermeture du fichier par l'éditeur)".to_string(),
+            phrase_hint: "(tapez {phrase} pour confirmer)".to_string(),
+            countdown: "(suite dans {seconds})".to_string(),
+            checking: "Vérification…".to_string(),
+            menu_hint: "(Entrée pour choisir)".to_string(),
+            multi_select_hint: "(Espace pour cocher, Entrée pour valider)".to_string(),
+            ranking_hint: "(Espace pour déplacer, Entrée pour valider)".to_string(),
+            moving: "(en déplacement)".to_string(),
+            tree_hint: "(Entrée pour ouvrir ou choisir)".to_string(),
+            enter_number: "Saisissez un nombre".to_string(),
+            enter_numbers: "Saisissez des nombres séparés par des espaces ou des virgules"
+                .to_string(),
+            enter_order: "Saisissez les nombres dans l'ordre, du premier au dernier".to_string(),
+            page: "page {page}/{pages}".to_string(),
+            no_matches: "(aucun résultat)".to_string(),
+            step: "Étape {number} sur {total}".to_string(),
+            summary: "Récapitulatif".to_string(),
+            hidden: "(masqué)".to_string(),
+            answers_right: "Ces réponses sont-elles correctes ?".to_string(),
+            change_answer: "Quelle réponse voulez-vous modifier ?".to_string(),
+            yes: "oui".to_string(),
+            no: "non".to_string(),
+            optional: "(facultatif)".to_string(),
+            none: "(aucun)".to_string(),
+            set_value: "Définir {label} ?".to_string(),
+            add_item: "Ajouter un élément à {label} ?".to_string(),
+            decimal_separator: ',',
+            group_separator: Some(' '),
+            yes_no: YesNo::new(&["o", "oui"], &["n", "non"])
+                .batch(&["a", "tous"], &["v", "jamais"]),
+        }
+    }
+
+    /// Spanish, answering yes/no questions with `s` or `n`.
+    pub fn spanish() -> Self {
+        Locale {
+            number: "Introduce un número.".to_string(),
+            whole_number: "Introduce un número entero.".to_string(),
+            whole_number_between: "Introduce un número entero entre {min} y {max}.".to_string(),
+            invalid: "Entrada no válida, inténtalo de nuevo.".to_string(),
+            between: "Introduce un valor entre {min} y {max}.".to_string(),
+            at_least: "Introduce un valor de al menos {min}.".to_string(),
+            at_most: "Introduce un valor de como máximo {max}.".to_string(),
+            decimals: "Introduce como máximo {count} decimales.".to_string(),
+            mismatch: "Las entradas no coinciden, inténtalo de nuevo.".to_string(),
+            character: "Introduce un solo carácter.".to_string(),
+            min_length: "Introduce al menos {count} caracteres.".to_string(),
+            max_length: "Introduce como máximo {count} caracteres.".to_string(),
+            pattern: "Introduce algo que coincida con {pattern}.".to_string(),
+            pick_number: "Introduce un número del 1 al {count}.".to_string(),
+            pick_numbers: "Introduce números del 1 al {count}.".to_string(),
+            nothing_matches: "Nada coincide con «{query}», inténtalo de nuevo.".to_string(),
+            yes_or_no: "Responde {yes} o {no}.".to_string(),
+            yes_no_or_cancel: "Responde {yes}, {no} o {cancel}.".to_string(),
+            yes_no_all_never: "Responde {yes}, {no}, {all} (todos) o {never} (nunca).".to_string(),
+            path: "Introduce una ruta.".to_string(),
+            missing: "{path} no existe.".to_string(),
+            not_directory: "{path} no es un directorio.".to_string(),
+            not_file: "{path} no es un archivo.".to_string(),
+            create_failed: "No se pudo crear {path}: {error}.".to_string(),
+            duration: "Introduce una duración como 90s, 1h30m o 2d.".to_string(),
+            date: "Introduce una fecha como {example}.".to_string(),
+            time: "Introduce una hora como 09:30 o 17:45:00.".to_string(),
+            ip: "Introduce una dirección IP, como 192.168.1.10 o ::1.".to_string(),
+            ip_version: "Introduce una dirección {version}.".to_string(),
+            network: "Introduce una red, como 10.0.0.0/8 o fd00::/64.".to_string(),
+            network_version: "Introduce una red {version}.".to_string(),
+            url: "Esa URL no es válida: {error}.".to_string(),
+            url_scheme: "Introduce una URL que empiece por {schemes}.".to_string(),
+            or: "o".to_string(),
+            pair: "Introduce una entrada como clave{separator}valor.".to_string(),
+            invalid_item: "{item} no es un {kind} válido.".to_string(),
+            pairs_hint: "(clave{separator}valor, vacío para terminar)".to_string(),
+            lines_hint: "(termina con un . solo o Ctrl+D):".to_string(),
+            editor_hint: "(esperando a que el editor cierre el archivo)".to_string(),
+            phrase_hint: "(escribe {phrase} para confirmar)".to_string(),
+            countdown: "(continuando en {seconds})".to_string(),
+            checking: "Comprobando…".to_string(),
+            menu_hint: "(Intro para elegir)".to_string(),
+            multi_select_hint: "(Espacio para marcar, Intro para confirmar)".to_string(),
+            ranking_hint: "(Espacio para mover, Intro para confirmar)".to_string(),
+            moving: "(moviendo)".to_string(),
+            tree_hint: "(Intro para abrir o elegir)".to_string(),
+            enter_number: "Introduce un número".to_string(),
+            enter_numbers: "Introduce números separados por espacios o comas".to_string(),
+            enter_order: "Introduce los números en orden, del primero al último".to_string(),
+            page: "página {page}/{pages}".to_string(),
+            no_matches: "(sin resultados)".to_string(),
+            step: "Paso {number} de {total}".to_string(),
+            summary: "Resumen".to_string(),
+            hidden: "(oculto)".to_string(),
+            answers_right: "¿Son correctas estas respuestas?".to_string(),
+            change_answer: "¿Qué respuesta quieres cambiar?".to_string(),
+            yes: "sí".to_string(),
+            no: "no".to_string(),
+            optional: "(opcional)".to_string(),
+            none: "(ninguno)".to_string(),
+            set_value: "¿Definir {label}?".to_string(),
+            add_item: "¿Añadir un elemento a {label}?".to_string(),
+            decimal_separator: ',',
+            group_separator: Some('.'),
+            yes_no: YesNo::new(&["s", "sí", "si"], &["n", "no"])
+                .batch(&["a", "todos"], &["v", "nunca"]),
+        }
+    }
+
+    /// The bundled locale for a language tag like `de`, `fr-CA` or the `de_DE.UTF-8`
+    /// of `LANG`, if there's one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// if let Some(locale) = std::env::var("LANG").ok().as_deref().and_then(Locale::for_tag) {
+    ///     set_locale(locale);
+    /// }
+    /// ```
+    pub fn for_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_', '.']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "en" => Some(Locale::english()),
+            "de" => Some(Locale::german()),
+            "fr" => Some(Locale::french()),
+            "es" => Some(Locale::spanish()),
+            _ => None,
+        }
+    }
+}
+
+static LOCALE: RwLock<Option<Arc<Locale>>> = RwLock::new(None);
+
+/// Sets the text every prompt shows from here on, on every thread, along with the
+/// words yes/no questions take unless [`set_yes_no`](crate::set_yes_no) says
+/// otherwise.
+pub fn set_locale(locale: Locale) {
+    *LOCALE.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(locale));
+}
+
+/// The locale set with [`set_locale`], or English.
+pub(crate) fn locale() -> Arc<Locale> {
+    static ENGLISH: OnceLock<Arc<Locale>> = OnceLock::new();
+    let locale = LOCALE.read().unwrap_or_else(PoisonError::into_inner);
+    match &*locale {
+        Some(locale) => Arc::clone(locale),
+        None => Arc::clone(ENGLISH.get_or_init(|| Arc::new(Locale::english()))),
+    }
+}
+
+/// `template` with each `{name}` in it replaced by the value given for `name`.
+pub(crate) fn fill(template: &str, values: &[(&str, &dyn Display)]) -> String {
+    values
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+/// `input` written the way Rust reads numbers, with the locale's digit grouping
+/// taken out and its decimal separator turned into `.`, or `None` if the digits
+/// aren't grouped in threes.
+pub(crate) fn number(input: &str) -> Option<String> {
+    number_in(input, &locale())
+}
+
+/// Like [`number`], in `locale` in place of the one set.
+fn number_in(input: &str, locale: &Locale) -> Option<String> {
+    let (sign, rest) = match input.strip_prefix(['+', '-']) {
+        Some(rest) => input.split_at(input.len() - rest.len()),
+        None => ("", input),
+    };
+    let (mantissa, exponent) = rest.split_at(rest.find(['e', 'E']).unwrap_or(rest.len()));
+    let (whole, fraction) = match mantissa.split_once(locale.decimal_separator) {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (mantissa, None),
+    };
+    let grouping = |c: char| match locale.group_separator {
+        Some(' ') => matches!(c, ' ' | '\u{a0}' | '\u{202f}'),
+        Some(group) => c == group,
+        None => false,
+    };
+    let mut normal = sign.to_string();
+    if whole.contains(grouping) {
+        let groups: Vec<&str> = whole.split(grouping).collect();
+        let digits = |group: &str| group.bytes().all(|b| b.is_ascii_digit());
+        let first = groups[0].len();
+        if !(1..=3).contains(&first) || !groups.iter().all(|group| digits(group)) {
+            return None;
+        }
+        if groups[1..].iter().any(|group| group.len() != 3) {
+            return None;
+        }
+        normal.extend(groups);
+    } else {
+        normal.push_str(whole);
+    }
+    if let Some(fraction) = fraction {
+        normal.push('.');
+        normal.push_str(fraction);
+    }
+    normal.push_str(exponent);
+    Some(normal)
+}
+
+/// `items` listed with commas, and the locale's `or` before the last one.
+#[cfg(feature = "url")]
+pub(crate) fn either(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [only] => only.clone(),
+        [rest @ .., last] => format!("{} {} {}", rest.join(", "), locale().or, last),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ungrouped_numbers_are_kept() {
+        let english = Locale::english();
+        assert_eq!(number_in("1234", &english).as_deref(), Some("1234"));
+        assert_eq!(number_in("-12.5", &english).as_deref(), Some("-12.5"));
+        assert_eq!(number_in("1e5", &english).as_deref(), Some("1e5"));
+    }
+
+    #[test]
+    fn groups_are_taken_out() {
+        let english = Locale::english();
+        assert_eq!(
+            number_in("1,234,567.5", &english).as_deref(),
+            Some("1234567.5")
+        );
+        assert_eq!(number_in("+1,234", &english).as_deref(), Some("+1234"));
+        assert_eq!(
+            number_in("-999,000e3", &english).as_deref(),
+            Some("-999000e3")
+        );
+    }
+
+    #[test]
+    fn groups_must_be_threes() {
+        let english = Locale::english();
+        assert_eq!(number_in("12,34", &english), None);
+        assert_eq!(number_in("1,2345", &english), None);
+        assert_eq!(number_in("1234,567", &english), None);
+        assert_eq!(number_in(",123", &english), None);
+        assert_eq!(number_in("1,23a", &english), None);
+    }
+
+    #[test]
+    fn separators_follow_the_locale() {
+        let german = Locale::german();
+        assert_eq!(number_in("1.234,5", &german).as_deref(), Some("1234.5"));
+        assert_eq!(number_in("1,234", &german).as_deref(), Some("1.234"));
+
+        let french = Locale::french();
+        assert_eq!(number_in("1 234,5", &french).as_deref(), Some("1234.5"));
+        assert_eq!(number_in("1\u{a0}234", &french).as_deref(), Some("1234"));
+        assert_eq!(
+            number_in("1\u{202f}234\u{202f}567", &french).as_deref(),
+            Some("1234567")
+        );
+    }
+}
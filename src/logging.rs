@@ -0,0 +1,21 @@
+//! Records of the prompts asked and how they were answered, for the `log` crate,
+//! behind the `log` feature, so an interactive session can be looked into from its
+//! logs. They're all logged under the `cumaea` target, and secrets are left out.
+
+/// Notes that `prompt` is being asked.
+pub(crate) fn asking(prompt: &str) {
+    log::debug!(target: "cumaea", "asking {:?}", prompt);
+}
+
+/// Notes that the answer to `prompt` was rejected, the `attempt`th time.
+pub(crate) fn rejected(prompt: &str, attempt: usize) {
+    log::debug!(target: "cumaea", "answer {} to {:?} was rejected", attempt, prompt);
+}
+
+/// Notes that `prompt` was answered with `answer`, or with a secret if it's `None`.
+pub(crate) fn answered(prompt: &str, answer: Option<&str>) {
+    match answer {
+        Some(answer) => log::info!(target: "cumaea", "{:?} was answered {:?}", prompt, answer),
+        None => log::info!(target: "cumaea", "{:?} was answered with a secret", prompt),
+    }
+}
@@ -0,0 +1,408 @@
+//! Network-flavoured prompts: IP addresses, CIDR networks, and URLs behind the
+//! `url` feature.
+
+use crate::builder::Layout;
+use crate::locale::{fill, locale};
+use crate::{Choice, Prompt, PromptBuilder};
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+#[cfg(feature = "url")]
+use url::{ParseError, Url};
+
+/// Which kinds of IP address a prompt accepts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IpVersion {
+    /// IPv4 or IPv6.
+    #[default]
+    Any,
+    /// IPv4 only.
+    V4,
+    /// IPv6 only.
+    V6,
+}
+
+impl IpVersion {
+    fn allows(self, address: &IpAddr) -> bool {
+        match self {
+            IpVersion::Any => true,
+            IpVersion::V4 => address.is_ipv4(),
+            IpVersion::V6 => address.is_ipv6(),
+        }
+    }
+
+    fn describe(self) -> &'static str {
+        match self {
+            IpVersion::Any => "IP",
+            IpVersion::V4 => "IPv4",
+            IpVersion::V6 => "IPv6",
+        }
+    }
+}
+
+/// An IP network in CIDR notation, like `10.0.0.0/8` or `fd00::/64`.
+///
+/// The address is kept as typed, so `192.168.1.7/24` remembers the `.7`;
+/// [`network`](Cidr::network) gives the masked address.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// let cidr: Cidr = "192.168.1.7/24".parse().unwrap();
+/// assert_eq!(cidr.network().to_string(), "192.168.1.0");
+/// assert!(cidr.contains(&"192.168.1.200".parse().unwrap()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    address: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// Makes a network from an address and prefix length, or `None` if the prefix
+    /// is too long for the address family.
+    pub fn new(address: IpAddr, prefix_len: u8) -> Option<Self> {
+        let max = if address.is_ipv4() { 32 } else { 128 };
+        (prefix_len <= max).then_some(Cidr {
+            address,
+            prefix_len,
+        })
+    }
+
+    /// The address, as given.
+    pub fn address(&self) -> IpAddr {
+        self.address
+    }
+
+    /// The number of leading bits that make up the network.
+    pub fn prefix_len(&self) -> u8 {
+        self.prefix_len
+    }
+
+    /// The address with the host bits cleared.
+    pub fn network(&self) -> IpAddr {
+        match self.address {
+            IpAddr::V4(address) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                IpAddr::V4((u32::from(address) & mask).into())
+            }
+            IpAddr::V6(address) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len as u32)
+                    .unwrap_or(0);
+                IpAddr::V6((u128::from(address) & mask).into())
+            }
+        }
+    }
+
+    /// Whether `address` is inside this network.
+    pub fn contains(&self, address: &IpAddr) -> bool {
+        match Cidr::new(*address, self.prefix_len) {
+            Some(other) => other.network() == self.network(),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.address, self.prefix_len)
+    }
+}
+
+/// The error returned when a [`Cidr`] can't be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CidrParseError;
+
+impl fmt::Display for CidrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid CIDR network")
+    }
+}
+
+impl std::error::Error for CidrParseError {}
+
+impl FromStr for Cidr {
+    type Err = CidrParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (address, prefix_len) = s.split_once('/').ok_or(CidrParseError)?;
+        let address = address.parse().map_err(|_| CidrParseError)?;
+        let prefix_len = prefix_len.parse().map_err(|_| CidrParseError)?;
+        Cidr::new(address, prefix_len).ok_or(CidrParseError)
+    }
+}
+
+impl Prompt {
+    /// An IPv4 or IPv6 address, like [`prompt_ip`](crate::prompt_ip). Use
+    /// [`version`](PromptBuilder::version) to allow only one of them.
+    pub fn ip(prompt: &str) -> PromptBuilder<IpAddr> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            input.parse().map_err(|_| locale().ip.clone())
+        })
+    }
+
+    /// A network in CIDR notation, like [`prompt_cidr`](crate::prompt_cidr).
+    pub fn cidr(prompt: &str) -> PromptBuilder<Cidr> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            input.parse().map_err(|_| locale().network.clone())
+        })
+    }
+
+    /// A URL, like [`prompt_url`](crate::prompt_url). Answers without a scheme,
+    /// like `example.com/docs`, are read as `https://`. The returned [`Url`] is
+    /// normalized, so `HTTPS://Example.com` comes back as `https://example.com/`.
+    #[cfg(feature = "url")]
+    pub fn url(prompt: &str) -> PromptBuilder<Url> {
+        PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            let url = match Url::parse(input) {
+                Err(ParseError::RelativeUrlWithoutBase) => {
+                    Url::parse(&format!("https://{}", input))
+                }
+                url => url,
+            };
+            url.map_err(|err| fill(&locale().url, &[("error", &err)]))
+        })
+    }
+}
+
+impl PromptBuilder<IpAddr> {
+    /// Only accepts addresses of the given version.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let gateway = Prompt::ip("Gateway").version(IpVersion::V4).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn version(mut self, version: IpVersion) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let address = parser(input)?;
+            if version.allows(&address) {
+                Ok(address)
+            } else {
+                Err(fill(
+                    &locale().ip_version,
+                    &[("version", &version.describe())],
+                ))
+            }
+        });
+        self
+    }
+}
+
+impl PromptBuilder<Cidr> {
+    /// Only accepts networks of the given version.
+    pub fn version(mut self, version: IpVersion) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let network = parser(input)?;
+            if version.allows(&network.address()) {
+                Ok(network)
+            } else {
+                Err(fill(
+                    &locale().network_version,
+                    &[("version", &version.describe())],
+                ))
+            }
+        });
+        self
+    }
+}
+
+#[cfg(feature = "url")]
+impl PromptBuilder<Url> {
+    /// Only accepts URLs with one of the given schemes, like `["https", "ssh"]`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let remote = Prompt::url("Remote").schemes(&["https", "ssh"]).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn schemes(mut self, schemes: &[&str]) -> Self {
+        let schemes: Vec<String> = schemes.iter().map(|s| s.to_ascii_lowercase()).collect();
+        let listed: Vec<String> = schemes.iter().map(|s| format!("{}://", s)).collect();
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let url = parser(input)?;
+            if schemes.iter().any(|scheme| scheme == url.scheme()) {
+                Ok(url)
+            } else {
+                Err(fill(
+                    &locale().url_scheme,
+                    &[("schemes", &crate::locale::either(&listed))],
+                ))
+            }
+        });
+        self
+    }
+}
+
+/// Prompts for an IP address given a prompt, color option, optional default, and
+/// which [`IpVersion`] to allow. Loops until the input is an address of that version.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let dns = prompt_ip("DNS server", None, Some("1.1.1.1".parse().unwrap()), IpVersion::Any);
+/// let gateway = prompt_ip("Gateway", None, None, IpVersion::V4);
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::ip`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_ip(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<IpAddr>,
+    version: IpVersion,
+) -> IpAddr {
+    let mut builder = Prompt::ip(prompt).colored(colored).version(version);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+/// Prompts for a network in CIDR notation given a prompt, color option, optional
+/// default, and which [`IpVersion`] to allow. Loops until the input is a network of
+/// that version.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let subnet = prompt_cidr("Subnet", None, None, IpVersion::V4);
+/// println!("Using {} (network {})", subnet, subnet.network());
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::cidr`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_cidr(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<Cidr>,
+    version: IpVersion,
+) -> Cidr {
+    let mut builder = Prompt::cidr(prompt).colored(colored).version(version);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+/// Prompts for a URL given a prompt, color option, optional default, and the
+/// schemes to allow. Loops until the input parses with one of those schemes;
+/// an empty list allows any.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let homepage = prompt_url("Homepage", None, None, &["http", "https"]);
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space. Answers without a
+/// scheme are read as `https://`, and the result is normalized.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::url`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+#[cfg(feature = "url")]
+pub fn prompt_url(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<Url>,
+    schemes: &[&str],
+) -> Url {
+    let mut builder = Prompt::url(prompt).colored(colored);
+    if !schemes.is_empty() {
+        builder = builder.schemes(schemes);
+    }
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    fn cidr(network: &str) -> Cidr {
+        network.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_networks() {
+        let network = cidr("192.168.1.77/24");
+        assert_eq!(network.address(), ip("192.168.1.77"));
+        assert_eq!(network.prefix_len(), 24);
+        assert_eq!(network.network(), ip("192.168.1.0"));
+        assert_eq!(network.to_string(), "192.168.1.77/24");
+    }
+
+    #[test]
+    fn rejects_what_isnt_a_network() {
+        for network in ["10.0.0.0", "10.0.0.0/", "10.0.0.0/x", "10.0.0/8", "/8"] {
+            assert_eq!(network.parse::<Cidr>(), Err(CidrParseError), "{}", network);
+        }
+    }
+
+    #[test]
+    fn prefixes_fit_the_address_family() {
+        assert!("10.0.0.0/32".parse::<Cidr>().is_ok());
+        assert_eq!("10.0.0.0/33".parse::<Cidr>(), Err(CidrParseError));
+        assert!("::/128".parse::<Cidr>().is_ok());
+        assert_eq!("::/129".parse::<Cidr>(), Err(CidrParseError));
+    }
+
+    #[test]
+    fn zero_prefix_holds_the_whole_family() {
+        let v4 = cidr("10.1.2.3/0");
+        assert_eq!(v4.network(), ip("0.0.0.0"));
+        assert!(v4.contains(&ip("255.255.255.255")));
+        assert!(!v4.contains(&ip("::1")));
+
+        let v6 = cidr("2001:db8::1/0");
+        assert_eq!(v6.network(), ip("::"));
+        assert!(v6.contains(&ip("ffff::1")));
+        assert!(!v6.contains(&ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn full_prefix_holds_one_address() {
+        let v4 = cidr("10.0.0.1/32");
+        assert_eq!(v4.network(), ip("10.0.0.1"));
+        assert!(v4.contains(&ip("10.0.0.1")));
+        assert!(!v4.contains(&ip("10.0.0.2")));
+
+        let v6 = cidr("2001:db8::1/128");
+        assert!(v6.contains(&ip("2001:db8::1")));
+        assert!(!v6.contains(&ip("2001:db8::2")));
+    }
+}
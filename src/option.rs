@@ -0,0 +1,178 @@
+//! Menus that return a typed value, like a variant of the caller's own enum.
+
+use crate::keymap::Keymap;
+use crate::select::Menu;
+use crate::theme::Style;
+use crate::{Choice, Prompt, PromptError};
+
+/// A type whose values can be picked from a menu by [`Prompt::select`].
+///
+/// With the `strum` feature, every type that implements strum's `IntoEnumIterator`,
+/// [`Display`](std::fmt::Display) and [`Clone`], like an enum deriving `EnumIter`,
+/// `Display` and `Clone`, is one already, listing its variants in order under their
+/// `Display` text.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// #[derive(Clone, Copy)]
+/// enum Shell {
+///     Bash,
+///     Zsh,
+///     Fish,
+/// }
+///
+/// impl SelectOption for Shell {
+///     fn options() -> Vec<Self> {
+///         vec![Shell::Bash, Shell::Zsh, Shell::Fish]
+///     }
+///
+///     fn label(&self) -> String {
+///         match self {
+///             Shell::Bash => "bash",
+///             Shell::Zsh => "zsh",
+///             Shell::Fish => "fish",
+///         }
+///         .to_string()
+///     }
+/// }
+///
+/// let shell: Shell = prompt_select("Shell", None);
+/// ```
+pub trait SelectOption: Clone {
+    /// Every value that can be picked, in the order they're listed.
+    fn options() -> Vec<Self>;
+
+    /// The text `self` is listed under.
+    fn label(&self) -> String;
+}
+
+#[cfg(feature = "strum")]
+impl<T: strum::IntoEnumIterator + std::fmt::Display + Clone> SelectOption for T {
+    fn options() -> Vec<Self> {
+        T::iter().collect()
+    }
+
+    fn label(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl Prompt {
+    /// A menu of every value of `T`, like [`prompt_select`](crate::prompt_select).
+    pub fn select<T: SelectOption>(prompt: &str) -> Select<T> {
+        let options = T::options();
+        let labels: Vec<String> = options.iter().map(T::label).collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        Select {
+            menu: Menu::new(prompt, &labels),
+            options,
+        }
+    }
+}
+
+/// A menu that returns the picked value of `T`, created through
+/// [`Prompt::select`]. It works like a [`Menu`] over the values' labels.
+pub struct Select<T> {
+    pub(crate) menu: Menu,
+    options: Vec<T>,
+}
+
+impl<T: SelectOption> Select<T> {
+    /// Sets the coloration of the highlighted value.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the highlighted value, in place of the theme's highlight
+    /// style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.menu = self.menu.style(style);
+        self
+    }
+
+    /// Lays the values out in columns, like [`Menu::columns`].
+    pub fn columns(mut self) -> Self {
+        self.menu = self.menu.columns();
+        self
+    }
+
+    /// Takes the pick from the environment variable `var` when it's set and not
+    /// empty, without showing the menu. The value has to be one of the labels.
+    pub fn env(mut self, var: &str) -> Self {
+        self.menu = self.menu.env(var);
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap).
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.menu = self.menu.keymap(keymap);
+        self
+    }
+
+    /// Shows the menu and returns the picked value.
+    ///
+    /// Returns [`PromptError::Validation`] if `T` has no values to pick from.
+    pub fn ask(&self) -> Result<T, PromptError> {
+        let picked = self.menu.ask()?;
+        Ok(self.options[picked].clone())
+    }
+}
+
+impl<T: SelectOption + PartialEq> Select<T> {
+    /// Sets which value is highlighted to start with, and picked on an empty
+    /// answer in the numbered fallback.
+    pub fn default(mut self, default: T) -> Self {
+        if let Some(index) = self.options.iter().position(|option| *option == default) {
+            self.menu = self.menu.default(index);
+        }
+        self
+    }
+}
+
+/// Prompts for a value of `T` from a menu of all of them, given a prompt and color
+/// option, so the answer doesn't have to be matched up from a string or an index.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// #[derive(Clone, Copy)]
+/// enum Size {
+///     Small,
+///     Large,
+/// }
+///
+/// impl SelectOption for Size {
+///     fn options() -> Vec<Self> {
+///         vec![Size::Small, Size::Large]
+///     }
+///
+///     fn label(&self) -> String {
+///         match self {
+///             Size::Small => "Small (2 vCPUs)".to_string(),
+///             Size::Large => "Large (8 vCPUs)".to_string(),
+///         }
+///     }
+/// }
+///
+/// let size: Size = prompt_select("Instance size", None);
+/// ```
+///
+/// The values are listed under their labels like in [`prompt_menu`](crate::prompt_menu),
+/// with the arrow keys to move and Enter to pick. If stdin or stderr isn't a
+/// terminal, a numbered list is printed and the answer is read as a number.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if `T` has
+/// no values. [`Prompt::select`] returns a [`PromptError`] instead.
+pub fn prompt_select<T: SelectOption>(prompt: &str, colored: Option<Choice>) -> T {
+    let mut select = Prompt::select(prompt);
+    if let Some(color) = colored {
+        select = select.color(color);
+    }
+    select.ask().expect("Failed to read selection.")
+}
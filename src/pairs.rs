@@ -0,0 +1,218 @@
+//! Prompts for a set of `key=value` entries, like environment variables or headers.
+
+use crate::builder::{self, Layout, Validator};
+use crate::locale::{fill, locale};
+use crate::term;
+use crate::theme::Style;
+use crate::{print_error, Choice, Prompt, PromptBuilder, PromptError};
+
+impl Prompt {
+    /// Entries of the form `key=value`, one per line, like
+    /// [`prompt_pairs`](crate::prompt_pairs).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let headers = Prompt::pairs("Header")
+    ///     .separator(':')
+    ///     .validate_key(|key| match key.contains(char::is_whitespace) {
+    ///         true => Err(format!("{:?} can't be a header name.", key)),
+    ///         false => Ok(()),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pairs(prompt: &str) -> Pairs {
+        Pairs {
+            prompt: prompt.to_string(),
+            separator: '=',
+            style: None,
+            key_validators: Vec::new(),
+            value_validators: Vec::new(),
+        }
+    }
+}
+
+/// A set of entries asked for one line at a time until an empty one, created
+/// through [`Prompt::pairs`].
+///
+/// Each line is split at the first separator, `=` unless
+/// [`separator`](Pairs::separator) says otherwise, and space around the key and
+/// value is trimmed. Entries are kept in the order they're given, and a key given
+/// again replaces its earlier value where it was.
+pub struct Pairs {
+    prompt: String,
+    separator: char,
+    style: Option<Style>,
+    key_validators: Vec<Validator>,
+    value_validators: Vec<Validator>,
+}
+
+impl Pairs {
+    /// Splits entries at the first `separator`, in place of `=`, like `:` for
+    /// headers.
+    pub fn separator(mut self, separator: char) -> Self {
+        self.separator = separator;
+        self
+    }
+
+    /// Sets the coloration of the prompt.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the prompt, in place of the theme's.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub(crate) fn colored(mut self, colored: Option<Choice>) -> Self {
+        self.style = colored.map(Style::from);
+        self
+    }
+
+    /// Adds a check on each entry's key. If it returns an error, the message is
+    /// printed and the entry is asked for again. Checks run in the order they were
+    /// added.
+    pub fn validate_key(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.key_validators.push(Box::new(validator));
+        self
+    }
+
+    /// Adds a check on each entry's value, run after the checks on its key.
+    pub fn validate_value(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), String> + 'static,
+    ) -> Self {
+        self.value_validators.push(Box::new(validator));
+        self
+    }
+
+    /// Asks for entries until an empty line, and returns them in the order given.
+    pub fn ask(&self) -> Result<Vec<(String, String)>, PromptError> {
+        term::asking(|| {
+            let mut entries: Vec<(String, String)> = Vec::new();
+            let entry = self.entry();
+            while let Some((key, value)) = entry.ask()? {
+                let checked = builder::validate(&self.key_validators, &key)
+                    .and_then(|()| builder::validate(&self.value_validators, &value));
+                match checked {
+                    Ok(()) => match entries.iter_mut().find(|(given, _)| *given == key) {
+                        Some((_, earlier)) => *earlier = value,
+                        None => entries.push((key, value)),
+                    },
+                    Err(message) => print_error(&message)?,
+                }
+            }
+            Ok(entries)
+        })
+    }
+
+    /// The prompt for a single entry, which returns `None` on an empty line.
+    fn entry(&self) -> PromptBuilder<Option<(String, String)>> {
+        let separator = self.separator;
+        let hint = fill(&locale().pairs_hint, &[("separator", &separator)]);
+        let shown = format!("{} {}", self.prompt, hint);
+        let mut entry = PromptBuilder::new(&shown, Layout::Question(": "), move |input| {
+            if input.is_empty() {
+                return Ok(None);
+            }
+            match input.split_once(separator) {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok(Some((key.trim().to_string(), value.trim().to_string())))
+                }
+                _ => Err(fill(&locale().pair, &[("separator", &separator)])),
+            }
+        });
+        if let Some(style) = &self.style {
+            entry = entry.style(style.clone());
+        }
+        entry
+    }
+}
+
+/// Prompts for `key=value` entries given a prompt and color option, one per line
+/// until an empty one, like for a set of environment variables. Returns them in
+/// the order they were given.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let env = prompt_pairs("Environment variable", None);
+/// for (key, value) in &env {
+///     println!("{}={}", key, value);
+/// }
+/// ```
+///
+/// The crate prints the prompt followed by ` (key=value, empty to finish): ` for each
+/// entry. A line without a `=` or with nothing before it is asked for again, and a
+/// key given twice keeps the later value in the earlier place.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::pairs`] returns a [`PromptError`] instead.
+pub fn prompt_pairs(prompt: &str, colored: Option<Choice>) -> Vec<(String, String)> {
+    Prompt::pairs(prompt)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read line.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::MockTerminal;
+
+    fn pairs(entries: &[(&str, &str)]) -> Vec<(String, String)> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn keeps_entries_in_the_order_given() {
+        let mut terminal = MockTerminal::new().type_text("PATH=/bin\n EDITOR = vi \nLANG=C=x\n\n");
+        let entries = terminal.run(|| Prompt::pairs("Variable").ask());
+        let expected = pairs(&[("PATH", "/bin"), ("EDITOR", "vi"), ("LANG", "C=x")]);
+        assert_eq!(entries.unwrap(), expected);
+        assert!(terminal
+            .screen()
+            .starts_with("Variable (key=value, empty to finish): PATH=/bin\n"));
+    }
+
+    #[test]
+    fn replaces_a_key_given_again_where_it_was() {
+        let mut terminal = MockTerminal::new().type_text("b=1\na=2\nb=3\n\n");
+        let entries = terminal.run(|| Prompt::pairs("Variable").ask());
+        assert_eq!(entries.unwrap(), pairs(&[("b", "3"), ("a", "2")]));
+    }
+
+    #[test]
+    fn asks_again_for_entries_it_cant_take() {
+        let mut terminal =
+            MockTerminal::new().type_text("Accept json\n: json\nbad key: x\nAccept: json\n\n");
+        let entries = terminal.run(|| {
+            Prompt::pairs("Header")
+                .separator(':')
+                .validate_key(|key| match key.contains(' ') {
+                    true => Err(format!("{:?} has a space.", key)),
+                    false => Ok(()),
+                })
+                .ask()
+        });
+        assert_eq!(entries.unwrap(), pairs(&[("Accept", "json")]));
+        let output = terminal.output();
+        assert!(output.contains(&fill(&locale().pair, &[("separator", &':')])));
+        assert!(output.contains("\"bad key\" has a space."));
+    }
+}
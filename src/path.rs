@@ -0,0 +1,165 @@
+//! Filesystem path prompts.
+
+use crate::builder::Layout;
+use crate::editor::complete_path;
+use crate::locale::{fill, locale};
+use crate::{Choice, Prompt, PromptBuilder};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What a path prompt requires of the answer. Everything is off by default.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// let options = PathOptions {
+///     must_be_dir: true,
+///     create_if_missing: true,
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathOptions {
+    /// The path has to exist already.
+    pub must_exist: bool,
+    /// The path has to be a directory, if it exists.
+    pub must_be_dir: bool,
+    /// The path has to be a file, if it exists.
+    pub must_be_file: bool,
+    /// A missing path is created: as an empty file if `must_be_file` is set,
+    /// otherwise as a directory. Parent directories are created as needed.
+    pub create_if_missing: bool,
+}
+
+impl Prompt {
+    /// A filesystem path, like [`prompt_path`](crate::prompt_path). Anything but an
+    /// empty answer is accepted unless [`options`](PromptBuilder::options) says otherwise.
+    ///
+    /// In a terminal, Tab completes the last component of the path from the
+    /// directory entries, as far as they agree. If that adds nothing, it lists them.
+    pub fn path(prompt: &str) -> PromptBuilder<PathBuf> {
+        let mut builder = PromptBuilder::new(prompt, Layout::Question(": "), |input| {
+            if input.is_empty() {
+                Err(locale().path.clone())
+            } else {
+                Ok(PathBuf::from(input))
+            }
+        });
+        builder.completer = Some(Box::new(complete_path));
+        builder.describe_default =
+            Some(|default| default.map(|default| format!("[{}]", default.display())));
+        builder.describe_answer = Some(|path| path.display().to_string());
+        builder
+    }
+}
+
+impl PromptBuilder<PathBuf> {
+    /// Checks answers against `options`, printing the specific reason an answer
+    /// was rejected before asking again.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let config = Prompt::path("Config file")
+    ///     .options(PathOptions {
+    ///         must_exist: true,
+    ///         must_be_file: true,
+    ///         ..Default::default()
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn options(mut self, options: PathOptions) -> Self {
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            let path = parser(input)?;
+            check_path(&path, options)?;
+            Ok(path)
+        });
+        self
+    }
+}
+
+fn check_path(path: &Path, options: PathOptions) -> Result<(), String> {
+    let shown = path.display();
+    if !path.exists() {
+        if options.create_if_missing {
+            return create(path, options).map_err(|err| {
+                fill(
+                    &locale().create_failed,
+                    &[("path", &shown), ("error", &err)],
+                )
+            });
+        }
+        if options.must_exist {
+            return Err(fill(&locale().missing, &[("path", &shown)]));
+        }
+        return Ok(());
+    }
+
+    if options.must_be_dir && !path.is_dir() {
+        return Err(fill(&locale().not_directory, &[("path", &shown)]));
+    }
+    if options.must_be_file && !path.is_file() {
+        return Err(fill(&locale().not_file, &[("path", &shown)]));
+    }
+    Ok(())
+}
+
+fn create(path: &Path, options: PathOptions) -> std::io::Result<()> {
+    if !options.must_be_file {
+        return fs::create_dir_all(path);
+    }
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::File::create_new(path).map(|_| ())
+}
+
+/// Prompts for a filesystem path given a prompt, color option, optional default,
+/// and the [`PathOptions`] the answer has to meet. Loops until it does, printing
+/// why each rejected answer was rejected.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let output = prompt_path(
+///     "Output directory",
+///     None,
+///     Some("./out".into()),
+///     PathOptions {
+///         must_be_dir: true,
+///         create_if_missing: true,
+///         ..Default::default()
+///     },
+/// );
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space, and Tab completes
+/// directory entries. The default isn't checked against the options.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed. [`Prompt::path`]
+/// returns a [`PromptError`](crate::PromptError) instead.
+pub fn prompt_path(
+    prompt: &str,
+    colored: Option<Choice>,
+    default: Option<PathBuf>,
+    options: PathOptions,
+) -> PathBuf {
+    let mut builder = Prompt::path(prompt).colored(colored).options(options);
+    if let Some(default) = default {
+        builder = builder.default(default);
+    }
+    builder.ask().expect("Failed to read line.")
+}
@@ -0,0 +1,72 @@
+//! Regex-validated prompts, behind the `regex` feature.
+
+use crate::locale::{fill, locale};
+use crate::{Choice, Prompt, PromptBuilder};
+use regex::Regex;
+
+impl<T: 'static> PromptBuilder<T> {
+    /// Only accepts answers that `regex` matches in full, as if it were wrapped in
+    /// `^(?:...)$`. Rejected answers print the pattern, unless an
+    /// [`error_message`](PromptBuilder::error_message) is set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # use regex::Regex;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let slug = Prompt::text("Project slug")
+    ///     .pattern(&Regex::new("[a-z0-9-]+").unwrap())
+    ///     .error_message("Use lowercase letters, digits and dashes only.")
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn pattern(mut self, regex: &Regex) -> Self {
+        let anchored = Regex::new(&format!("^(?:{})$", regex.as_str()))
+            .expect("wrapping a valid regex keeps it valid");
+        let pattern = regex.as_str().to_string();
+        let parser = self.parser;
+        self.parser = Box::new(move |input| {
+            if anchored.is_match(input) {
+                parser(input)
+            } else {
+                Err(fill(&locale().pattern, &[("pattern", &pattern)]))
+            }
+        });
+        self
+    }
+}
+
+/// Prompts for text matching a regular expression, given a prompt, the pattern,
+/// color option, and optional default. Loops until the whole answer matches.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let version = prompt_matching("Version", r"\d+\.\d+\.\d+", None, Some("0.1.0"));
+/// ```
+///
+/// The crate prints the prompt followed by a colon and a space, and prints the
+/// pattern after each rejected answer. For a friendlier message, use
+/// [`Prompt::text`] with [`pattern`](PromptBuilder::pattern) and
+/// [`error_message`](PromptBuilder::error_message).
+///
+/// # Panics
+///
+/// Panics if `pattern` isn't a valid regex, on I/O failure, if stdin is closed, or if
+/// Ctrl+C is pressed.
+pub fn prompt_matching(
+    prompt: &str,
+    pattern: &str,
+    colored: Option<Choice>,
+    default: Option<&str>,
+) -> String {
+    let regex = Regex::new(pattern).expect("Invalid pattern.");
+    let mut builder = Prompt::text(prompt).colored(colored).pattern(&regex);
+    if let Some(default) = default {
+        builder = builder.default(default.to_string());
+    }
+    builder.ask().expect("Failed to read line.")
+}
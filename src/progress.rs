@@ -0,0 +1,52 @@
+//! Keeping `indicatif` progress bars out of the way of prompts, behind the
+//! `indicatif` feature.
+
+use indicatif::MultiProgress;
+use std::cell::Cell;
+use std::sync::{Mutex, PoisonError};
+
+static PROGRESS: Mutex<Option<MultiProgress>> = Mutex::new(None);
+
+thread_local! {
+    /// Whether a prompt on this thread has already put the bars away.
+    static SUSPENDED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Sets the progress bars every prompt puts away while it asks, on every thread,
+/// or `None` to stop. The bars are wiped when a prompt starts and drawn again
+/// below it once it's answered, and the bars on other threads wait for it rather
+/// than drawing over it.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use indicatif::{MultiProgress, ProgressBar};
+///
+/// let bars = MultiProgress::new();
+/// let download = bars.add(ProgressBar::new(100));
+/// set_progress(Some(bars.clone()));
+/// let overwrite = Prompt::confirm("Overwrite the old copy?").ask();
+/// download.inc(1);
+/// ```
+pub fn set_progress(progress: Option<MultiProgress>) {
+    *PROGRESS.lock().unwrap_or_else(PoisonError::into_inner) = progress;
+}
+
+/// Runs `f` with the progress bars put away, unless a prompt it's part of already
+/// put them away.
+pub(crate) fn suspended<T>(f: impl FnOnce() -> T) -> T {
+    let progress = PROGRESS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    match progress {
+        Some(progress) if !SUSPENDED.get() => {
+            SUSPENDED.set(true);
+            let result = progress.suspend(f);
+            SUSPENDED.set(false);
+            result
+        }
+        _ => f(),
+    }
+}
@@ -0,0 +1,70 @@
+//! Running prompts against a reader and writer other than stdin and stdout.
+
+use crate::term::{self, Streams};
+use std::cell::{Ref, RefCell};
+use std::io::{BufRead, Write};
+use std::rc::Rc;
+
+/// Runs prompts against a reader and writer of your choosing instead of stdin and
+/// stdout, so tests and embedders can drive them with in-memory buffers.
+///
+/// Every prompt and `prompt_` function called inside [`run`](Prompter::run) reads
+/// its answers from the reader and prints to the writer. The free functions and
+/// [`PromptBuilder::ask`](crate::PromptBuilder::ask) are the same prompts run
+/// against stdin and stdout. A prompter is never a terminal, so prompts are
+/// answered a line at a time, the way they are when stdin is piped in: menus read
+/// their pick as a number, and timeouts don't apply.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// let mut prompter = Prompter::new(&b"8080\ny\n"[..], Vec::new());
+/// let (port, tls) = prompter.run(|| {
+///     let port = Prompt::int("Port").range(1..=65535).ask()?;
+///     let tls = Prompt::confirm("Use TLS?").ask()?;
+///     Ok::<_, PromptError>((port, tls))
+/// })?;
+/// assert_eq!((port, tls), (8080, true));
+/// assert_eq!(*prompter.writer(), b"Port: Use TLS? ");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Prompter<R, W> {
+    streams: Rc<RefCell<Streams<R, W>>>,
+}
+
+impl<R: BufRead + 'static, W: Write + 'static> Prompter<R, W> {
+    /// Creates a prompter that reads answers from `reader` and prints to `writer`.
+    pub fn new(reader: R, writer: W) -> Self {
+        Prompter {
+            streams: Rc::new(RefCell::new(Streams { reader, writer })),
+        }
+    }
+
+    /// Runs `f`, with the prompts it shows on this thread reading from and writing
+    /// to this prompter, and returns what it returns.
+    pub fn run<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        term::redirect(self.streams.clone(), f)
+    }
+
+    /// The reader, with whatever the prompts haven't read yet.
+    pub fn reader(&self) -> Ref<'_, R> {
+        Ref::map(self.streams.borrow(), |streams| &streams.reader)
+    }
+
+    /// The writer, with everything the prompts have printed so far.
+    pub fn writer(&self) -> Ref<'_, W> {
+        Ref::map(self.streams.borrow(), |streams| &streams.writer)
+    }
+
+    /// Gives back the reader and writer.
+    pub fn into_inner(self) -> (R, W) {
+        let Ok(streams) = Rc::try_unwrap(self.streams) else {
+            unreachable!("prompts only hold on to the streams while running");
+        };
+        let Streams { reader, writer } = streams.into_inner();
+        (reader, writer)
+    }
+}
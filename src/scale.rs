@@ -0,0 +1,260 @@
+//! Prompts for a value on a bounded scale, like a rating or a slider.
+
+use crate::builder::{self, Layout};
+use crate::locale::locale;
+use crate::select::{answered, question};
+use crate::session;
+use crate::term::{self, outln, Frame, Key, RawMode};
+use crate::theme::{self, Style};
+use crate::wizard;
+use crate::{paint, Choice, Prompt, PromptBuilder, PromptError};
+use std::ops::RangeInclusive;
+
+/// How many characters wide a slider's bar is.
+const BAR: usize = 30;
+
+impl Prompt {
+    /// A rating on the scale `range`, like [`prompt_rating`](crate::prompt_rating).
+    /// The scale is listed after the question, with the default picked out in the
+    /// theme's default style.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let score = Prompt::rating("How likely are you to recommend us?", 0..=10)
+    ///     .default(8)
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn rating(prompt: &str, range: RangeInclusive<u8>) -> PromptBuilder<u8> {
+        let mut builder = PromptBuilder::new(prompt, Layout::List(scale(&range)), |input| {
+            input.parse().map_err(|_| locale().whole_number.clone())
+        })
+        .range(range);
+        builder.show_default = Some(|value: &u8| value.to_string());
+        builder
+    }
+
+    /// A number picked by sliding it along `range`, like
+    /// [`prompt_slider`](crate::prompt_slider).
+    pub fn slider(prompt: &str, range: RangeInclusive<i64>) -> Slider {
+        Slider {
+            prompt: prompt.to_string(),
+            range,
+            step: 1,
+            default: None,
+            style: None,
+        }
+    }
+}
+
+/// A number on a bar adjusted with the arrow keys, created through
+/// [`Prompt::slider`].
+///
+/// Left/Right (or `h`/`l`) move the value by a step, PageDown/PageUp by a tenth of
+/// the range, Home/End jump to either end, and Enter picks it. When stdin or stderr
+/// isn't a terminal, the number is typed instead, like with [`Prompt::int`].
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// let volume = Prompt::slider("Volume", 0..=100).step(5).default(50).ask()?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct Slider {
+    prompt: String,
+    range: RangeInclusive<i64>,
+    step: i64,
+    default: Option<i64>,
+    style: Option<Style>,
+}
+
+impl Slider {
+    /// Sets how far Left and Right move the value, in place of 1.
+    pub fn step(mut self, step: i64) -> Self {
+        self.step = step.max(1);
+        self
+    }
+
+    /// Sets where the value starts, and what's picked on an empty answer when it's
+    /// typed. Otherwise it starts at the low end of the range.
+    pub fn default(mut self, default: i64) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Sets the coloration of the filled part of the bar.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the filled part of the bar, in place of the theme's
+    /// highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub(crate) fn colored(mut self, colored: Option<Choice>) -> Self {
+        self.style = colored.map(Style::from);
+        self
+    }
+
+    /// Shows the slider and returns the picked number.
+    ///
+    /// Returns [`PromptError::Validation`] if the range is empty.
+    pub fn ask(&self) -> Result<i64, PromptError> {
+        self.ask_from(self.default)
+    }
+
+    /// Like [`ask`](Slider::ask), starting at `default` in place of the slider's own.
+    pub(crate) fn ask_from(&self, default: Option<i64>) -> Result<i64, PromptError> {
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, None);
+        term::asking(|| {
+            let (&min, &max) = (self.range.start(), self.range.end());
+            if min > max {
+                return Err(PromptError::Validation("the range is empty".into()));
+            }
+            let start = default.unwrap_or(min).clamp(min, max);
+            let given = builder::given_answer(&self.prompt, None, None, false).is_some();
+            if given || !term::redraws() {
+                return Prompt::int(&self.prompt)
+                    .range(self.range.clone())
+                    .default(start)
+                    .ask();
+            }
+            if default.is_some() && builder::assume_defaults() {
+                return Ok(start);
+            }
+
+            let raw = RawMode::enable()?;
+            let mut frame = Frame::new();
+            let mut value = start;
+            let page = ((max as i128 - min as i128) / 10).min(i64::MAX as i128) as i64;
+            let page = page.max(self.step);
+            loop {
+                frame.draw(&[self.line(value)])?;
+                value = match term::read_key()? {
+                    Key::Left | Key::Char('h') => value.saturating_sub(self.step),
+                    Key::Right | Key::Char('l') => value.saturating_add(self.step),
+                    Key::PageDown => value.saturating_sub(page),
+                    Key::PageUp => value.saturating_add(page),
+                    Key::Home => min,
+                    Key::End => max,
+                    Key::Enter => break,
+                    Key::Esc if wizard::can_go_back() => {
+                        frame.clear()?;
+                        return Err(PromptError::Back);
+                    }
+                    _ => value,
+                }
+                .clamp(min, max);
+            }
+
+            frame.clear()?;
+            drop(raw);
+            outln!("{}", answered(&self.prompt, &value.to_string()))?;
+            session::record(&self.prompt, &value.to_string())?;
+            Ok(value)
+        })
+    }
+
+    /// The prompt, the bar filled up to `value`, and `value` itself.
+    fn line(&self, value: i64) -> String {
+        let (&min, &max) = (self.range.start(), self.range.end());
+        let filled = match max == min {
+            true => BAR,
+            false => {
+                let share = (value as f64 - min as f64) / (max as f64 - min as f64);
+                (share * BAR as f64).round() as usize
+            }
+        };
+        let theme = theme::theme();
+        format!(
+            "{}: [{}{}] {}",
+            question(&self.prompt),
+            paint(&"=".repeat(filled), &self.style, &theme.highlight_style),
+            "-".repeat(BAR - filled),
+            value
+        )
+    }
+}
+
+/// The scale listed after a rating's question: every value when there are up to
+/// ten of them, like `1/2/3/4/5`, or else just the ends, like `1-100`.
+fn scale(range: &RangeInclusive<u8>) -> String {
+    if range.len() <= 10 {
+        let values: Vec<String> = range.clone().map(|value| value.to_string()).collect();
+        values.join("/")
+    } else {
+        format!("{}-{}", range.start(), range.end())
+    }
+}
+
+/// Prompts for a rating from `min` to `max` given a prompt and color option, like
+/// for a feedback or survey question. Loops until the answer is a whole number on
+/// the scale.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let stars = prompt_rating("How was the install?", None, 1, 5);
+/// ```
+///
+/// The crate prints the prompt, a colon, and the scale in brackets, like
+/// `How was the install?: [1/2/3/4/5]: `. Scales of more than ten values are shown
+/// by their ends, like `[1-100]`. The color applies to the scale.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, or if Ctrl+C is pressed.
+/// [`Prompt::rating`] returns a [`PromptError`] instead.
+pub fn prompt_rating(prompt: &str, colored: Option<Choice>, min: u8, max: u8) -> u8 {
+    Prompt::rating(prompt, min..=max)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read line.")
+}
+
+/// Prompts for a number from `min` to `max` given a prompt, color option, and
+/// optional starting value, picked by sliding it along a bar with the arrow keys.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let brightness = prompt_slider("Brightness", None, 0, 100, Some(70));
+/// ```
+///
+/// The crate draws the prompt, a colon, the bar and the value, like
+/// `Brightness: [=====================---------] 70`. The color applies to the filled
+/// part of the bar. When stdin or stderr isn't a terminal, the number is typed
+/// instead, and an empty answer picks the starting value.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if `min` is
+/// more than `max`. [`Prompt::slider`] returns a [`PromptError`] instead.
+pub fn prompt_slider(
+    prompt: &str,
+    colored: Option<Choice>,
+    min: i64,
+    max: i64,
+    default: Option<i64>,
+) -> i64 {
+    let mut slider = Prompt::slider(prompt, min..=max).colored(colored);
+    if let Some(default) = default {
+        slider = slider.default(default);
+    }
+    slider.ask().expect("Failed to read line.")
+}
@@ -0,0 +1,477 @@
+//! Forms generated from a JSON Schema, behind the `json` feature.
+
+use crate::confirm;
+use crate::fields::humanize;
+use crate::locale::{fill, locale};
+use crate::{Prompt, PromptBuilder, PromptError};
+use serde_json::{Map, Value};
+
+impl Prompt {
+    /// Asks for a value matching the JSON Schema `schema`, like
+    /// [`prompt_json_schema`](crate::prompt_json_schema), returning a [`PromptError`]
+    /// if anything goes wrong.
+    ///
+    /// Returns [`PromptError::Validation`] if the schema has a part that can't be
+    /// asked for, like a `$ref` or an unknown `type`.
+    pub fn json_schema(schema: &Value) -> Result<Value, PromptError> {
+        if kind(schema)?.0 == "object" {
+            return object(schema, None);
+        }
+        let label = title(schema).unwrap_or("Value");
+        ask(schema, label, false).map(|value| value.unwrap_or(Value::Null))
+    }
+}
+
+/// The schema's `title`.
+fn title(schema: &Value) -> Option<&str> {
+    schema.get("title").and_then(Value::as_str)
+}
+
+/// The schema's `type`, and whether `null` is allowed too, as in
+/// `"type": ["string", "null"]`. Without a `type`, it's an object if it has
+/// `properties` and a string otherwise.
+fn kind(schema: &Value) -> Result<(&str, bool), PromptError> {
+    if schema.get("$ref").is_some() {
+        return Err(invalid("references with $ref aren't supported"));
+    }
+    match schema.get("type") {
+        Some(Value::String(kind)) => Ok((kind, false)),
+        Some(Value::Array(kinds)) => {
+            let null = kinds.iter().any(|kind| kind == "null");
+            let kind = kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|&kind| kind != "null")
+                .unwrap_or("null");
+            Ok((kind, null))
+        }
+        Some(other) => Err(invalid(&format!("{} isn't a type", other))),
+        None if schema.get("properties").is_some() => Ok(("object", false)),
+        None => Ok(("string", false)),
+    }
+}
+
+fn invalid(reason: &str) -> PromptError {
+    PromptError::Validation(format!("the schema can't be asked for: {}", reason))
+}
+
+/// Asks for the value `schema` describes under `label`. An optional one can be
+/// left out, which returns `None`.
+fn ask(schema: &Value, label: &str, optional: bool) -> Result<Option<Value>, PromptError> {
+    if let Some(value) = schema.get("const") {
+        return Ok(Some(value.clone()));
+    }
+    if let Some(Value::Array(options)) = schema.get("enum") {
+        return pick(label, options, optional);
+    }
+    let (kind, null) = kind(schema)?;
+    let optional = optional || null;
+    let shown = match optional {
+        true => format!("{} {}", label, locale().optional),
+        false => label.to_string(),
+    };
+    match kind {
+        "string" => {
+            let mut builder = Prompt::text(&shown);
+            if let Some(default) = schema.get("default").and_then(Value::as_str) {
+                builder = builder.default(default.to_string());
+            }
+            builder = string_checks(builder, schema)?;
+            scalar(builder, optional, Value::String)
+        }
+        "integer" => {
+            let mut builder = Prompt::int(&shown);
+            if let Some(min) = schema.get("minimum").and_then(Value::as_i64) {
+                builder = builder.min(min);
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_i64) {
+                builder = builder.max(max);
+            }
+            if let Some(default) = schema.get("default").and_then(Value::as_i64) {
+                builder = builder.default(default);
+            }
+            scalar(builder, optional, Value::from)
+        }
+        "number" => {
+            let mut builder = Prompt::float(&shown);
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                builder = builder.min(min);
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                builder = builder.max(max);
+            }
+            if let Some(default) = schema.get("default").and_then(Value::as_f64) {
+                builder = builder.default(default);
+            }
+            scalar(builder, optional, Value::from)
+        }
+        "boolean" => {
+            let default = schema.get("default").and_then(Value::as_bool);
+            let question = format!("{} {}", shown, confirm::hint(default));
+            let mut builder = Prompt::confirm(&question);
+            if let Some(default) = default {
+                builder = builder.default(default);
+            }
+            scalar(builder, optional, Value::Bool)
+        }
+        "null" => Ok(Some(Value::Null)),
+        "array" => array(schema, label, optional),
+        "object" => {
+            let lower = label.to_lowercase();
+            let set = fill(&locale().set_value, &[("label", &lower)]);
+            let question = format!("{} {}", set, confirm::hint(Some(false)));
+            if optional && !Prompt::confirm(&question).default(false).ask()? {
+                return Ok(None);
+            }
+            object(schema, Some(label)).map(Some)
+        }
+        other => Err(invalid(&format!("{:?} isn't a type", other))),
+    }
+}
+
+/// Asks `builder`, which an optional value can skip with an empty answer.
+fn scalar<T: Clone>(
+    builder: PromptBuilder<T>,
+    optional: bool,
+    value: impl Fn(T) -> Value,
+) -> Result<Option<Value>, PromptError> {
+    match optional {
+        true => builder
+            .allow_skip()
+            .sentinel("")
+            .ask()
+            .map(|answer| answer.map(value)),
+        false => builder.ask().map(|answer| Some(value(answer))),
+    }
+}
+
+/// Adds the checks of `minLength`, `maxLength` and, with the `regex` feature,
+/// `pattern`.
+fn string_checks(
+    mut builder: PromptBuilder<String>,
+    schema: &Value,
+) -> Result<PromptBuilder<String>, PromptError> {
+    if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+        builder = builder.validate(move |input| match input.chars().count() as u64 >= min {
+            true => Ok(()),
+            false => Err(fill(&locale().min_length, &[("count", &min)])),
+        });
+    }
+    if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+        builder = builder.validate(move |input| match input.chars().count() as u64 <= max {
+            true => Ok(()),
+            false => Err(fill(&locale().max_length, &[("count", &max)])),
+        });
+    }
+    #[cfg(feature = "regex")]
+    if let Some(pattern) = schema.get("pattern").and_then(Value::as_str) {
+        let regex = regex::Regex::new(pattern)
+            .map_err(|_| invalid(&format!("{:?} isn't a valid pattern", pattern)))?;
+        builder = builder.validate(move |input| match regex.is_match(input) {
+            true => Ok(()),
+            false => Err(fill(&locale().pattern, &[("pattern", &regex.as_str())])),
+        });
+    }
+    Ok(builder)
+}
+
+/// How a value of an `enum` is listed in a menu.
+fn option_label(option: &Value) -> String {
+    match option {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Picks one of `options` from a menu, with `(none)` after them if it's optional.
+fn pick(label: &str, options: &[Value], optional: bool) -> Result<Option<Value>, PromptError> {
+    let mut labels: Vec<String> = options.iter().map(option_label).collect();
+    if optional {
+        labels.push(locale().none.clone());
+    }
+    let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+    let picked = Prompt::menu(label, &labels).ask()?;
+    Ok(options.get(picked).cloned())
+}
+
+/// Asks for an array: values typed on one line for simple `items`, a checkbox list
+/// for items from an `enum`, and otherwise one item at a time for as long as
+/// another is wanted. An optional array that's left empty returns `None`.
+fn array(schema: &Value, label: &str, optional: bool) -> Result<Option<Value>, PromptError> {
+    let items = schema.get("items").unwrap_or(&Value::Null);
+    let values = if let Some(Value::Array(options)) = items.get("enum") {
+        let labels: Vec<String> = options.iter().map(option_label).collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+        let picked = Prompt::multi_select(label, &labels).ask()?;
+        picked.into_iter().map(|i| options[i].clone()).collect()
+    } else {
+        match kind(items)? {
+            ("object" | "array", _) => repeated(items, label)?,
+            (kind, _) => typed_list(kind, label)?,
+        }
+    };
+    match values.is_empty() && optional {
+        true => Ok(None),
+        false => Ok(Some(Value::Array(values))),
+    }
+}
+
+/// Values of `kind` typed on one line, like [`Prompt::list`].
+fn typed_list(kind: &str, label: &str) -> Result<Vec<Value>, PromptError> {
+    let parse: fn(&str) -> Option<Value> = match kind {
+        "string" => |item| Some(Value::String(item.to_string())),
+        "integer" => |item| item.parse::<i64>().ok().map(Value::from),
+        "number" => |item| {
+            item.parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(Value::from)
+        },
+        "boolean" => |item| item.parse::<bool>().ok().map(Value::Bool),
+        other => return Err(invalid(&format!("{:?} isn't a type", other))),
+    };
+    let kind = kind.to_string();
+    let items = Prompt::list(label)
+        .validate_each(move |item| match parse(item) {
+            Some(_) => Ok(()),
+            None => Err(fill(
+                &locale().invalid_item,
+                &[("item", &item), ("kind", &kind)],
+            )),
+        })
+        .ask()?;
+    Ok(items.iter().filter_map(|item| parse(item)).collect())
+}
+
+/// Items asked for one at a time, each after a yes to adding it.
+fn repeated(items: &Value, label: &str) -> Result<Vec<Value>, PromptError> {
+    let mut values = Vec::new();
+    let lower = label.to_lowercase();
+    let add = fill(&locale().add_item, &[("label", &lower)]);
+    let question = format!("{} {}", add, confirm::hint(Some(false)));
+    while Prompt::confirm(&question).default(false).ask()? {
+        let item = format!("{} {}", label, values.len() + 1);
+        values.extend(ask(items, &item, false)?);
+    }
+    Ok(values)
+}
+
+/// Asks for each of the object's `properties` in order. The properties of an object
+/// inside another are labelled with the outer one's name first, like
+/// `Database host`.
+fn object(schema: &Value, prefix: Option<&str>) -> Result<Value, PromptError> {
+    let required: Vec<&str> = match schema.get("required") {
+        Some(Value::Array(names)) => names.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    let mut answers = Map::new();
+    let Some(Value::Object(properties)) = schema.get("properties") else {
+        return Ok(Value::Object(answers));
+    };
+    for (name, property) in properties {
+        let label = match (title(property), prefix) {
+            (Some(title), _) => title.to_string(),
+            (None, Some(prefix)) => format!("{} {}", prefix, humanize(name).to_lowercase()),
+            (None, None) => humanize(name),
+        };
+        let needed = required.contains(&name.as_str());
+        match ask(property, &label, !needed)? {
+            Some(value) => {
+                answers.insert(name.clone(), value);
+            }
+            // Only a property that can be null is left out when it's needed.
+            None if needed => {
+                answers.insert(name.clone(), Value::Null);
+            }
+            None => {}
+        }
+    }
+    Ok(Value::Object(answers))
+}
+
+/// Prompts for a value described by a JSON Schema, asking for each property of
+/// an object in turn, and returns the answers as JSON. It's behind the `json`
+/// feature.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let schema = serde_json::json!({
+///     "type": "object",
+///     "properties": {
+///         "name": { "type": "string", "minLength": 1 },
+///         "port": { "type": "integer", "minimum": 1, "maximum": 65535, "default": 8080 },
+///         "log_level": { "enum": ["debug", "info", "warn"] },
+///         "tags": { "type": "array", "items": { "type": "string" } },
+///         "tls": { "type": "boolean", "default": false }
+///     },
+///     "required": ["name", "port", "log_level"]
+/// });
+/// let config = prompt_json_schema(&schema);
+/// println!("{}", config);
+/// ```
+///
+/// Each property is asked for under its `title`, or else its name, like
+/// `Log level` for `log_level`. Strings, numbers and booleans are typed, checked
+/// against `minLength`, `maxLength`, `minimum` and `maximum`, and `pattern` with the
+/// `regex` feature. An `enum` is picked from a menu, and an array is typed on one
+/// line, picked from a checkbox list if its items are an `enum`, or added to an
+/// item at a time if they're objects. A property that isn't `required` is marked
+/// `(optional)` and left out on an empty answer, and a `default` is taken on one.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if the
+/// schema has a part that can't be asked for. [`Prompt::json_schema`] returns a
+/// [`PromptError`] instead.
+pub fn prompt_json_schema(schema: &Value) -> Value {
+    Prompt::json_schema(schema).expect("Failed to read value.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{Key, MockTerminal};
+    use serde_json::json;
+
+    fn answered(schema: Value, terminal: &mut MockTerminal) -> Value {
+        terminal.run(|| Prompt::json_schema(&schema)).unwrap()
+    }
+
+    #[test]
+    fn asks_for_each_property() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string", "minLength": 1 },
+                "listenPort": { "type": "integer", "minimum": 1, "default": 8080 },
+                "log_level": { "enum": ["debug", "info"] },
+                "ratio": { "type": "number" },
+                "tls": { "type": "boolean" }
+            },
+            "required": ["name", "listenPort", "log_level", "ratio", "tls"]
+        });
+        let mut terminal = MockTerminal::new()
+            .type_text("\ndemo\n0\n\n")
+            .press(Key::Down)
+            .press(Key::Enter)
+            .type_text("0.5\ny\n");
+        let value = answered(schema, &mut terminal);
+        assert_eq!(
+            value,
+            json!({"name": "demo", "listenPort": 8080, "log_level": "info", "ratio": 0.5, "tls": true})
+        );
+        let screen = terminal.screen();
+        assert!(
+            screen.starts_with("Name: demo\nListen port:\nLog level: info\n"),
+            "{}",
+            screen
+        );
+    }
+
+    #[test]
+    fn leaves_out_optional_properties_left_empty() {
+        let schema = json!({
+            "properties": {
+                "nickname": { "type": "string" },
+                "port": { "type": "integer" },
+                "tls": { "type": "boolean" },
+                "verbose": { "type": "boolean", "default": true }
+            }
+        });
+        let mut terminal = MockTerminal::new().type_text("\n\n\n\n");
+        let value = answered(schema, &mut terminal);
+        assert_eq!(value, json!({"verbose": true}));
+        assert!(
+            terminal.screen().contains("Tls (optional) (y/n)"),
+            "{}",
+            terminal.screen()
+        );
+    }
+
+    #[test]
+    fn nullable_required_properties_are_null() {
+        let schema = json!({
+            "properties": { "proxy": { "type": ["string", "null"] } },
+            "required": ["proxy"]
+        });
+        let mut terminal = MockTerminal::new().type_text("\n");
+        assert_eq!(answered(schema, &mut terminal), json!({"proxy": null}));
+    }
+
+    #[test]
+    fn asks_for_arrays() {
+        let schema = json!({
+            "properties": {
+                "ports": { "type": "array", "items": { "type": "integer" } },
+                "features": { "type": "array", "items": { "enum": ["a", "b", "c"] } },
+                "users": {
+                    "type": "array",
+                    "items": { "properties": { "name": { "type": "string" } }, "required": ["name"] }
+                }
+            },
+            "required": ["ports", "features", "users"]
+        });
+        let mut terminal = MockTerminal::new()
+            .type_text("80, x\n80, 443\n")
+            .press(Key::Down)
+            .press(Key::Char(' '))
+            .press(Key::Enter)
+            .type_text("y\nada\nn\n");
+        let value = answered(schema, &mut terminal);
+        assert_eq!(
+            value,
+            json!({"ports": [80, 443], "features": ["b"], "users": [{"name": "ada"}]})
+        );
+    }
+
+    #[test]
+    fn labels_nested_properties_with_their_object() {
+        let schema = json!({
+            "properties": {
+                "database": {
+                    "properties": { "host": { "type": "string" } },
+                    "required": ["host"]
+                },
+                "cache": { "properties": { "ttl": { "type": "integer" } } }
+            },
+            "required": ["database"]
+        });
+        let mut terminal = MockTerminal::new().type_text("db\n\n");
+        let value = answered(schema, &mut terminal);
+        assert_eq!(value, json!({"database": {"host": "db"}}));
+        assert!(
+            terminal.screen().starts_with("Database host: db\n"),
+            "{}",
+            terminal.screen()
+        );
+    }
+
+    #[test]
+    fn rejects_what_it_cant_ask_for() {
+        for schema in [
+            json!({"$ref": "#/x"}),
+            json!({"type": "tuple"}),
+            json!({"type": 1}),
+        ] {
+            let answer = Prompt::json_schema(&schema);
+            assert!(
+                matches!(answer, Err(PromptError::Validation(_))),
+                "{}",
+                schema
+            );
+        }
+    }
+
+    #[test]
+    fn names_become_prompts() {
+        assert_eq!(humanize("listen_port"), "Listen port");
+        assert_eq!(humanize("listen-port"), "Listen port");
+        assert_eq!(humanize("listenPort"), "Listen port");
+        assert_eq!(humanize("userID"), "User ID");
+        assert_eq!(humanize("HTTP_proxy"), "HTTP proxy");
+        assert_eq!(humanize("_private_"), "Private");
+        assert_eq!(humanize("__"), "__");
+    }
+}
@@ -0,0 +1,1695 @@
+//! Interactive list prompts driven by the arrow keys.
+
+use crate::builder::{self, Attempts, NonInteractive, Validator};
+use crate::defaults;
+use crate::fuzzy;
+use crate::keymap::{self, Action, Keymap};
+use crate::locale::{fill, locale};
+use crate::session;
+use crate::term::{self, out, outln, Frame, Key, Output, RawMode};
+use crate::theme::{self, Status, Style, Theme};
+use crate::wizard;
+use crate::{paint, print_error, read_input, Choice, PromptError};
+use std::ops::Range;
+
+/// How many matches the search prompt shows at once, at most.
+const VISIBLE: usize = 10;
+
+/// A caller's own check on the items picked in a [`MultiSelect`].
+type ListValidator = Box<dyn Fn(&[&str]) -> Result<(), String>>;
+
+/// What to show under a menu about its highlighted item.
+type Preview = Box<dyn Fn(&str) -> String>;
+
+/// A menu with a movable highlight, created through [`Prompt::menu`](crate::Prompt::menu).
+///
+/// Up/Down (or `k`/`j`) move the highlight, Home/End jump to either end, and Enter
+/// picks the highlighted item, unless the [`Keymap`] says otherwise. A menu too
+/// long for the terminal is shown a page at a time, with PageUp/PageDown to flip
+/// through them. When stdin or stderr isn't a terminal, the items are printed as a
+/// numbered list and the pick is read as a number instead.
+pub struct Menu {
+    prompt: String,
+    pub(crate) items: Vec<String>,
+    headers: Vec<(usize, String)>,
+    columns: bool,
+    default: usize,
+    style: Option<Style>,
+    validators: Vec<Validator>,
+    max_attempts: Option<usize>,
+    default_on_eof: bool,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    keymap: Option<Keymap>,
+    output: Option<Output>,
+    preview: Option<Preview>,
+}
+
+impl Menu {
+    pub(crate) fn new(prompt: &str, items: &[&str]) -> Self {
+        Menu {
+            prompt: prompt.to_string(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            headers: Vec::new(),
+            columns: false,
+            default: 0,
+            style: None,
+            validators: Vec::new(),
+            max_attempts: None,
+            default_on_eof: false,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            keymap: None,
+            output: None,
+            preview: None,
+        }
+    }
+
+    /// Adds `items` after the ones so far, under a `header` that can't be picked,
+    /// like `Databases`. Indices still count every item in order, ungrouped ones
+    /// first.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let service = Prompt::menu("Service", &[])
+    ///     .group("Databases", &["Postgres", "MySQL"])
+    ///     .group("Caches", &["Redis", "Memcached"])
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn group(mut self, header: &str, items: &[&str]) -> Self {
+        self.headers.push((self.items.len(), header.to_string()));
+        self.items.extend(items.iter().map(|item| item.to_string()));
+        self
+    }
+
+    /// Lays the items out in as many columns as fit on the terminal, filled top to
+    /// bottom like `ls` does, so a long menu of short items doesn't take a line
+    /// each. Left/Right then move between columns. Menus with groups stay in one
+    /// column.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let states = ["AL", "AK", "AZ", "AR", "CA", "CO", "CT", "DE", "FL", "GA"];
+    /// let state = Prompt::menu("State", &states).columns().ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn columns(mut self) -> Self {
+        self.columns = true;
+        self
+    }
+
+    /// Sets which item is highlighted to start with, and picked on an empty answer
+    /// in the numbered fallback.
+    pub fn default(mut self, default: usize) -> Self {
+        self.default = default;
+        self
+    }
+
+    /// Sets the coloration of the highlighted item.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the highlighted item, in place of the theme's highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Adds a check on the text of the picked item. If it returns an error, the
+    /// message is shown and the menu stays open. Checks run in the order they were
+    /// added, whichever way the item was picked.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let region = Prompt::menu("Region", &["us-east", "eu-west", "ap-south (full)"])
+    ///     .validate(|item| match item.ends_with("(full)") {
+    ///         true => Err("That region has no capacity left.".to_string()),
+    ///         false => Ok(()),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(mut self, validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` picks have
+    /// been rejected, instead of asking forever.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Picks the default item when stdin is closed before a pick is made, rather
+    /// than returning [`PromptError::Eof`].
+    pub fn default_on_eof(mut self) -> Self {
+        self.default_on_eof = true;
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// pick is read from stdin as a number.
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the pick from the environment variable `var` when it's set and not
+    /// empty, without showing the menu. The value has to be the text of one of the
+    /// items, and is checked like a pick; a rejected one is returned as
+    /// [`PromptError::Validation`].
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the pick from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without showing the menu. The environment
+    /// variable set with `env` still comes first.
+    /// Arguments loaded with [`load_args`](crate::load_args) are taken before the
+    /// file.
+    /// The defaults set with [`set_defaults`](crate::set_defaults) are looked up by
+    /// `key` too.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap).
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Sets where the prompt is drawn, in place of the output set with
+    /// [`set_output`](crate::set_output).
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Shows what `preview` returns for the highlighted item under the menu,
+    /// updated as the highlight moves, like fzf's preview window. Only as many
+    /// lines as fit in half the terminal are shown.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # use std::process::Command;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let commits = ["3f2a9c1 Fix the login timeout", "9b07e44 Add a dark theme"];
+    /// let picked = Prompt::menu("Commit to revert", &commits)
+    ///     .preview(|commit| {
+    ///         let hash = commit.split(' ').next().unwrap_or_default();
+    ///         let output = Command::new("git").args(["show", "--stat", hash]).output();
+    ///         output.map_or_else(|err| err.to_string(), |output| {
+    ///             String::from_utf8_lossy(&output.stdout).into_owned()
+    ///         })
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn preview(mut self, preview: impl Fn(&str) -> String + 'static) -> Self {
+        self.preview = Some(Box::new(preview));
+        self
+    }
+
+    /// Shows the menu and returns the index of the picked item.
+    ///
+    /// Returns [`PromptError::Validation`] if there are no items to pick from.
+    pub fn ask(&self) -> Result<usize, PromptError> {
+        let provided = defaults::provided(&self.prompt, self.key.as_ref())
+            .and_then(|default| self.items.iter().position(|item| *item == default));
+        self.ask_from(provided.unwrap_or(self.default))
+    }
+
+    /// Like [`ask`](Menu::ask), with `default` highlighted in place of the menu's own.
+    pub(crate) fn ask_from(&self, default: usize) -> Result<usize, PromptError> {
+        let _output = term::draw_on(self.output);
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            if self.items.is_empty() {
+                return Err(PromptError::Validation(
+                    "there's nothing to pick from".into(),
+                ));
+            }
+            let check =
+                |picked: &[usize]| builder::validate(&self.validators, &self.items[picked[0]]);
+            if let Some(picked) = given_pick(
+                &self.prompt,
+                self.key.as_ref(),
+                self.env.as_ref(),
+                &self.items,
+                false,
+                check,
+            ) {
+                return picked.map(|picked| picked[0]);
+            }
+            let default = default.min(self.items.len() - 1);
+            if builder::assume_defaults() || self.non_interactive.use_default()? {
+                return Ok(default);
+            }
+
+            let picked = if term::redraws() {
+                self.ask_interactive(default)?
+            } else {
+                match self.ask_numbered(default) {
+                    Err(PromptError::Eof) if self.default_on_eof => {
+                        outln!()?;
+                        default
+                    }
+                    picked => picked?,
+                }
+            };
+            session::record(&self.prompt, &self.items[picked])?;
+            Ok(picked)
+        })
+    }
+
+    fn ask_interactive(&self, default: usize) -> Result<usize, PromptError> {
+        let keymap = self.keymap.clone().unwrap_or_else(keymap::keymap);
+        let raw = RawMode::enable()?;
+        let mut frame = Frame::new();
+        let mut cursor = default;
+        let mut error = None;
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        let width = self.columns && self.headers.is_empty();
+        let width = width.then(|| cell_width(&self.items, "> ".len()));
+        loop {
+            let preview = preview_lines(&self.preview, Some(&self.items[cursor]));
+            let size = page_size(RESERVED + self.headers.len() + preview.len());
+            let grid = width.and_then(|width| Grid::fit(width, self.items.len()));
+            let mut lines = self.lines(cursor, size, grid);
+            lines.extend(preview);
+            lines.extend(error_line(&error));
+            frame.draw(&lines)?;
+            let key = term::read_key()?;
+            error = None;
+            match keymap.action(key) {
+                Some(Action::Up) => cursor = cursor.saturating_sub(1),
+                Some(Action::Down) if cursor + 1 < self.items.len() => cursor += 1,
+                Some(Action::First) => cursor = 0,
+                Some(Action::Last) => cursor = self.items.len() - 1,
+                Some(Action::PageUp) => cursor = cursor.saturating_sub(size),
+                Some(Action::PageDown) => cursor = (cursor + size).min(self.items.len() - 1),
+                Some(Action::Left) => cursor = grid.map_or(cursor, |grid| grid.left(cursor)),
+                Some(Action::Right) => {
+                    cursor = grid.map_or(cursor, |grid| grid.right(cursor, self.items.len()))
+                }
+                Some(Action::Pick) => {
+                    match builder::validate(&self.validators, &self.items[cursor]) {
+                        Ok(()) => break,
+                        Err(message) => {
+                            attempts.reject()?;
+                            error = Some(message);
+                        }
+                    }
+                }
+                Some(Action::Cancel) => return cancel(&mut frame),
+                Some(Action::Back) if wizard::can_go_back() => return back(&mut frame),
+                _ => {}
+            }
+        }
+
+        frame.clear()?;
+        drop(raw);
+        outln!("{}", answered(&self.prompt, &self.items[cursor]))?;
+        Ok(cursor)
+    }
+
+    fn lines(&self, cursor: usize, size: usize, grid: Option<Grid>) -> Vec<String> {
+        let theme = theme::theme();
+        let mut lines = vec![format!("{} {}", question(&self.prompt), locale().menu_hint)];
+        let cell = |i: usize| {
+            let text = format!("{} {}", if i == cursor { ">" } else { " " }, self.items[i]);
+            let painted = match i == cursor {
+                true => paint(&text, &self.style, &theme.highlight_style),
+                false => text.clone(),
+            };
+            (text, painted)
+        };
+        if let Some(grid) = grid {
+            return grid.draw(lines, cursor, self.items.len(), size, cell);
+        }
+        let (shown, page_line) = page(cursor, self.items.len(), size);
+        for i in shown {
+            lines.extend(headers(&theme, &self.headers, i));
+            lines.push(cell(i).1);
+        }
+        lines.extend(page_line);
+        lines
+    }
+
+    fn ask_numbered(&self, default: usize) -> Result<usize, PromptError> {
+        outln!("{}", question(&self.prompt))?;
+        let theme = theme::theme();
+        for (i, item) in self.items.iter().enumerate() {
+            for header in headers(&theme, &self.headers, i) {
+                outln!("{}", header)?;
+            }
+            outln!("  {}) {}", i + 1, item)?;
+        }
+
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            let default_style = &theme.default_style;
+            out!(
+                "{} [{}]: ",
+                locale().enter_number,
+                default_style.paint(&(default + 1).to_string())
+            )?;
+            let input = read_input()?;
+            if wizard::goes_back(&input) {
+                return Err(PromptError::Back);
+            }
+            let picked = match parse_numbers(&input, self.items.len()).as_deref() {
+                _ if input.is_empty() => default,
+                Some(&[picked]) => picked,
+                _ => {
+                    let count = self.items.len();
+                    let message = fill(&locale().pick_number, &[("count", &count)]);
+                    print_error(&message)?;
+                    attempts.reject()?;
+                    continue;
+                }
+            };
+            match builder::validate(&self.validators, &self.items[picked]) {
+                Ok(()) => return Ok(picked),
+                Err(message) => {
+                    print_error(&message)?;
+                    attempts.reject()?;
+                }
+            }
+        }
+    }
+}
+
+/// A checkbox list, created through [`Prompt::multi_select`](crate::Prompt::multi_select).
+///
+/// Up/Down (or `k`/`j`) move the cursor, Space toggles the item under it, and
+/// Enter confirms, unless the [`Keymap`] says otherwise. A list too long for the
+/// terminal is shown a page at a time, with PageUp/PageDown to flip through them.
+/// When stdin or stderr isn't a terminal, the items are printed as a numbered list
+/// and the selection is read as a line of numbers instead.
+pub struct MultiSelect {
+    prompt: String,
+    pub(crate) items: Vec<String>,
+    headers: Vec<(usize, String)>,
+    columns: bool,
+    defaults: Vec<bool>,
+    style: Option<Style>,
+    validators: Vec<ListValidator>,
+    max_attempts: Option<usize>,
+    default_on_eof: bool,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    keymap: Option<Keymap>,
+    output: Option<Output>,
+}
+
+impl MultiSelect {
+    pub(crate) fn new(prompt: &str, items: &[&str]) -> Self {
+        MultiSelect {
+            prompt: prompt.to_string(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            headers: Vec::new(),
+            columns: false,
+            defaults: Vec::new(),
+            style: None,
+            validators: Vec::new(),
+            max_attempts: None,
+            default_on_eof: false,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            keymap: None,
+            output: None,
+        }
+    }
+
+    /// Adds `items` after the ones so far, under a `header` that can't be checked,
+    /// like `Databases`. Indices still count every item in order, ungrouped ones
+    /// first.
+    pub fn group(mut self, header: &str, items: &[&str]) -> Self {
+        self.headers.push((self.items.len(), header.to_string()));
+        self.items.extend(items.iter().map(|item| item.to_string()));
+        self
+    }
+
+    /// Lays the items out in as many columns as fit on the terminal, filled top to
+    /// bottom like `ls` does. Left/Right then move between columns. Lists with
+    /// groups stay in one column.
+    pub fn columns(mut self) -> Self {
+        self.columns = true;
+        self
+    }
+
+    /// Sets which items start out checked. Missing entries count as unchecked.
+    pub fn defaults(mut self, defaults: &[bool]) -> Self {
+        self.defaults = defaults.to_vec();
+        self
+    }
+
+    /// Sets the coloration of the item under the cursor.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the item under the cursor, in place of the theme's highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Adds a check on the text of the checked items, in order. If it returns an
+    /// error, the message is shown and the list stays open. Checks run in the order
+    /// they were added.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let toppings = Prompt::multi_select("Toppings", &["cheese", "olives", "ham"])
+    ///     .validate(|picked| match picked.len() {
+    ///         1..=2 => Ok(()),
+    ///         _ => Err("Pick one or two toppings.".to_string()),
+    ///     })
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(mut self, validator: impl Fn(&[&str]) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` picks have
+    /// been rejected, instead of asking forever.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Keeps the default selection when stdin is closed before one is made, rather
+    /// than returning [`PromptError::Eof`].
+    pub fn default_on_eof(mut self) -> Self {
+        self.default_on_eof = true;
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// selection is read from stdin as a line of numbers.
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the selection from the environment variable `var` when it's set and not
+    /// empty, without showing the list. The value has to be the text of the items
+    /// to check, separated by commas or as a JSON array of strings, and is checked
+    /// like a selection; a rejected one is returned as [`PromptError::Validation`].
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the pick from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without showing the list. The environment
+    /// variable set with `env` still comes first.
+    /// Arguments loaded with [`load_args`](crate::load_args) are taken before the
+    /// file.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap).
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Sets where the prompt is drawn, in place of the output set with
+    /// [`set_output`](crate::set_output).
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Shows the list and returns the indices of the checked items, in order.
+    pub fn ask(&self) -> Result<Vec<usize>, PromptError> {
+        self.ask_from(&self.defaults)
+    }
+
+    /// Like [`ask`](MultiSelect::ask), with the items in `defaults` checked to start
+    /// with in place of the list's own.
+    pub(crate) fn ask_from(&self, defaults: &[bool]) -> Result<Vec<usize>, PromptError> {
+        let _output = term::draw_on(self.output);
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            let mut checked: Vec<bool> = (0..self.items.len())
+                .map(|i| defaults.get(i).copied().unwrap_or(false))
+                .collect();
+
+            let check = |picked: &[usize]| {
+                let mut checked = vec![false; self.items.len()];
+                picked.iter().for_each(|&i| checked[i] = true);
+                self.check(&checked)
+            };
+            if let Some(picked) = given_pick(
+                &self.prompt,
+                self.key.as_ref(),
+                self.env.as_ref(),
+                &self.items,
+                true,
+                check,
+            ) {
+                return picked;
+            }
+            let indices = |checked: &[bool]| (0..checked.len()).filter(|&i| checked[i]).collect();
+            if builder::assume_defaults() || self.non_interactive.use_default()? {
+                return Ok(indices(&checked));
+            }
+
+            if term::redraws() {
+                self.ask_interactive(&mut checked)?;
+            } else {
+                match self.ask_numbered(&mut checked) {
+                    Err(PromptError::Eof) if self.default_on_eof => outln!()?,
+                    result => result?,
+                }
+            }
+
+            session::record(&self.prompt, &session::list(&self.picked(&checked)))?;
+            Ok(indices(&checked))
+        })
+    }
+
+    fn ask_interactive(&self, checked: &mut [bool]) -> Result<(), PromptError> {
+        let keymap = self.keymap.clone().unwrap_or_else(keymap::keymap);
+        let raw = RawMode::enable()?;
+        let mut frame = Frame::new();
+        let mut cursor = 0;
+        let mut error = None;
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        let width = self.columns && self.headers.is_empty();
+        let width = width.then(|| cell_width(&self.items, "> [x] ".len()));
+        loop {
+            let size = page_size(RESERVED + self.headers.len());
+            let grid = width.and_then(|width| Grid::fit(width, self.items.len()));
+            let mut lines = self.lines(checked, cursor, size, grid);
+            lines.extend(error_line(&error));
+            frame.draw(&lines)?;
+            let key = term::read_key()?;
+            error = None;
+            match keymap.action(key) {
+                Some(Action::Up) => cursor = cursor.saturating_sub(1),
+                Some(Action::Down) if cursor + 1 < self.items.len() => cursor += 1,
+                Some(Action::First) => cursor = 0,
+                Some(Action::Last) => cursor = self.items.len().saturating_sub(1),
+                Some(Action::PageUp) => cursor = cursor.saturating_sub(size),
+                Some(Action::PageDown) => {
+                    cursor = (cursor + size).min(self.items.len().saturating_sub(1))
+                }
+                Some(Action::Left) => cursor = grid.map_or(cursor, |grid| grid.left(cursor)),
+                Some(Action::Right) => {
+                    cursor = grid.map_or(cursor, |grid| grid.right(cursor, self.items.len()))
+                }
+                Some(Action::Toggle) if !checked.is_empty() => checked[cursor] = !checked[cursor],
+                Some(Action::Pick) => match self.check(checked) {
+                    Ok(()) => break,
+                    Err(message) => {
+                        attempts.reject()?;
+                        error = Some(message);
+                    }
+                },
+                Some(Action::Cancel) => return cancel(&mut frame),
+                Some(Action::Back) if wizard::can_go_back() => return back(&mut frame),
+                _ => {}
+            }
+        }
+
+        frame.clear()?;
+        drop(raw);
+        outln!(
+            "{}",
+            answered(&self.prompt, &self.picked(checked).join(", "))
+        )?;
+        Ok(())
+    }
+
+    fn check(&self, checked: &[bool]) -> Result<(), String> {
+        let picked = self.picked(checked);
+        self.validators
+            .iter()
+            .try_for_each(|validator| validator(&picked))
+    }
+
+    fn lines(
+        &self,
+        checked: &[bool],
+        cursor: usize,
+        size: usize,
+        grid: Option<Grid>,
+    ) -> Vec<String> {
+        let theme = theme::theme();
+        let mut lines = vec![format!(
+            "{} {}",
+            question(&self.prompt),
+            locale().multi_select_hint
+        )];
+        let cell = |i: usize| {
+            let mark = if checked[i] { "[x]" } else { "[ ]" };
+            let pointer = if i == cursor { ">" } else { " " };
+            let text = format!("{} {} {}", pointer, mark, self.items[i]);
+            let painted = match i == cursor {
+                true => paint(&text, &self.style, &theme.highlight_style),
+                false => text.clone(),
+            };
+            (text, painted)
+        };
+        if let Some(grid) = grid {
+            return grid.draw(lines, cursor, self.items.len(), size, cell);
+        }
+        let (shown, page_line) = page(cursor, self.items.len(), size);
+        for i in shown {
+            lines.extend(headers(&theme, &self.headers, i));
+            lines.push(cell(i).1);
+        }
+        lines.extend(page_line);
+        lines
+    }
+
+    fn picked(&self, checked: &[bool]) -> Vec<&str> {
+        self.items
+            .iter()
+            .zip(checked)
+            .filter(|(_, &checked)| checked)
+            .map(|(item, _)| item.as_str())
+            .collect()
+    }
+
+    fn ask_numbered(&self, checked: &mut [bool]) -> Result<(), PromptError> {
+        outln!("{}", question(&self.prompt))?;
+        let theme = theme::theme();
+        for (i, item) in self.items.iter().enumerate() {
+            for header in headers(&theme, &self.headers, i) {
+                outln!("{}", header)?;
+            }
+            let mark = if checked[i] { "[x]" } else { "[ ]" };
+            outln!("  {}) {} {}", i + 1, mark, item)?;
+        }
+
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            out!("{}: ", locale().enter_numbers)?;
+            let input = read_input()?;
+            if wizard::goes_back(&input) {
+                return Err(PromptError::Back);
+            }
+            let mut picked = checked.to_vec();
+            if !input.is_empty() {
+                let Some(numbers) = parse_numbers(&input, self.items.len()) else {
+                    let count = self.items.len();
+                    print_error(&fill(&locale().pick_numbers, &[("count", &count)]))?;
+                    attempts.reject()?;
+                    continue;
+                };
+                picked.iter_mut().for_each(|c| *c = false);
+                numbers.into_iter().for_each(|i| picked[i] = true);
+            }
+            match self.check(&picked) {
+                Ok(()) => {
+                    checked.copy_from_slice(&picked);
+                    return Ok(());
+                }
+                Err(message) => {
+                    print_error(&message)?;
+                    attempts.reject()?;
+                }
+            }
+        }
+    }
+}
+
+/// A list put in order, created through [`Prompt::ranking`](crate::Prompt::ranking).
+///
+/// Up/Down (or `k`/`j`) move the cursor, Shift+K/Shift+J move the item under it up
+/// or down, and Enter confirms the order, unless the [`Keymap`] says otherwise.
+/// Space picks the item up, so that Up/Down carry it until Space puts it down
+/// again. A list too long for the terminal is shown a page at a time. When stdin or
+/// stderr isn't a terminal, the items are printed as a numbered list and the order
+/// is read as a line of numbers instead.
+pub struct Ranking {
+    prompt: String,
+    pub(crate) items: Vec<String>,
+    style: Option<Style>,
+    max_attempts: Option<usize>,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    keymap: Option<Keymap>,
+    output: Option<Output>,
+}
+
+impl Ranking {
+    pub(crate) fn new(prompt: &str, items: &[&str]) -> Self {
+        Ranking {
+            prompt: prompt.to_string(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            style: None,
+            max_attempts: None,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            keymap: None,
+            output: None,
+        }
+    }
+
+    /// Sets the coloration of the item under the cursor.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the item under the cursor, in place of the theme's highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` orders have
+    /// been rejected, instead of asking forever.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// order is read from stdin as a line of numbers.
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the order from the environment variable `var` when it's set and not
+    /// empty, without showing the list. The value has to be the text of the items,
+    /// separated by commas or as a JSON array of strings; any left out keep their
+    /// order after the rest. One that names an unknown item is returned as
+    /// [`PromptError::Validation`].
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the order from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without showing the list. The environment
+    /// variable set with `env` still comes first.
+    /// Arguments loaded with [`load_args`](crate::load_args) are taken before the
+    /// file.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap).
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Sets where the prompt is drawn, in place of the output set with
+    /// [`set_output`](crate::set_output).
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Shows the list and returns the indices of the items in the order they were
+    /// put in, first to last.
+    pub fn ask(&self) -> Result<Vec<usize>, PromptError> {
+        self.ask_from(&[])
+    }
+
+    /// Like [`ask`](Ranking::ask), with the items in `first` put at the top to start
+    /// with, in that order.
+    pub(crate) fn ask_from(&self, first: &[usize]) -> Result<Vec<usize>, PromptError> {
+        let _output = term::draw_on(self.output);
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            if let Some(order) = self.given_order() {
+                return order;
+            }
+            let mut order = put_first(first, self.items.len());
+            if builder::assume_defaults() || self.non_interactive.use_default()? {
+                return Ok(order);
+            }
+
+            if term::redraws() {
+                self.ask_interactive(&mut order)?;
+            } else {
+                self.ask_numbered(&mut order)?;
+            }
+            session::record(&self.prompt, &session::list(&self.names(&order)))?;
+            Ok(order)
+        })
+    }
+
+    /// The order given up front, from the environment variable, the answers file or
+    /// a replayed session, and recorded if it names only known items.
+    fn given_order(&self) -> Option<Result<Vec<usize>, PromptError>> {
+        let (origin, value) =
+            builder::given_answer(&self.prompt, self.key.as_ref(), self.env.as_ref(), false)?;
+        let named = session::items(&value, ',')
+            .iter()
+            .map(|name| {
+                self.items
+                    .iter()
+                    .position(|item| item == name)
+                    .ok_or_else(|| format!("there's no {:?} to rank", name))
+            })
+            .collect::<Result<Vec<usize>, String>>();
+        Some(match named {
+            Ok(named) => {
+                let order = put_first(&named, self.items.len());
+                session::record(&self.prompt, &value).map(|()| order)
+            }
+            Err(message) => Err(builder::rejected(origin, message)),
+        })
+    }
+
+    fn ask_interactive(&self, order: &mut [usize]) -> Result<(), PromptError> {
+        let keymap = self.keymap.clone().unwrap_or_else(keymap::keymap);
+        let raw = RawMode::enable()?;
+        let mut frame = Frame::new();
+        let mut cursor = 0;
+        let mut held = false;
+        let last = order.len().saturating_sub(1);
+        loop {
+            let size = page_size(RESERVED);
+            frame.draw(&self.lines(order, cursor, held, size))?;
+            let moved = match keymap.action(term::read_key()?) {
+                Some(Action::Up) if held => Some(cursor.saturating_sub(1)),
+                Some(Action::Down) if held => Some((cursor + 1).min(last)),
+                Some(Action::First) if held => Some(0),
+                Some(Action::Last) if held => Some(last),
+                Some(Action::PageUp) if held => Some(cursor.saturating_sub(size)),
+                Some(Action::PageDown) if held => Some((cursor + size).min(last)),
+                Some(Action::Up) => {
+                    cursor = cursor.saturating_sub(1);
+                    None
+                }
+                Some(Action::Down) => {
+                    cursor = (cursor + 1).min(last);
+                    None
+                }
+                Some(Action::First) => {
+                    cursor = 0;
+                    None
+                }
+                Some(Action::Last) => {
+                    cursor = last;
+                    None
+                }
+                Some(Action::PageUp) => {
+                    cursor = cursor.saturating_sub(size);
+                    None
+                }
+                Some(Action::PageDown) => {
+                    cursor = (cursor + size).min(last);
+                    None
+                }
+                Some(Action::MoveUp) => Some(cursor.saturating_sub(1)),
+                Some(Action::MoveDown) => Some((cursor + 1).min(last)),
+                Some(Action::Toggle) => {
+                    held = !held && !order.is_empty();
+                    None
+                }
+                Some(Action::Pick) => break,
+                Some(Action::Cancel) => return cancel(&mut frame),
+                Some(Action::Back) if wizard::can_go_back() => return back(&mut frame),
+                _ => None,
+            };
+            if let Some(to) = moved.filter(|_| !order.is_empty()) {
+                move_item(order, cursor, to);
+                cursor = to;
+            }
+        }
+
+        frame.clear()?;
+        drop(raw);
+        outln!("{}", answered(&self.prompt, &self.names(order).join(", ")))?;
+        Ok(())
+    }
+
+    fn lines(&self, order: &[usize], cursor: usize, held: bool, size: usize) -> Vec<String> {
+        let theme = theme::theme();
+        let locale = locale();
+        let mut lines = vec![format!(
+            "{} {}",
+            question(&self.prompt),
+            locale.ranking_hint
+        )];
+        let (shown, page_line) = page(cursor, order.len(), size);
+        for (i, &item) in order.iter().enumerate().skip(shown.start).take(shown.len()) {
+            if i == cursor {
+                let line = match held {
+                    true => format!("> {}. {} {}", i + 1, self.items[item], locale.moving),
+                    false => format!("> {}. {}", i + 1, self.items[item]),
+                };
+                lines.push(paint(&line, &self.style, &theme.highlight_style));
+            } else {
+                lines.push(format!("  {}. {}", i + 1, self.items[item]));
+            }
+        }
+        lines.extend(page_line);
+        lines
+    }
+
+    fn names(&self, order: &[usize]) -> Vec<&str> {
+        order.iter().map(|&i| self.items[i].as_str()).collect()
+    }
+
+    fn ask_numbered(&self, order: &mut [usize]) -> Result<(), PromptError> {
+        outln!("{}", question(&self.prompt))?;
+        for (i, item) in self.items.iter().enumerate() {
+            outln!("  {}) {}", i + 1, item)?;
+        }
+
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            out!("{}: ", locale().enter_order)?;
+            let input = read_input()?;
+            if wizard::goes_back(&input) {
+                return Err(PromptError::Back);
+            }
+            if input.is_empty() {
+                return Ok(());
+            }
+            match parse_numbers(&input, self.items.len()) {
+                Some(numbers) => {
+                    order.copy_from_slice(&put_first(&numbers, self.items.len()));
+                    return Ok(());
+                }
+                None => {
+                    let count = self.items.len();
+                    print_error(&fill(&locale().pick_numbers, &[("count", &count)]))?;
+                    attempts.reject()?;
+                }
+            }
+        }
+    }
+}
+
+/// Moves the item at `from` in `order` to `to`, shifting the ones in between.
+fn move_item(order: &mut [usize], from: usize, to: usize) {
+    if from < to {
+        order[from..=to].rotate_left(1);
+    } else {
+        order[to..=from].rotate_right(1);
+    }
+}
+
+/// The order of `count` items with `first` at the front, leaving out repeats, and
+/// the rest after them in their own order.
+fn put_first(first: &[usize], count: usize) -> Vec<usize> {
+    let mut order = Vec::with_capacity(count);
+    for i in first.iter().copied().chain(0..count) {
+        if !order.contains(&i) {
+            order.push(i);
+        }
+    }
+    order
+}
+
+/// The pick given up front, from the environment variable `env`, the answers file
+/// under `key` or a replayed session, as a list of the items' text if `many` is
+/// set. Runs `check` on their indices, and records the pick if it's
+/// accepted.
+fn given_pick(
+    prompt: &str,
+    key: Option<&String>,
+    env: Option<&String>,
+    items: &[String],
+    many: bool,
+    check: impl Fn(&[usize]) -> Result<(), String>,
+) -> Option<Result<Vec<usize>, PromptError>> {
+    let (origin, value) = builder::given_answer(prompt, key, env, false)?;
+    let names = match many {
+        true => session::items(&value, ','),
+        false => vec![value.trim().to_string()],
+    };
+
+    let picked = names
+        .iter()
+        .map(|name| {
+            items
+                .iter()
+                .position(|item| item == name)
+                .ok_or_else(|| format!("there's no {:?} to pick", name))
+        })
+        .collect::<Result<Vec<usize>, String>>()
+        .and_then(|mut picked| {
+            picked.sort_unstable();
+            picked.dedup();
+            check(&picked).map(|()| picked)
+        });
+    Some(match picked {
+        Ok(picked) => session::record(prompt, &value).map(|()| picked),
+        Err(message) => Err(builder::rejected(origin, message)),
+    })
+}
+
+/// `prompt` in the theme's prompt style, after its symbol for asking.
+pub(crate) fn question(prompt: &str) -> String {
+    let theme = theme::theme();
+    format!(
+        "{}{}",
+        theme.mark(Status::Asking),
+        theme.prompt_style.paint(prompt)
+    )
+}
+
+/// The line left behind once `answer` is picked for `prompt`.
+pub(crate) fn answered(prompt: &str, answer: &str) -> String {
+    let theme = theme::theme();
+    if let Some(summary) = theme.summary(Status::Answered, prompt, answer) {
+        return summary;
+    }
+    format!(
+        "{}{}: {}",
+        theme.mark(Status::Answered),
+        theme.prompt_style.paint(prompt),
+        theme.answer_style.paint(answer)
+    )
+}
+
+/// Wipes `frame` for a prompt given up on with [`Action::Cancel`].
+pub(crate) fn cancel<T>(frame: &mut Frame) -> Result<T, PromptError> {
+    frame.clear()?;
+    Err(PromptError::Cancelled)
+}
+
+/// Wipes `frame` for a wizard step left with [`Action::Back`].
+fn back<T>(frame: &mut Frame) -> Result<T, PromptError> {
+    frame.clear()?;
+    Err(PromptError::Back)
+}
+
+/// How many lines a list takes up besides its items and headers: the question, the
+/// page it's on, and why a pick was rejected.
+pub(crate) const RESERVED: usize = 3;
+
+/// How many items of a list fit on the terminal at once, when `reserved` of its
+/// lines aren't items.
+pub(crate) fn page_size(reserved: usize) -> usize {
+    term::size().1.saturating_sub(reserved).max(1)
+}
+
+/// Which of `count` items to show, a page of `size` at a time, for the one at
+/// `cursor` to be on screen. When they don't all fit, there's also a line saying
+/// which page it is, like `page 2/5`.
+pub(crate) fn page(cursor: usize, count: usize, size: usize) -> (Range<usize>, Option<String>) {
+    if count <= size {
+        return (0..count, None);
+    }
+    let page = cursor / size;
+    let start = page * size;
+    let pages = count.div_ceil(size);
+    let line = format!(
+        "  {}",
+        fill(&locale().page, &[("page", &(page + 1)), ("pages", &pages)])
+    );
+    (start..count.min(start + size), Some(line))
+}
+
+/// How wide a column of `items` has to be, each after a `prefix` characters wide,
+/// with a gap of two before the next column.
+fn cell_width(items: &[String], prefix: usize) -> usize {
+    let widest = items.iter().map(|item| term::width(item)).max();
+    prefix + widest.unwrap_or(0) + 2
+}
+
+/// Items laid out in columns, filled top to bottom like `ls` does.
+#[derive(Clone, Copy)]
+struct Grid {
+    /// How many rows the columns have.
+    rows: usize,
+    /// How wide each column is, gap included.
+    width: usize,
+}
+
+impl Grid {
+    /// As many columns `width` wide as fit on the terminal for `count` items, or
+    /// `None` if there's only room for one.
+    fn fit(width: usize, count: usize) -> Option<Grid> {
+        let columns = (term::size().0 / width).min(count);
+        if columns < 2 {
+            return None;
+        }
+        Some(Grid {
+            rows: count.div_ceil(columns),
+            width,
+        })
+    }
+
+    /// The item a column left of `cursor`, if there is one.
+    fn left(self, cursor: usize) -> usize {
+        cursor.checked_sub(self.rows).unwrap_or(cursor)
+    }
+
+    /// The item a column right of `cursor`, if there is one.
+    fn right(self, cursor: usize, count: usize) -> usize {
+        Some(cursor + self.rows)
+            .filter(|&i| i < count)
+            .unwrap_or(cursor)
+    }
+
+    /// Adds the rows of the grid to `lines`, a page of `size` at a time for the
+    /// one with `cursor` in it to show. `cell` gives the text of an item and how
+    /// it's drawn.
+    fn draw(
+        self,
+        mut lines: Vec<String>,
+        cursor: usize,
+        count: usize,
+        size: usize,
+        cell: impl Fn(usize) -> (String, String),
+    ) -> Vec<String> {
+        let (shown, page_line) = page(cursor % self.rows, self.rows, size);
+        for row in shown {
+            let mut line = String::new();
+            let mut pad = 0;
+            for i in (row..count).step_by(self.rows) {
+                let (text, painted) = cell(i);
+                line.push_str(&" ".repeat(pad));
+                line.push_str(&painted);
+                pad = self.width.saturating_sub(term::width(&text));
+            }
+            lines.push(line);
+        }
+        lines.extend(page_line);
+        lines
+    }
+}
+
+/// The headers of the groups starting at item `index`, in the theme's header style.
+fn headers(theme: &Theme, headers: &[(usize, String)], index: usize) -> Vec<String> {
+    headers
+        .iter()
+        .filter(|(start, _)| *start == index)
+        .map(|(_, header)| theme.header_style.paint(header))
+        .collect()
+}
+
+/// What `preview` has to show about the `highlighted` item, after a blank line,
+/// cut down to fit in half the terminal.
+fn preview_lines(preview: &Option<Preview>, highlighted: Option<&str>) -> Vec<String> {
+    let Some(text) = preview
+        .as_ref()
+        .zip(highlighted)
+        .map(|(preview, item)| preview(item))
+    else {
+        return Vec::new();
+    };
+    // Lines that wrap would throw off how far the frame thinks it has to go back up.
+    let (columns, rows) = term::size();
+    let shown = text.lines().take(rows / 2).map(|line| {
+        let line = line.replace('\t', "    ");
+        let line: String = line.chars().take(columns.saturating_sub(3)).collect();
+        format!("  {}", line)
+    });
+    [String::new()].into_iter().chain(shown).collect()
+}
+
+/// The line showing why a pick was rejected, if it was.
+fn error_line(error: &Option<String>) -> Option<String> {
+    let theme = theme::theme();
+    error
+        .as_ref()
+        .map(|message| theme.error_style.paint(message))
+}
+
+/// Parses 1-based item numbers into indices, or `None` if any are out of range.
+pub(crate) fn parse_numbers(input: &str, count: usize) -> Option<Vec<usize>> {
+    input
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|part| !part.is_empty())
+        .map(|part| match part.parse::<usize>() {
+            Ok(n) if (1..=count).contains(&n) => Some(n - 1),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A menu filtered by typing, created through
+/// [`Prompt::fuzzy_select`](crate::Prompt::fuzzy_select).
+///
+/// Typed characters narrow the list down to the items containing them in order,
+/// best match first, like fzf. Up/Down move the highlight and Enter picks it, unless
+/// the [`Keymap`] says otherwise.
+/// When stdin or stderr isn't a terminal, a line is read as the search and the
+/// best match is picked.
+pub struct FuzzySelect {
+    prompt: String,
+    pub(crate) items: Vec<String>,
+    style: Option<Style>,
+    validators: Vec<Validator>,
+    max_attempts: Option<usize>,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    keymap: Option<Keymap>,
+    output: Option<Output>,
+    preview: Option<Preview>,
+}
+
+impl FuzzySelect {
+    pub(crate) fn new(prompt: &str, items: &[&str]) -> Self {
+        FuzzySelect {
+            prompt: prompt.to_string(),
+            items: items.iter().map(|item| item.to_string()).collect(),
+            style: None,
+            validators: Vec::new(),
+            max_attempts: None,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            keymap: None,
+            output: None,
+            preview: None,
+        }
+    }
+
+    /// Sets the coloration of the highlighted item.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the highlighted item, in place of the theme's highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    /// Adds a check on the text of the picked item. If it returns an error, the
+    /// message is shown and the search stays open. Checks run in the order they
+    /// were added.
+    pub fn validate(mut self, validator: impl Fn(&str) -> Result<(), String> + 'static) -> Self {
+        self.validators.push(Box::new(validator));
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` picks have
+    /// been rejected, instead of asking forever.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// search is read from stdin as a line. There's no default to fall back on, so
+    /// [`NonInteractive::Default`] fails like [`NonInteractive::Fail`].
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the pick from the environment variable `var` when it's set and not
+    /// empty, without showing the search. The value has to be the text of one of the
+    /// items, and is checked like a pick; a rejected one is returned as
+    /// [`PromptError::Validation`].
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the pick from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without showing the search. The environment
+    /// variable set with `env` still comes first.
+    /// Arguments loaded with [`load_args`](crate::load_args) are taken before the
+    /// file.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap). Typed characters always go to the
+    /// search.
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Sets where the prompt is drawn, in place of the output set with
+    /// [`set_output`](crate::set_output).
+    pub fn output(mut self, output: Output) -> Self {
+        self.output = Some(output);
+        self
+    }
+
+    /// Shows what `preview` returns for the highlighted match under the list,
+    /// updated as the highlight moves, like fzf's preview window. Only as many
+    /// lines as fit in half the terminal are shown.
+    pub fn preview(mut self, preview: impl Fn(&str) -> String + 'static) -> Self {
+        self.preview = Some(Box::new(preview));
+        self
+    }
+
+    /// Shows the search and returns the index of the picked item in the original list.
+    ///
+    /// Returns [`PromptError::Validation`] if there are no items to pick from.
+    pub fn ask(&self) -> Result<usize, PromptError> {
+        let _output = term::draw_on(self.output);
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            if self.items.is_empty() {
+                return Err(PromptError::Validation(
+                    "there's nothing to pick from".into(),
+                ));
+            }
+            let check =
+                |picked: &[usize]| builder::validate(&self.validators, &self.items[picked[0]]);
+            if let Some(picked) = given_pick(
+                &self.prompt,
+                self.key.as_ref(),
+                self.env.as_ref(),
+                &self.items,
+                false,
+                check,
+            ) {
+                return picked.map(|picked| picked[0]);
+            }
+            if self.non_interactive.use_default()? {
+                return Err(PromptError::NotInteractive);
+            }
+
+            let picked = if term::redraws() {
+                self.ask_interactive()?
+            } else {
+                self.ask_line()?
+            };
+            session::record(&self.prompt, &self.items[picked])?;
+            Ok(picked)
+        })
+    }
+
+    fn ask_interactive(&self) -> Result<usize, PromptError> {
+        let keymap = self.keymap.clone().unwrap_or_else(keymap::keymap);
+        let raw = RawMode::enable()?;
+        let mut frame = Frame::new();
+        let mut query = String::new();
+        let mut matches = fuzzy::filter(&query, &self.items);
+        let mut cursor = 0;
+        let mut error = None;
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            let highlighted = matches.get(cursor).map(|&i| self.items[i].as_str());
+            let preview = preview_lines(&self.preview, highlighted);
+            let visible = VISIBLE.min(page_size(RESERVED + preview.len()));
+            let mut lines = self.lines(&query, &matches, cursor, visible);
+            lines.extend(preview);
+            lines.extend(error_line(&error));
+            frame.draw(&lines)?;
+            let column = term::width(&question(&self.prompt)) + 2 + term::width(&query);
+            frame.place_cursor(0, column)?;
+
+            let key = term::read_key()?;
+            error = None;
+            let action = match key {
+                Key::Char(_) => None,
+                key => keymap.action(key),
+            };
+            let edited = match (action, key) {
+                (Some(Action::Up), _) => {
+                    cursor = cursor.saturating_sub(1);
+                    false
+                }
+                (Some(Action::Down), _) => {
+                    if cursor + 1 < matches.len() {
+                        cursor += 1;
+                    }
+                    false
+                }
+                (Some(Action::First), _) => {
+                    cursor = 0;
+                    false
+                }
+                (Some(Action::Last), _) => {
+                    cursor = matches.len().saturating_sub(1);
+                    false
+                }
+                (Some(Action::Pick), _) if !matches.is_empty() => {
+                    match builder::validate(&self.validators, &self.items[matches[cursor]]) {
+                        Ok(()) => break,
+                        Err(message) => {
+                            attempts.reject()?;
+                            error = Some(message);
+                        }
+                    }
+                    false
+                }
+                (Some(Action::Cancel), _) => return cancel(&mut frame),
+                (Some(Action::Back), _) if wizard::can_go_back() => return back(&mut frame),
+                (Some(_), _) => false,
+                (None, Key::Backspace) => query.pop().is_some(),
+                (None, Key::Ctrl('u')) => {
+                    query.clear();
+                    true
+                }
+                (None, Key::Char(c)) => {
+                    query.push(c);
+                    true
+                }
+                _ => false,
+            };
+            if edited {
+                matches = match key {
+                    Key::Char(_) => fuzzy::refine(&query, &self.items, &matches),
+                    _ => fuzzy::filter(&query, &self.items),
+                };
+                cursor = 0;
+            }
+        }
+
+        frame.clear()?;
+        drop(raw);
+        let picked = matches[cursor];
+        outln!("{}", answered(&self.prompt, &self.items[picked]))?;
+        Ok(picked)
+    }
+
+    fn lines(&self, query: &str, matches: &[usize], cursor: usize, visible: usize) -> Vec<String> {
+        let theme = theme::theme();
+        let mut lines = vec![format!("{}: {}", question(&self.prompt), query)];
+        let start = cursor.saturating_sub(visible - 1);
+        for (i, &item) in matches.iter().enumerate().skip(start).take(visible) {
+            if i == cursor {
+                let line = format!("> {}", self.items[item]);
+                lines.push(paint(&line, &self.style, &theme.highlight_style));
+            } else {
+                lines.push(format!("  {}", self.items[item]));
+            }
+        }
+        if matches.is_empty() {
+            lines.push(format!("  {}", locale().no_matches));
+        }
+        lines.push(format!("  {}/{}", matches.len(), self.items.len()));
+        lines
+    }
+
+    fn ask_line(&self) -> Result<usize, PromptError> {
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            out!("{}: ", question(&self.prompt))?;
+            let query = read_input()?;
+            if wizard::goes_back(&query) {
+                return Err(PromptError::Back);
+            }
+            let Some(&best) = fuzzy::filter(&query, &self.items).first() else {
+                print_error(&fill(&locale().nothing_matches, &[("query", &query)]))?;
+                attempts.reject()?;
+                continue;
+            };
+            match builder::validate(&self.validators, &self.items[best]) {
+                Ok(()) => return Ok(best),
+                Err(message) => {
+                    print_error(&message)?;
+                    attempts.reject()?;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{Key, MockTerminal};
+    use crate::Prompt;
+
+    #[test]
+    fn numbers_are_one_based() {
+        assert_eq!(parse_numbers("1, 3", 3), Some(vec![0, 2]));
+        assert_eq!(parse_numbers(" 2 1,,3 ", 3), Some(vec![1, 0, 2]));
+        assert_eq!(parse_numbers("", 3), Some(vec![]));
+    }
+
+    #[test]
+    fn numbers_must_name_items() {
+        assert_eq!(parse_numbers("0", 3), None);
+        assert_eq!(parse_numbers("4", 3), None);
+        assert_eq!(parse_numbers("1, x", 3), None);
+        assert_eq!(parse_numbers("-1", 3), None);
+    }
+
+    #[test]
+    fn moving_shifts_the_items_between() {
+        let mut order = [0, 1, 2, 3];
+        move_item(&mut order, 0, 2);
+        assert_eq!(order, [1, 2, 0, 3]);
+
+        let mut order = [0, 1, 2, 3];
+        move_item(&mut order, 3, 1);
+        assert_eq!(order, [0, 3, 1, 2]);
+
+        let mut order = [0, 1, 2, 3];
+        move_item(&mut order, 2, 2);
+        assert_eq!(order, [0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn put_first_skips_repeats() {
+        assert_eq!(put_first(&[2, 2, 0], 4), [2, 0, 1, 3]);
+        assert_eq!(put_first(&[], 3), [0, 1, 2]);
+    }
+
+    #[test]
+    fn ranking_moves_the_held_item() {
+        let mut terminal = MockTerminal::new()
+            .press(Key::Char(' '))
+            .press(Key::Down)
+            .press(Key::Down)
+            .press(Key::Char(' '))
+            .press(Key::Enter);
+        let order = terminal.run(|| Prompt::ranking("Order", &["a", "b", "c"]).ask());
+        assert_eq!(order.unwrap(), [1, 2, 0]);
+        assert_eq!(terminal.screen(), "Order: b, c, a");
+    }
+
+    #[test]
+    fn ranking_moves_with_shift() {
+        let mut terminal = MockTerminal::new()
+            .press(Key::End)
+            .press(Key::Char('K'))
+            .press(Key::Char('K'))
+            .press(Key::Enter);
+        let order = terminal.run(|| Prompt::ranking("Order", &["a", "b", "c"]).ask());
+        assert_eq!(order.unwrap(), [2, 0, 1]);
+    }
+
+    #[test]
+    fn picks_items_with_commas_from_a_list() {
+        std::env::set_var("CUMAEA_TEST_PICKED", r#"["a, b", " c "]"#);
+        let picked = Prompt::multi_select("Pick", &["a, b", " c ", "d"])
+            .env("CUMAEA_TEST_PICKED")
+            .ask();
+        assert_eq!(picked.unwrap(), [0, 1]);
+
+        std::env::set_var("CUMAEA_TEST_RANKED", r#"["d", " c "]"#);
+        let order = Prompt::ranking("Rank", &["a, b", " c ", "d"])
+            .env("CUMAEA_TEST_RANKED")
+            .ask();
+        assert_eq!(order.unwrap(), [2, 1, 0]);
+    }
+
+    #[test]
+    fn searches_as_its_typed() {
+        let fruit = ["apple", "apricot", "banana", "grape"];
+        let mut terminal = MockTerminal::new()
+            .type_text("apz")
+            .press(Key::Backspace)
+            .press(Key::Down)
+            .press(Key::Enter);
+        let picked = terminal.run(|| Prompt::fuzzy_select("Fruit", &fruit).ask());
+        assert_eq!(picked.unwrap(), 1);
+        assert_eq!(terminal.screen(), "Fruit: apricot");
+    }
+
+    #[test]
+    fn pages_hold_the_cursor() {
+        let line = |page: usize, pages: usize| {
+            let shown = fill(&locale().page, &[("page", &page), ("pages", &pages)]);
+            Some(format!("  {}", shown))
+        };
+        assert_eq!(page(3, 5, 10), (0..5, None));
+        assert_eq!(page(7, 20, 5), (5..10, line(2, 4)));
+        assert_eq!(page(21, 22, 10), (20..22, line(3, 3)));
+    }
+
+    #[test]
+    fn searches_show_a_window_around_the_cursor() {
+        let items: Vec<String> = (0..30).map(|i| format!("item {:02}", i)).collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let search = FuzzySelect::new("Item", &items);
+        let matches = fuzzy::filter("", &items);
+        let lines = search.lines("", &matches, 5, 3);
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1].ends_with("item 03"), "{:?}", lines);
+        assert!(lines[3].contains("> item 05"), "{:?}", lines);
+        assert_eq!(lines[4], "  30/30");
+    }
+
+    #[test]
+    fn searches_only_draw_what_fits() {
+        let items: Vec<String> = (0..30).map(|i| format!("item {:02}", i)).collect();
+        let items: Vec<&str> = items.iter().map(String::as_str).collect();
+        let mut terminal = MockTerminal::new().size(40, 6);
+        for _ in 0..4 {
+            terminal = terminal.press(Key::Down);
+        }
+        let mut terminal = terminal.press(Key::Enter);
+        let picked = terminal.run(|| Prompt::fuzzy_select("Item", &items).ask());
+        assert_eq!(picked.unwrap(), 4);
+        let output = terminal.output();
+        assert!(output.contains("item 02"));
+        assert!(!output.contains("item 05"), "{}", output);
+    }
+}
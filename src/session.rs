@@ -0,0 +1,626 @@
+//! Recording the answers given in one session, and replaying them in another.
+//!
+//! Sessions are stored as JSON Lines: one `{"prompt": ..., "answer": ...}` object
+//! per line, in the order the prompts were answered. Answers files, loaded with
+//! `load_answers` behind the `toml` feature, and command-line arguments, loaded
+//! with `load_args` behind the `clap` feature, are kept here too.
+
+use crate::config;
+use crate::{print_error, PromptError};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, Seek, SeekFrom, Write};
+use std::path::Path;
+use std::str::Chars;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+struct Session {
+    recording: Option<File>,
+    recorded: Vec<String>,
+    replaying: Vec<(String, String)>,
+    answers: BTreeMap<String, String>,
+    arguments: BTreeMap<String, String>,
+    configured: bool,
+}
+
+static SESSION: Mutex<Session> = Mutex::new(Session {
+    recording: None,
+    recorded: Vec::new(),
+    replaying: Vec::new(),
+    answers: BTreeMap::new(),
+    arguments: BTreeMap::new(),
+    configured: false,
+});
+
+/// The session, which starts out with the files named by `CUMAEA_RECORD_FILE`,
+/// `CUMAEA_REPLAY_FILE` and `CUMAEA_ANSWERS_FILE`, if they're set.
+fn session() -> MutexGuard<'static, Session> {
+    let mut session = SESSION.lock().unwrap_or_else(PoisonError::into_inner);
+    if !session.configured {
+        session.configured = true;
+        session.configure();
+    }
+    session
+}
+
+impl Session {
+    /// Takes in the files named in the environment. One that can't be used is
+    /// reported and left out, rather than stopping the tool.
+    fn configure(&mut self) {
+        let report = |var: &str, err: io::Error| {
+            let _ = print_error(&format!("Couldn't use {}: {}", var, err));
+        };
+        if let Some(path) = config::env_path("CUMAEA_RECORD_FILE") {
+            match File::create(path) {
+                Ok(file) => self.recording = Some(file),
+                Err(err) => report("CUMAEA_RECORD_FILE", err),
+            }
+        }
+        if let Some(path) = config::env_path("CUMAEA_REPLAY_FILE") {
+            match read_session(path) {
+                Ok(answers) => self.replaying = answers,
+                Err(err) => report("CUMAEA_REPLAY_FILE", err),
+            }
+        }
+        #[cfg(feature = "toml")]
+        if let Some(path) = config::env_path("CUMAEA_ANSWERS_FILE") {
+            match read_toml(path) {
+                Ok(answers) => self.answers = answers,
+                Err(err) => report("CUMAEA_ANSWERS_FILE", err),
+            }
+        }
+    }
+}
+
+/// Starts writing every answer from here on to `path`, replacing whatever was
+/// there. Each answer is written as soon as it's accepted, so a session cut short
+/// still leaves a usable file behind.
+///
+/// Answers are recorded as they were typed, or as the text of the picked items for
+/// menus. Checkbox lists and rankings record their items as a JSON array of
+/// strings, and trees the labels on the way to the leaf, so an item with a comma
+/// or a slash in it comes back as it was. The answer to a wizard step that's gone
+/// back to is taken out again, so only the one it ends up with is replayed. Hidden
+/// prompts, like [`Prompt::password`](crate::Prompt::password), aren't
+/// recorded.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> std::io::Result<()> {
+/// match std::env::args().nth(1).as_deref() {
+///     Some("--record") => record_answers("install.jsonl")?,
+///     Some("--replay") => replay_answers("install.jsonl")?,
+///     _ => {}
+/// }
+/// let prefix = prompt_path("Install to", None, Some("/usr/local".into()), PathOptions::default());
+/// # Ok(())
+/// # }
+/// ```
+pub fn record_answers(path: impl AsRef<Path>) -> io::Result<()> {
+    let file = File::create(path)?;
+    let mut session = session();
+    session.recording = Some(file);
+    session.recorded.clear();
+    Ok(())
+}
+
+/// Answers prompts from a file written by [`record_answers`] instead of asking.
+///
+/// Each prompt takes the first unused answer recorded for a prompt with the same
+/// text, and is asked as usual if there's none left. A replayed answer goes through
+/// the same checks as a typed one, but a rejected one is returned as
+/// [`PromptError::Validation`] rather than asked again.
+pub fn replay_answers(path: impl AsRef<Path>) -> io::Result<()> {
+    session().replaying = read_session(path)?;
+    Ok(())
+}
+
+/// The prompts and answers in a file written by [`record_answers`].
+fn read_session(path: impl AsRef<Path>) -> io::Result<Vec<(String, String)>> {
+    fs::read_to_string(path)?
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            parse_entry(line).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("not a recorded answer: {}", line),
+                )
+            })
+        })
+        .collect()
+}
+
+/// Answers prompts by key from a TOML file, for prompts given a key with
+/// [`PromptBuilder::key`](crate::PromptBuilder::key) or the menus' `key`. Prompts
+/// whose key isn't in the file are asked as usual. Replaces any answers loaded
+/// before.
+///
+/// Keys in tables are joined with dots, so `host` under `[database]` answers
+/// `.key("database.host")`. Strings are taken as typed, `true` and `false` answer
+/// yes/no prompts, and arrays pick several items from a
+/// [`MultiSelect`](crate::MultiSelect). An answer from the file goes through the
+/// same checks as a typed one, but a rejected one is returned as
+/// [`PromptError::Validation`] rather than asked again.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// // answers.toml:
+/// //     region = "eu-west"
+/// //     [database]
+/// //     host = "db.internal"
+/// load_answers("answers.toml")?;
+/// let host = Prompt::text("Database host").key("database.host").ask()?;
+/// let port = Prompt::int("Database port").key("database.port").ask()?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "toml")]
+pub fn load_answers(path: impl AsRef<Path>) -> io::Result<()> {
+    session().answers = read_toml(path)?;
+    Ok(())
+}
+
+/// The values in the TOML file at `path` as they'd be typed, by key.
+#[cfg(feature = "toml")]
+pub(crate) fn read_toml(path: impl AsRef<Path>) -> io::Result<BTreeMap<String, String>> {
+    let table: toml::Table = fs::read_to_string(path)?
+        .parse()
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut values = BTreeMap::new();
+    flatten("", &table, &mut values)?;
+    Ok(values)
+}
+
+/// Adds the answers in `table` to `answers`, with their keys under `prefix`.
+#[cfg(feature = "toml")]
+fn flatten(
+    prefix: &str,
+    table: &toml::Table,
+    answers: &mut BTreeMap<String, String>,
+) -> io::Result<()> {
+    for (key, value) in table {
+        let key = match prefix.is_empty() {
+            true => key.clone(),
+            false => format!("{}.{}", prefix, key),
+        };
+        let answer = match value {
+            toml::Value::Table(table) => {
+                flatten(&key, table, answers)?;
+                continue;
+            }
+            toml::Value::Array(items) => items
+                .iter()
+                .map(|item| scalar(&key, item))
+                .collect::<io::Result<Vec<_>>>()?
+                .join(", "),
+            value => scalar(&key, value)?,
+        };
+        answers.insert(key, answer);
+    }
+    Ok(())
+}
+
+/// `value` as it would be typed at the prompt for `key`.
+#[cfg(feature = "toml")]
+fn scalar(key: &str, value: &toml::Value) -> io::Result<String> {
+    Ok(match value {
+        toml::Value::String(text) => text.clone(),
+        toml::Value::Boolean(true) => "y".to_string(),
+        toml::Value::Boolean(false) => "n".to_string(),
+        toml::Value::Integer(number) => number.to_string(),
+        toml::Value::Float(number) => number.to_string(),
+        toml::Value::Datetime(datetime) => datetime.to_string(),
+        toml::Value::Array(_) | toml::Value::Table(_) => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} can only list plain values", key),
+            ))
+        }
+    })
+}
+
+/// Answers prompts by key from the arguments in `matches` that were given on the
+/// command line or through their environment variables, for prompts given a key
+/// with [`PromptBuilder::key`](crate::PromptBuilder::key) or the menus' `key`. So
+/// only what was left off the command line is asked for. Replaces any arguments
+/// loaded before, and they're taken over the answers loaded with `load_answers`.
+///
+/// Each argument is keyed by its id, and a subcommand's arguments are under its
+/// name, so `port` of `myapp serve --port 80` answers `.key("serve.port")`. Values
+/// are taken as typed, and several are joined with commas, the way a
+/// [`Prompt::list`](crate::Prompt::list) reads them. A flag set to `true` answers
+/// yes/no prompts with yes. Defaults aren't loaded, as the prompt's own default
+/// applies instead. An argument goes through the same checks as a typed answer,
+/// but a rejected one is returned as [`PromptError::Validation`] rather than asked
+/// again.
+///
+/// With clap's derive API, get the matches with `Cli::command().get_matches()`,
+/// load them, and build the struct from them with `Cli::from_arg_matches`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # fn main() -> Result<(), PromptError> {
+/// use clap::{Arg, Command};
+///
+/// let matches = Command::new("myapp")
+///     .arg(Arg::new("host").long("host"))
+///     .arg(Arg::new("port").long("port"))
+///     .get_matches();
+/// load_args(&matches);
+///
+/// // `myapp --host db.internal` only asks for the port.
+/// let host = Prompt::text("Database host").key("host").ask()?;
+/// let port = Prompt::int("Database port").key("port").default(5432).ask()?;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "clap")]
+pub fn load_args(matches: &clap::ArgMatches) {
+    let mut arguments = BTreeMap::new();
+    given_args("", matches, &mut arguments);
+    session().arguments = arguments;
+}
+
+/// Adds the arguments given in `matches` to `arguments`, with their keys under
+/// `prefix`, and then those of the subcommand.
+#[cfg(feature = "clap")]
+fn given_args(prefix: &str, matches: &clap::ArgMatches, arguments: &mut BTreeMap<String, String>) {
+    use clap::parser::ValueSource;
+
+    for id in matches.ids() {
+        let id = id.as_str();
+        if !matches!(
+            matches.value_source(id),
+            Some(ValueSource::CommandLine | ValueSource::EnvVariable)
+        ) {
+            continue;
+        }
+        let value = match matches.try_get_one::<bool>(id) {
+            Ok(Some(true)) => "y".to_string(),
+            Ok(Some(false)) => "n".to_string(),
+            _ => match matches.try_get_raw(id) {
+                Ok(Some(values)) => values
+                    .map(|value| value.to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                _ => continue,
+            },
+        };
+        arguments.insert(format!("{}{}", prefix, id), value);
+    }
+    if let Some((name, matches)) = matches.subcommand() {
+        given_args(&format!("{}{}.", prefix, name), matches, arguments);
+    }
+}
+
+/// The answer given for `key` on the command line, or else in the answers file, if
+/// there is one. Comes with a description of where it came from, for error
+/// messages, which leaves out the answer if it's `hidden`.
+pub(crate) fn answer(key: &str, hidden: bool) -> Option<(String, String)> {
+    let session = session();
+    let (value, place) = match session.arguments.get(key) {
+        Some(value) => (value, "on the command line"),
+        None => (session.answers.get(key)?, "in the answers file"),
+    };
+    let origin = match hidden {
+        true => format!("{} {}", key, place),
+        false => format!("{} = {:?} {}", key, value, place),
+    };
+    Some((origin, value.clone()))
+}
+
+/// Writes down `answer` to `prompt`, if a recording is running.
+pub(crate) fn record(prompt: &str, answer: &str) -> Result<(), PromptError> {
+    #[cfg(feature = "log")]
+    crate::logging::answered(prompt, Some(answer));
+    let mut session = session();
+    let Session {
+        recording,
+        recorded,
+        ..
+    } = &mut *session;
+    if let Some(file) = recording {
+        let entry = entry(prompt, answer);
+        writeln!(file, "{}", entry)?;
+        recorded.push(entry);
+    }
+    Ok(())
+}
+
+/// How many answers have been recorded so far, to [`forget`] the ones after.
+pub(crate) fn recorded() -> usize {
+    session().recorded.len()
+}
+
+/// Takes the answers recorded after the first `count` back out of the recording,
+/// when the prompts they answer are gone back on, and writes the file out again.
+pub(crate) fn forget(count: usize) -> Result<(), PromptError> {
+    let mut session = session();
+    let Session {
+        recording,
+        recorded,
+        ..
+    } = &mut *session;
+    let Some(file) = recording.as_mut().filter(|_| recorded.len() > count) else {
+        return Ok(());
+    };
+    recorded.truncate(count);
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    for entry in recorded.iter() {
+        writeln!(file, "{}", entry)?;
+    }
+    Ok(())
+}
+
+/// The line [`record`] writes down for `answer` to `prompt`.
+fn entry(prompt: &str, answer: &str) -> String {
+    format!(
+        "{{\"prompt\": {}, \"answer\": {}}}",
+        quote(prompt),
+        quote(answer)
+    )
+}
+
+/// Takes the next replayed answer to `prompt`, if there is one.
+pub(crate) fn replayed(prompt: &str) -> Option<String> {
+    let mut session = session();
+    let i = session.replaying.iter().position(|(p, _)| p == prompt)?;
+    Some(session.replaying.remove(i).1)
+}
+
+/// `items` as a JSON array of strings, the way lists are recorded.
+pub(crate) fn list<S: AsRef<str>>(items: &[S]) -> String {
+    let quoted: Vec<String> = items.iter().map(|item| quote(item.as_ref())).collect();
+    format!("[{}]", quoted.join(", "))
+}
+
+/// The items in `value`: the strings in it if it's a JSON array of them, the way
+/// [`list`] writes them, or else the text between each `separator`, trimmed, with
+/// empty ones left out.
+pub(crate) fn items(value: &str, separator: char) -> Vec<String> {
+    parse_list(value).unwrap_or_else(|| {
+        value
+            .split(separator)
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    })
+}
+
+/// `text` as a JSON string literal.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Reads a line written by [`record`]. Other keys are allowed, as long as their
+/// values are strings too.
+fn parse_entry(line: &str) -> Option<(String, String)> {
+    let mut chars = line.chars();
+    let (mut prompt, mut answer) = (None, None);
+    expect(&mut chars, '{')?;
+    loop {
+        let key = parse_string(&mut chars)?;
+        expect(&mut chars, ':')?;
+        let value = parse_string(&mut chars)?;
+        match key.as_str() {
+            "prompt" => prompt = Some(value),
+            "answer" => answer = Some(value),
+            _ => {}
+        }
+        match skip_whitespace(&mut chars)? {
+            ',' => continue,
+            '}' => break,
+            _ => return None,
+        }
+    }
+    if skip_whitespace(&mut chars).is_some() {
+        return None;
+    }
+    Some((prompt?, answer?))
+}
+
+/// Reads a JSON array of strings.
+fn parse_list(text: &str) -> Option<Vec<String>> {
+    let mut chars = text.chars();
+    expect(&mut chars, '[')?;
+    let mut items = Vec::new();
+    if chars.clone().find(|c| !c.is_whitespace()) == Some(']') {
+        skip_whitespace(&mut chars);
+    } else {
+        loop {
+            items.push(parse_string(&mut chars)?);
+            match skip_whitespace(&mut chars)? {
+                ',' => continue,
+                ']' => break,
+                _ => return None,
+            }
+        }
+    }
+    skip_whitespace(&mut chars).is_none().then_some(items)
+}
+
+/// Skips whitespace and returns the next character.
+fn skip_whitespace(chars: &mut Chars) -> Option<char> {
+    chars.find(|c| !c.is_whitespace())
+}
+
+fn expect(chars: &mut Chars, expected: char) -> Option<()> {
+    (skip_whitespace(chars)? == expected).then_some(())
+}
+
+fn parse_string(chars: &mut Chars) -> Option<String> {
+    expect(chars, '"')?;
+    let mut string = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(string),
+            '\\' => string.push(match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'u' => parse_escape(chars)?,
+                c @ ('"' | '\\' | '/') => c,
+                _ => return None,
+            }),
+            c => string.push(c),
+        }
+    }
+}
+
+/// Reads the rest of a `\uXXXX` escape, including the second half of a surrogate pair.
+fn parse_escape(chars: &mut Chars) -> Option<char> {
+    let high = parse_hex(chars)?;
+    if !(0xd800..0xdc00).contains(&high) {
+        return char::from_u32(high);
+    }
+    if chars.next()? != '\\' || chars.next()? != 'u' {
+        return None;
+    }
+    let low = parse_hex(chars).filter(|low| (0xdc00..0xe000).contains(low))?;
+    char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+}
+
+/// Reads exactly four hex digits. `from_str_radix` alone would take a sign, or
+/// fewer digits along with the quote after them.
+fn parse_hex(chars: &mut Chars) -> Option<u32> {
+    let digits: String = chars.by_ref().take(4).collect();
+    if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(prompt: &str, answer: &str) -> Option<(String, String)> {
+        parse_entry(&entry(prompt, answer))
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        for answer in [
+            "",
+            "plain",
+            "say \"hi\" \\ bye",
+            "two\nlines\r\n\ttabbed",
+            "bell \u{7} null \u{0} delete \u{7f}",
+            "caf\u{e9} \u{1f600} \u{5c71}",
+        ] {
+            assert_eq!(
+                round_trip("Prompt: \"quoted\"", answer),
+                Some(("Prompt: \"quoted\"".to_string(), answer.to_string())),
+                "{:?}",
+                answer
+            );
+        }
+    }
+
+    #[test]
+    fn control_characters_are_escaped() {
+        assert_eq!(quote("a\u{1}b"), "\"a\\u0001b\"");
+        assert_eq!(quote("\u{1b}[0m"), "\"\\u001b[0m\"");
+    }
+
+    #[test]
+    fn reads_escapes() {
+        let entry = |answer: &str| {
+            parse_entry(&format!(r#"{{"prompt": "p", "answer": "{}"}}"#, answer))
+                .map(|(_, answer)| answer)
+        };
+        assert_eq!(entry(r"\u0041\/\b\f").as_deref(), Some("A/\u{8}\u{c}"));
+        assert_eq!(entry(r"\ud83d\ude00").as_deref(), Some("\u{1f600}"));
+        assert_eq!(entry(r"\u00E9").as_deref(), Some("\u{e9}"));
+    }
+
+    #[test]
+    fn reads_other_keys_and_spacing() {
+        assert_eq!(
+            parse_entry(r#" { "at" : "now" ,"answer":"b",  "prompt": "a" } "#),
+            Some(("a".to_string(), "b".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_escapes() {
+        for answer in [
+            r"\u+04a",
+            r"\u41",
+            r"\u004",
+            r"\uzzzz",
+            r"\ud83d",
+            r"\ud83dx",
+            r"\ud83d\u0041",
+            r"\ude00",
+            r"\x",
+        ] {
+            let line = format!(r#"{{"prompt": "p", "answer": "{}"}}"#, answer);
+            assert_eq!(parse_entry(&line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn rejects_bad_entries() {
+        for line in [
+            "",
+            r#"{"prompt": "p"}"#,
+            r#"{"answer": "a"}"#,
+            r#"{"prompt": "p", "answer": "a""#,
+            r#"{"prompt": "p", "answer": "a"} x"#,
+            r#"{"prompt": "p", "answer": 1}"#,
+            r#"{"prompt": "p" "answer": "a"}"#,
+            r#"{"prompt": "p", "answer": "unterminated}"#,
+        ] {
+            assert_eq!(parse_entry(line), None, "{}", line);
+        }
+    }
+
+    #[test]
+    fn lists_round_trip() {
+        let awkward = ["a, b", " spaced ", "say \"hi\"", "", "[x]"];
+        assert_eq!(
+            list(&awkward),
+            r#"["a, b", " spaced ", "say \"hi\"", "", "[x]"]"#
+        );
+        assert_eq!(items(&list(&awkward), ','), awkward);
+        assert_eq!(items(&list::<&str>(&[]), ','), Vec::<String>::new());
+    }
+
+    #[test]
+    fn plain_lists_are_split() {
+        assert_eq!(items(" a, b ,,c", ','), ["a", "b", "c"]);
+        assert_eq!(
+            items("Databases / Postgres", '/'),
+            ["Databases", "Postgres"]
+        );
+        assert_eq!(items("[a, b", ','), ["[a", "b"]);
+        assert_eq!(items(r#"["a"] b"#, ','), [r#"["a"] b"#]);
+    }
+}
@@ -0,0 +1,57 @@
+//! Spans around prompts for the `tracing` crate, behind the `tracing` feature, so
+//! the time spent waiting on an answer shows up in the same traces as the rest of
+//! the program. They're all under the `cumaea` target.
+
+use std::cell::RefCell;
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::span::EnteredSpan;
+use tracing::Span;
+
+thread_local! {
+    /// The spans of the prompts being asked on this thread, innermost last, like a
+    /// wizard's step inside the wizard.
+    static ASKING: RefCell<Vec<Span>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The span of the prompt being asked, entered until it's dropped, when how long
+/// the prompt took is recorded on it.
+pub(crate) struct Asking {
+    span: EnteredSpan,
+    start: Instant,
+}
+
+/// Enters a span for asking `prompt`, looked up by `key` if it has one, with
+/// `attempts` and `duration_ms` fields filled in as it goes.
+pub(crate) fn asking(prompt: &str, key: Option<&str>) -> Asking {
+    let span = tracing::info_span!(
+        target: "cumaea",
+        "prompt",
+        prompt,
+        key,
+        attempts = 1u64,
+        duration_ms = Empty,
+    );
+    ASKING.with(|asking| asking.borrow_mut().push(span.clone()));
+    Asking {
+        span: span.entered(),
+        start: Instant::now(),
+    }
+}
+
+/// Records that the prompt being asked is on its `attempt`th answer.
+pub(crate) fn attempt(attempt: usize) {
+    ASKING.with(|asking| {
+        if let Some(span) = asking.borrow().last() {
+            span.record("attempts", attempt as u64);
+        }
+    });
+}
+
+impl Drop for Asking {
+    fn drop(&mut self) {
+        let duration = self.start.elapsed().as_millis() as u64;
+        self.span.record("duration_ms", duration);
+        ASKING.with(|asking| asking.borrow_mut().pop());
+    }
+}
@@ -0,0 +1,1090 @@
+//! Raw-mode terminal input, for the prompts that can't be built on `read_line`, and
+//! the streams every prompt reads from and writes to.
+
+use crate::ansi::{self, Queue};
+use crate::config;
+use crate::theme;
+use crate::PromptError;
+#[cfg(not(target_arch = "wasm32"))]
+use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+#[cfg(not(target_arch = "wasm32"))]
+use crossterm::terminal;
+use std::cell::{Cell, RefCell};
+use std::fs::{File, OpenOptions};
+use std::io::{self, stderr, stdin, stdout, BufRead, BufReader, IsTerminal, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex, OnceLock, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Whatever was typed at a prompt. With the `zeroize` feature it's scrubbed on drop,
+/// so secrets don't linger in memory after a prompt loop moves on.
+#[cfg(feature = "zeroize")]
+pub(crate) type Buffer = zeroize::Zeroizing<String>;
+#[cfg(not(feature = "zeroize"))]
+pub(crate) type Buffer = String;
+
+/// Wraps `input` in a [`Buffer`].
+#[cfg(feature = "zeroize")]
+pub(crate) fn buffer(input: String) -> Buffer {
+    zeroize::Zeroizing::new(input)
+}
+#[cfg(not(feature = "zeroize"))]
+pub(crate) fn buffer(input: String) -> Buffer {
+    input
+}
+
+/// What the line readers return when Esc is pressed at a prompt that can be skipped.
+/// It's also what Esc followed by Enter reads as in canonical mode.
+pub(crate) const ESCAPE: &str = "\x1b";
+
+/// A key press, boiled down to what the interactive prompts care about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Enter,
+    Tab,
+    Backspace,
+    Delete,
+    Esc,
+    Char(char),
+    /// A letter typed while holding Ctrl, lowercased. `Ctrl('c')` interrupts the
+    /// prompt.
+    Ctrl(char),
+}
+
+/// A reader and writer for prompts to use instead of stdin and stdout.
+pub(crate) struct Streams<R, W> {
+    pub(crate) reader: R,
+    pub(crate) writer: W,
+}
+
+/// What prompts read from and draw on. Prompts use a [`CrosstermBackend`] on
+/// stdin and stderr unless they're run inside [`with_backend`], so another
+/// terminal library, a remote session or a recording can be put in its place.
+///
+/// A backend that isn't a terminal is only ever read a line at a time from its
+/// [`reader`](Backend::reader), like a pipe. One that is is read a key at a time
+/// with [`read_key`](Backend::read_key), and drawn on with the escape sequences a
+/// terminal takes, written to its [`writer`](Backend::writer).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use std::io::{self, BufRead, Write};
+/// use std::time::Duration;
+///
+/// /// The terminal, with what's typed written down too.
+/// struct Logged {
+///     terminal: CrosstermBackend,
+///     typed: Vec<Key>,
+/// }
+///
+/// impl Backend for Logged {
+///     fn reader(&mut self) -> &mut dyn BufRead {
+///         self.terminal.reader()
+///     }
+///
+///     fn writer(&mut self) -> &mut dyn Write {
+///         self.terminal.writer()
+///     }
+///
+///     fn is_terminal(&self) -> bool {
+///         self.terminal.is_terminal()
+///     }
+///
+///     fn read_key(&mut self, timeout: Option<Duration>) -> io::Result<Option<Key>> {
+///         let key = self.terminal.read_key(timeout)?;
+///         self.typed.extend(key);
+///         Ok(key)
+///     }
+///
+///     fn size(&self) -> (usize, usize) {
+///         self.terminal.size()
+///     }
+///
+///     fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+///         self.terminal.set_raw_mode(on)
+///     }
+/// }
+///
+/// let logged = Logged { terminal: CrosstermBackend::new(), typed: Vec::new() };
+/// let name = with_backend(logged, || Prompt::text("Name").ask());
+/// ```
+pub trait Backend {
+    /// Where lines are read from when this isn't a terminal.
+    fn reader(&mut self) -> &mut dyn BufRead;
+
+    /// Where prompts are printed and drawn.
+    fn writer(&mut self) -> &mut dyn Write;
+
+    /// Whether this is a terminal, so prompts read key presses from
+    /// [`read_key`](Backend::read_key) instead of lines from the reader, and draw
+    /// and redraw themselves. It isn't by default.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
+    /// Waits up to `timeout`, or for as long as it takes if there's none, for the
+    /// next key press, returning `None` if there's none by then. Ctrl+C comes back
+    /// as `Key::Ctrl('c')`, and interrupts the prompt. An
+    /// [`UnexpectedEof`](io::ErrorKind::UnexpectedEof) error says no more keys will
+    /// come, which is what the default does.
+    fn read_key(&mut self, timeout: Option<Duration>) -> io::Result<Option<Key>> {
+        let _ = timeout;
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+
+    /// How many columns and rows the terminal has. It's 80 by 24 by default.
+    fn size(&self) -> (usize, usize) {
+        DEFAULT_SIZE
+    }
+
+    /// Turns raw mode on or off: keys are passed on as they're pressed, without
+    /// being echoed, while it's on. Prompts turn it on while they read keys, and
+    /// off again before they return. It does nothing by default.
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+        let _ = on;
+        Ok(())
+    }
+}
+
+/// The [`Backend`] prompts use by default: stdin, and stderr or stdout as
+/// [`set_output`] has it, with keys read and the terminal put in raw mode through
+/// `crossterm`, which works the same way on Linux, macOS and Windows. There's none
+/// on WebAssembly.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct CrosstermBackend {
+    reader: io::BufReader<io::Stdin>,
+    stderr: io::Stderr,
+    stdout: io::Stdout,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl CrosstermBackend {
+    /// The terminal on stdin and stderr or stdout.
+    pub fn new() -> Self {
+        CrosstermBackend {
+            reader: io::BufReader::new(stdin()),
+            stderr: stderr(),
+            stdout: stdout(),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for CrosstermBackend {
+    fn default() -> Self {
+        CrosstermBackend::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Backend for CrosstermBackend {
+    fn reader(&mut self) -> &mut dyn BufRead {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        match drawn_on() {
+            Output::Stderr => &mut self.stderr,
+            Output::Stdout => &mut self.stdout,
+        }
+    }
+
+    fn is_terminal(&self) -> bool {
+        input_is_terminal() && drawn_on().is_terminal()
+    }
+
+    fn read_key(&mut self, timeout: Option<Duration>) -> io::Result<Option<Key>> {
+        // Keys are only passed on as they're pressed in raw mode.
+        let raw = terminal::is_raw_mode_enabled()?;
+        if !raw {
+            terminal::enable_raw_mode()?;
+        }
+        let key = terminal_key(timeout);
+        if !raw {
+            terminal::disable_raw_mode()?;
+        }
+        key
+    }
+
+    fn size(&self) -> (usize, usize) {
+        terminal_size()
+    }
+
+    fn set_raw_mode(&mut self, on: bool) -> io::Result<()> {
+        set_terminal_raw_mode(on)
+    }
+}
+
+/// Runs `f`, with the prompts it shows on this thread reading from and drawing on
+/// `backend` in place of the terminal, and returns what it returns.
+pub fn with_backend<T>(backend: impl Backend + 'static, f: impl FnOnce() -> T) -> T {
+    redirect(Rc::new(RefCell::new(backend)), f)
+}
+
+/// The size assumed for a terminal that can't say how big it is.
+pub(crate) const DEFAULT_SIZE: (usize, usize) = (80, 24);
+
+impl<R: BufRead, W: Write> Backend for Streams<R, W> {
+    fn reader(&mut self) -> &mut dyn BufRead {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        &mut self.writer
+    }
+}
+
+thread_local! {
+    /// The streams prompts on this thread use instead of stdin and stdout, while a
+    /// [`Prompter`](crate::Prompter) is running them.
+    static REDIRECT: RefCell<Option<Rc<RefCell<dyn Backend>>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with prompts on this thread reading from and writing to `streams`.
+pub(crate) fn redirect<T>(streams: Rc<RefCell<dyn Backend>>, f: impl FnOnce() -> T) -> T {
+    /// Puts back the streams from before, even if `f` panics.
+    struct Restore(Option<Rc<RefCell<dyn Backend>>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            REDIRECT.with(|redirect| *redirect.borrow_mut() = self.0.take());
+        }
+    }
+
+    let _restore = Restore(REDIRECT.with(|redirect| redirect.borrow_mut().replace(streams)));
+    f()
+}
+
+thread_local! {
+    /// Set from elsewhere to call off the prompt on this thread, while prompts on
+    /// it can be called off.
+    static CALL_OFF: RefCell<Option<Arc<AtomicBool>>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with reads on this thread giving up with [`PromptError::Cancelled`]
+/// once `call_off` is set.
+#[cfg(feature = "async")]
+pub(crate) fn call_off_with<T>(call_off: Arc<AtomicBool>, f: impl FnOnce() -> T) -> T {
+    /// Leaves prompts on this thread as they were, even if `f` panics, since the
+    /// thread goes on to run other tasks.
+    struct Restore;
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CALL_OFF.with(|flag| *flag.borrow_mut() = None);
+        }
+    }
+
+    CALL_OFF.with(|flag| *flag.borrow_mut() = Some(call_off));
+    let _restore = Restore;
+    f()
+}
+
+/// Whether the prompt on this thread has been called off.
+fn called_off() -> bool {
+    CALL_OFF.with(|flag| {
+        let flag = flag.borrow();
+        flag.as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+    })
+}
+
+/// How long to wait for input before giving up on it or looking again: until
+/// `deadline` if there is one, and only a moment at a time while the prompt can be
+/// called off. `None` waits for as long as it takes.
+fn wait(deadline: Option<Instant>) -> Option<Duration> {
+    let left = deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+    match CALL_OFF.with(|flag| flag.borrow().is_some()) {
+        true => Some(left.map_or(CALL_OFF_CHECK, |left| left.min(CALL_OFF_CHECK))),
+        false => left,
+    }
+}
+
+/// How often a prompt that can be called off looks whether it has been.
+const CALL_OFF_CHECK: Duration = Duration::from_millis(50);
+
+fn redirected() -> Option<Rc<RefCell<dyn Backend>>> {
+    REDIRECT.with(|redirect| redirect.borrow().clone())
+}
+
+/// Runs `f`, a prompt, with anything else that draws on the terminal put out of
+/// its way.
+pub(crate) fn asking<T>(f: impl FnOnce() -> T) -> T {
+    #[cfg(feature = "indicatif")]
+    return crate::progress::suspended(f);
+    #[cfg(not(feature = "indicatif"))]
+    f()
+}
+
+/// Where prompts are drawn when they aren't run with [`with_backend`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Output {
+    /// Standard error, leaving standard output to what the program prints, so
+    /// prompts can still be answered when it's piped into another program.
+    #[default]
+    Stderr,
+    /// Standard output, along with what the program prints.
+    Stdout,
+}
+
+impl Output {
+    fn is_terminal(self) -> bool {
+        match self {
+            Output::Stderr => stderr().is_terminal(),
+            Output::Stdout => stdout().is_terminal(),
+        }
+    }
+}
+
+static OUTPUT: Mutex<Output> = Mutex::new(Output::Stderr);
+
+/// Sets where prompts are drawn from here on, on every thread, for the ones that
+/// don't say with their own `output`. They're drawn on stderr unless this says
+/// otherwise, so `mytool | jq` still shows them on the terminal while only what
+/// the program prints goes to `jq`.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_output(Output::Stdout);
+/// let name = Prompt::text("Name").ask();
+/// ```
+pub fn set_output(output: Output) {
+    *OUTPUT.lock().unwrap_or_else(PoisonError::into_inner) = output;
+}
+
+thread_local! {
+    /// Where the prompt being asked on this thread is drawn, if it says.
+    static PROMPT_OUTPUT: Cell<Option<Output>> = const { Cell::new(None) };
+}
+
+/// Draws prompts on this thread on `output` if it's given, until what's returned is
+/// dropped.
+pub(crate) fn draw_on(output: Option<Output>) -> DrawnOn {
+    DrawnOn(output.map(|output| PROMPT_OUTPUT.with(|prompt| prompt.replace(Some(output)))))
+}
+
+/// Puts back where prompts were drawn before [`draw_on`] when it's dropped.
+pub(crate) struct DrawnOn(Option<Option<Output>>);
+
+impl Drop for DrawnOn {
+    fn drop(&mut self) {
+        if let Some(before) = self.0 {
+            PROMPT_OUTPUT.with(|output| output.set(before));
+        }
+    }
+}
+
+/// Where prompts are drawn right now, when they aren't redirected.
+fn drawn_on() -> Output {
+    PROMPT_OUTPUT
+        .with(Cell::get)
+        .unwrap_or_else(|| *OUTPUT.lock().unwrap_or_else(PoisonError::into_inner))
+}
+
+/// Runs `f` on whatever prompts write to: stderr or stdout, or the streams they've
+/// been redirected to.
+/// In plain mode, escape sequences are left out of what's written.
+pub(crate) fn output<T>(f: impl FnOnce(&mut dyn Write) -> io::Result<T>) -> io::Result<T> {
+    let plain = plain();
+    let write = |out: &mut dyn Write| match plain {
+        true => f(&mut Stripped {
+            out,
+            escape: Escape::None,
+        }),
+        false => f(out),
+    };
+    match redirected() {
+        Some(streams) => write(streams.borrow_mut().writer()),
+        None => match drawn_on() {
+            Output::Stderr => write(&mut stderr().lock()),
+            Output::Stdout => write(&mut stdout().lock()),
+        },
+    }
+}
+
+/// Passes what's written on to `out` without its escape sequences.
+struct Stripped<'a> {
+    out: &'a mut dyn Write,
+    escape: Escape,
+}
+
+/// How far into an escape sequence the bytes written so far are.
+#[derive(Clone, Copy)]
+enum Escape {
+    None,
+    Started,
+    Codes,
+}
+
+impl Write for Stripped<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut kept = Vec::with_capacity(buf.len());
+        for &byte in buf {
+            self.escape = match (self.escape, byte) {
+                (Escape::None, 0x1b) => Escape::Started,
+                (Escape::None, _) => {
+                    kept.push(byte);
+                    Escape::None
+                }
+                (Escape::Started, b'[') => Escape::Codes,
+                (Escape::Codes, 0x40..=0x7e) | (Escape::Started, _) => Escape::None,
+                (Escape::Codes, _) => Escape::Codes,
+            };
+        }
+        self.out.write_all(&kept)?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+}
+
+/// Like `print!`, but to wherever prompts write. Evaluates to an `io::Result`.
+///
+/// The text is formatted before the output is borrowed, so the arguments can look
+/// at the streams too.
+macro_rules! out {
+    ($($arg:tt)*) => {{
+        let text = format!($($arg)*);
+        $crate::term::output(|out| ::std::io::Write::write_all(out, text.as_bytes()))
+    }};
+}
+
+/// Like `println!`, but to wherever prompts write. Evaluates to an `io::Result`.
+macro_rules! outln {
+    () => {
+        $crate::term::out!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::term::out!("{}\n", format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {out, outln};
+
+/// Whether both ends are a terminal, so raw-mode prompts can be drawn at all.
+pub(crate) fn interactive() -> bool {
+    match redirected() {
+        Some(streams) => streams.borrow().is_terminal(),
+        None => input_is_terminal() && drawn_on().is_terminal(),
+    }
+}
+
+static ACCESSIBLE: AtomicBool = AtomicBool::new(false);
+
+/// Turns accessibility mode on or off from here on, on every thread. In it, prompts
+/// never move the cursor or redraw what they've printed, so a screen reader can
+/// read everything as it comes: menus, checkbox lists and trees list their items as
+/// numbered lines and take the numbers picked, and answers are read a line at a
+/// time. Prompts still tell a terminal from a pipe, so defaults aren't taken
+/// without asking. It's also on when `CUMAEA_ACCESSIBLE` is set.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if std::env::args().any(|arg| arg == "--accessible") {
+///     set_accessible(true);
+/// }
+/// ```
+pub fn set_accessible(on: bool) {
+    ACCESSIBLE.store(on, Ordering::Relaxed);
+}
+
+/// Whether prompts can take over the terminal to draw and redraw themselves: it's
+/// a terminal, and neither accessibility mode nor plain mode is on.
+pub(crate) fn redraws() -> bool {
+    let accessible = ACCESSIBLE.load(Ordering::Relaxed) || config::env_config().accessible;
+    interactive() && !accessible && !plain()
+}
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Turns plain mode on or off from here on, on every thread. In it, prompts print
+/// no escape sequences at all, not even ones in the text they're given, and menus,
+/// checkbox lists and trees list their items as numbered lines and take the numbers
+/// picked, so a session captured with `tee` reads like it did on the screen.
+///
+/// It's on without this when what prompts print doesn't go to a terminal, unless
+/// colors are forced on with [`ColorMode::Always`](crate::ColorMode::Always) or
+/// `CLICOLOR_FORCE`, in a Windows console too old to take escape sequences, and
+/// when `CUMAEA_PLAIN` is set.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if std::env::args().any(|arg| arg == "--plain") {
+///     set_plain(true);
+/// }
+/// ```
+pub fn set_plain(on: bool) {
+    PLAIN.store(on, Ordering::Relaxed);
+}
+
+/// Whether prompts print plain text only, as [`set_plain`] describes.
+pub(crate) fn plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+        || config::env_config().plain
+        || !output_is_terminal() && !theme::colors_forced()
+        || redirected().is_none() && !escapes_work()
+}
+
+/// Whether the terminal prompts are drawn on acts on escape sequences. The Windows console
+/// only does once it's asked to, which is done here the first time; consoles that
+/// can't, from before Windows 10, are printed to in plain mode.
+#[cfg(windows)]
+fn escapes_work() -> bool {
+    crossterm::ansi_support::supports_ansi()
+}
+
+/// Whether the terminal prompts are drawn on acts on escape sequences, which every
+/// terminal outside Windows does.
+#[cfg(not(windows))]
+fn escapes_work() -> bool {
+    true
+}
+
+/// Whether what prompts print ends up on a terminal.
+pub(crate) fn output_is_terminal() -> bool {
+    match redirected() {
+        Some(streams) => streams.borrow().is_terminal(),
+        None => drawn_on().is_terminal(),
+    }
+}
+
+/// How many columns and rows the terminal prompts are drawn on has, or 80 by 24 if
+/// it can't say.
+pub(crate) fn size() -> (usize, usize) {
+    match redirected() {
+        Some(streams) => streams.borrow().size(),
+        None => terminal_size(),
+    }
+}
+
+/// How many columns `text` takes on the terminal, with wide characters like CJK
+/// counted twice and color codes not at all.
+pub(crate) fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        rest = &rest[start + code_len(&rest[start..])..];
+    }
+    width + rest.width()
+}
+
+/// How long the color code at the start of `text` is, counting the escape it
+/// starts with.
+pub(crate) fn code_len(text: &str) -> usize {
+    match text.strip_prefix("\x1b[") {
+        Some(codes) => codes
+            .find(|c| ('@'..='~').contains(&c))
+            .map_or(text.len(), |end| end + 3),
+        None => 1,
+    }
+}
+
+fn stdin_is_terminal() -> bool {
+    match redirected() {
+        Some(streams) => streams.borrow().is_terminal(),
+        None => input_is_terminal(),
+    }
+}
+
+static TTY_INPUT: AtomicBool = AtomicBool::new(false);
+
+/// Turns reading answers from the terminal itself on or off from here on, on every
+/// thread, for when stdin is piped in. With it on, a program can read the data it's
+/// given on stdin and still ask questions, like `git log | mytool`: answers are read
+/// from `/dev/tty`, or `CONIN$` on Windows, so long as there's a terminal to open.
+/// Without one, like in a CI job or under cron, answers are read from stdin as
+/// usual.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// # use std::io::Read;
+/// # fn main() -> Result<(), PromptError> {
+/// let mut patch = String::new();
+/// std::io::stdin().read_to_string(&mut patch)?;
+/// set_tty_input(true);
+/// let apply = Prompt::confirm("Apply the patch?").ask()?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn set_tty_input(on: bool) {
+    TTY_INPUT.store(on, Ordering::Relaxed);
+}
+
+/// Where the terminal can be opened to read from, whatever stdin is.
+#[cfg(windows)]
+const TTY: &str = "CONIN$";
+#[cfg(not(windows))]
+const TTY: &str = "/dev/tty";
+
+static TERMINAL: OnceLock<Option<Mutex<BufReader<File>>>> = OnceLock::new();
+
+/// The terminal answers are read from in place of stdin, if that's been turned on
+/// with [`set_tty_input`], stdin isn't a terminal itself, and there's one to open.
+fn tty() -> Option<&'static Mutex<BufReader<File>>> {
+    if !TTY_INPUT.load(Ordering::Relaxed) || stdin().is_terminal() {
+        return None;
+    }
+    let open = || OpenOptions::new().read(true).write(true).open(TTY);
+    TERMINAL
+        .get_or_init(|| open().ok().map(|tty| Mutex::new(BufReader::new(tty))))
+        .as_ref()
+}
+
+/// Whether answers are typed on a terminal: stdin, or the one opened in its place.
+fn input_is_terminal() -> bool {
+    stdin().is_terminal() || tty().is_some()
+}
+
+/// Reads a line from stdin, or from the terminal opened in its place.
+fn read_input_line(line: &mut String) -> io::Result<usize> {
+    match tty() {
+        Some(tty) => tty
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .read_line(line),
+        None => stdin().read_line(line),
+    }
+}
+
+/// Puts the terminal in raw mode until dropped, so it's restored even when a
+/// prompt bails out early with `?`.
+pub(crate) struct RawMode {
+    backend: Option<Rc<RefCell<dyn Backend>>>,
+}
+
+impl RawMode {
+    pub(crate) fn enable() -> Result<Self, PromptError> {
+        output(|out| out.flush())?;
+        let backend = redirected();
+        match &backend {
+            Some(backend) => backend.borrow_mut().set_raw_mode(true)?,
+            None => set_terminal_raw_mode(true)?,
+        }
+        Ok(RawMode { backend })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = match &self.backend {
+            Some(backend) => backend.borrow_mut().set_raw_mode(false),
+            None => set_terminal_raw_mode(false),
+        };
+    }
+}
+
+/// Lines read from stdin by a background thread, so reads can give up without
+/// leaving a half-read line behind. Once the thread is running, every line is
+/// read through it.
+static LINES: OnceLock<Mutex<Receiver<io::Result<String>>>> = OnceLock::new();
+
+fn lines() -> &'static Mutex<Receiver<io::Result<String>>> {
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::sync_channel(0);
+        thread::spawn(move || loop {
+            let mut line = String::new();
+            let read = read_input_line(&mut line).map(|_| line);
+            let done = !matches!(&read, Ok(line) if !line.is_empty());
+            if sender.send(read).is_err() || done {
+                break;
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// Reads a line from stdin, or the terminal opened in its place, like `read_line`,
+/// line ending and all, or an empty string once it's closed. Returns [`PromptError::Timeout`] if `deadline`
+/// passes first. Redirected streams are read straight away, without a deadline,
+/// and a stand-in terminal's key presses are taken up to Enter and echoed the way
+/// a terminal reading a line echoes them.
+pub(crate) fn read_stdin_line(deadline: Option<Instant>) -> Result<String, PromptError> {
+    if let Some(streams) = redirected() {
+        let mut streams = streams.borrow_mut();
+        let mut line = String::new();
+        if !streams.is_terminal() {
+            streams.reader().read_line(&mut line)?;
+            return Ok(line);
+        }
+        loop {
+            let key = match streams.read_key(None) {
+                Ok(Some(key)) => key,
+                Ok(None) => continue,
+                Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(err) => return Err(err.into()),
+            };
+            match key {
+                Key::Enter => {
+                    line.push('\n');
+                    writeln!(streams.writer())?;
+                    break;
+                }
+                Key::Char(c) => {
+                    line.push(c);
+                    write!(streams.writer(), "{}", c)?;
+                }
+                Key::Backspace => {
+                    // The last character is wiped from the screen too, the way a
+                    // terminal reading a line wipes it.
+                    let Some((end, erased)) = line.grapheme_indices(true).next_back() else {
+                        continue;
+                    };
+                    let erase = "\x08 \x08".repeat(erased.width());
+                    line.truncate(end);
+                    write!(streams.writer(), "{}", erase)?;
+                }
+                _ => {}
+            }
+        }
+        return Ok(line);
+    }
+    if wait(deadline).is_none() && LINES.get().is_none() {
+        let mut line = String::new();
+        read_input_line(&mut line)?;
+        return Ok(line);
+    }
+
+    let receiver = lines().lock().unwrap_or_else(PoisonError::into_inner);
+    let received = loop {
+        if called_off() {
+            return Err(PromptError::Cancelled);
+        }
+        let Some(wait) = wait(deadline) else {
+            break receiver.recv().ok();
+        };
+        match receiver.recv_timeout(wait) {
+            Err(RecvTimeoutError::Timeout) if passed(deadline) => return Err(PromptError::Timeout),
+            Err(RecvTimeoutError::Timeout) => continue,
+            received => break received.ok(),
+        }
+    };
+    // The thread hangs up after passing on the end of input.
+    Ok(received.transpose()?.unwrap_or_default())
+}
+
+/// Whether `deadline` has come.
+fn passed(deadline: Option<Instant>) -> bool {
+    deadline.is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Waits for the next key press. Must be called in raw mode.
+///
+/// Ctrl+C doesn't raise a signal in raw mode, so it's turned into
+/// [`PromptError::Interrupted`] here instead.
+pub(crate) fn read_key() -> Result<Key, PromptError> {
+    read_key_before(None)
+}
+
+/// Like [`read_key`], but returns [`PromptError::Timeout`] if `deadline` passes
+/// before a key is pressed.
+///
+/// Redirected streams that run out of keys give up straight away, with
+/// [`PromptError::Timeout`] if there's a deadline and [`PromptError::Eof`] if not.
+pub(crate) fn read_key_before(deadline: Option<Instant>) -> Result<Key, PromptError> {
+    loop {
+        if called_off() {
+            return Err(PromptError::Cancelled);
+        }
+        let key = match redirected() {
+            Some(streams) => streams.borrow_mut().read_key(wait(deadline)),
+            None => terminal_key(wait(deadline)),
+        };
+        match key {
+            Ok(Some(Key::Ctrl('c'))) => return Err(PromptError::Interrupted),
+            Ok(Some(key)) => return Ok(key),
+            Ok(None) if passed(deadline) => return Err(PromptError::Timeout),
+            Ok(None) => continue,
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof && deadline.is_some() => {
+                return Err(PromptError::Timeout)
+            }
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+/// Turns raw mode on or off for the terminal on stdin.
+#[cfg(not(target_arch = "wasm32"))]
+fn set_terminal_raw_mode(on: bool) -> io::Result<()> {
+    match on {
+        true => terminal::enable_raw_mode(),
+        false => terminal::disable_raw_mode(),
+    }
+}
+
+/// How many columns and rows the terminal has, or 80 by 24 if it can't say.
+#[cfg(not(target_arch = "wasm32"))]
+fn terminal_size() -> (usize, usize) {
+    terminal::size()
+        .ok()
+        .filter(|&(columns, rows)| columns > 0 && rows > 0)
+        .map_or(DEFAULT_SIZE, |(columns, rows)| {
+            (columns as usize, rows as usize)
+        })
+}
+
+/// The next key pressed on the terminal within `timeout`, if there is one, with
+/// crossterm. Must be called in raw mode.
+#[cfg(not(target_arch = "wasm32"))]
+fn terminal_key(timeout: Option<Duration>) -> io::Result<Option<Key>> {
+    loop {
+        if let Some(timeout) = timeout {
+            if !event::poll(timeout)? {
+                return Ok(None);
+            }
+        }
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+
+        // Windows reports AltGr as Ctrl and Alt together, so characters typed with
+        // it, like `@` on a German keyboard, are characters and not Ctrl shortcuts.
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL)
+            && !key.modifiers.contains(KeyModifiers::ALT);
+        let key = match key.code {
+            // Some consoles pass Enter and Backspace on as the characters they type.
+            KeyCode::Char('\r' | '\n') => Key::Enter,
+            KeyCode::Char('\x08' | '\x7f') => Key::Backspace,
+            KeyCode::Char(c) if ctrl => Key::Ctrl(c.to_ascii_lowercase()),
+            KeyCode::Char(c) => Key::Char(c),
+            KeyCode::Up => Key::Up,
+            KeyCode::Down => Key::Down,
+            KeyCode::Left => Key::Left,
+            KeyCode::Right => Key::Right,
+            KeyCode::Home => Key::Home,
+            KeyCode::End => Key::End,
+            KeyCode::PageUp => Key::PageUp,
+            KeyCode::PageDown => Key::PageDown,
+            KeyCode::Enter => Key::Enter,
+            KeyCode::Tab => Key::Tab,
+            KeyCode::Backspace => Key::Backspace,
+            KeyCode::Delete => Key::Delete,
+            KeyCode::Esc => Key::Esc,
+            _ => continue,
+        };
+
+        return Ok(Some(key));
+    }
+}
+
+// WebAssembly has no terminal of its own: prompts there only read and draw on the
+// backends they're run with.
+
+#[cfg(target_arch = "wasm32")]
+fn set_terminal_raw_mode(_on: bool) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn terminal_size() -> (usize, usize) {
+    DEFAULT_SIZE
+}
+
+#[cfg(target_arch = "wasm32")]
+fn terminal_key(_timeout: Option<Duration>) -> io::Result<Option<Key>> {
+    Err(io::ErrorKind::Unsupported.into())
+}
+
+/// Reads one line without echoing it, until `deadline` if there is one. If
+/// `escape` is set, Esc gives up on the line and returns [`ESCAPE`].
+///
+/// Falls back to a plain `read_line` when stdin isn't a terminal, since
+/// there's nothing to hide from a pipe. Either way only the line ending is removed.
+pub(crate) fn read_hidden(escape: bool, deadline: Option<Instant>) -> Result<Buffer, PromptError> {
+    output(|out| out.flush())?;
+    if !stdin_is_terminal() {
+        let mut input = buffer(read_stdin_line(deadline)?);
+        if input.is_empty() {
+            return Err(PromptError::Eof);
+        }
+        let len = input.trim_end_matches(['\r', '\n']).len();
+        input.truncate(len);
+        return Ok(input);
+    }
+
+    let raw = RawMode::enable()?;
+    let input = read_hidden_raw(escape, deadline);
+    drop(raw);
+    // Enter wasn't echoed either, so move past the prompt ourselves.
+    outln!()?;
+
+    input
+}
+
+fn read_hidden_raw(escape: bool, deadline: Option<Instant>) -> Result<Buffer, PromptError> {
+    // Reserved up front so pushing doesn't leave unscrubbed copies behind.
+    let mut input = buffer(String::with_capacity(256));
+    loop {
+        match read_key_before(deadline)? {
+            Key::Enter => return Ok(input),
+            Key::Esc if escape => return Ok(buffer(ESCAPE.to_string())),
+            Key::Ctrl('d') if input.is_empty() => return Err(PromptError::Eof),
+            Key::Ctrl('u') => input.clear(),
+            Key::Char(c) => input.push(c),
+            Key::Backspace => {
+                let end = input
+                    .grapheme_indices(true)
+                    .next_back()
+                    .map_or(0, |(i, _)| i);
+                input.truncate(end);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Redraws a block of lines in place. Each call to [`draw`](Frame::draw) replaces
+/// whatever the previous call printed.
+pub(crate) struct Frame {
+    /// The terminal row the cursor is on, counted from the top of the frame.
+    row: usize,
+    /// How many terminal rows each of the lines last drawn took, so lines too wide
+    /// for the terminal are rewound past in full.
+    heights: Vec<usize>,
+}
+
+impl Frame {
+    pub(crate) fn new() -> Self {
+        Frame {
+            row: 0,
+            heights: Vec::new(),
+        }
+    }
+
+    /// Replaces the previous frame with `lines`, leaving the cursor at the end of
+    /// the last one. Must be called in raw mode.
+    pub(crate) fn draw(&mut self, lines: &[String]) -> Result<(), PromptError> {
+        #[cfg(feature = "bidi")]
+        let laid_out = crate::bidi::lay_out(lines);
+        #[cfg(feature = "bidi")]
+        let lines = laid_out.as_deref().unwrap_or(lines);
+        output(|out| {
+            self.rewind(out)?;
+            write!(out, "{}", lines.join("\r\n"))?;
+            out.flush()
+        })?;
+        let columns = size().0;
+        self.heights = lines
+            .iter()
+            .map(|line| width(line).div_ceil(columns).max(1))
+            .collect();
+        self.row = self.heights.iter().sum::<usize>().saturating_sub(1);
+        Ok(())
+    }
+
+    /// Moves the cursor to `column` of line `row`, which must be no further down
+    /// than where it is now, e.g. back to an input line above a list.
+    pub(crate) fn place_cursor(&mut self, row: usize, column: usize) -> Result<(), PromptError> {
+        let columns = size().0;
+        let target = self.heights.iter().take(row).sum::<usize>() + column / columns;
+        output(|out| {
+            if target < self.row {
+                out.queue(ansi::MoveUp((self.row - target) as u16))?;
+            }
+            out.queue(ansi::MoveToColumn((column % columns) as u16))?;
+            out.flush()
+        })?;
+        self.row = target;
+        Ok(())
+    }
+
+    /// Erases the frame, leaving the cursor where it started.
+    pub(crate) fn clear(&mut self) -> Result<(), PromptError> {
+        output(|out| {
+            self.rewind(out)?;
+            out.flush()
+        })?;
+        self.row = 0;
+        Ok(())
+    }
+
+    fn rewind(&self, out: &mut dyn Write) -> io::Result<()> {
+        out.queue(ansi::MoveToColumn(0))?;
+        if self.row > 0 {
+            out.queue(ansi::MoveUp(self.row as u16))?;
+        }
+        out.queue(ansi::Clear(ansi::ClearType::FromCursorDown))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::MockTerminal;
+
+    /// What's left of `chunks` after writing them through [`Stripped`] one by one.
+    fn stripped(chunks: &[&str]) -> String {
+        let mut out = Vec::new();
+        let mut plain = Stripped {
+            out: &mut out,
+            escape: Escape::None,
+        };
+        for chunk in chunks {
+            plain.write_all(chunk.as_bytes()).unwrap();
+        }
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn strips_control_sequences() {
+        assert_eq!(stripped(&["\x1b[1;31mred\x1b[0m plain"]), "red plain");
+        assert_eq!(stripped(&["\x1b[2K\x1b[1Gline"]), "line");
+        assert_eq!(stripped(&["\x1b[38;5;208m\u{e9}\x1b[0m"]), "\u{e9}");
+    }
+
+    #[test]
+    fn strips_sequences_split_across_writes() {
+        assert_eq!(
+            stripped(&["bold: \x1b", "[1mbold\x1b[", "0m"]),
+            "bold: bold"
+        );
+        assert_eq!(stripped(&["\x1b[38;5", ";208mx"]), "x");
+    }
+
+    #[test]
+    fn strips_two_byte_escapes() {
+        assert_eq!(stripped(&["a\x1bcb"]), "ab");
+        assert_eq!(stripped(&["a\x1b", "7b"]), "ab");
+    }
+
+    #[test]
+    fn backspace_wipes_what_it_erases() {
+        let mut terminal = MockTerminal::new()
+            .type_text("adx")
+            .press(Key::Backspace)
+            .type_text("a!")
+            .press(Key::Backspace)
+            .type_text("\n");
+        let line = terminal.run(|| read_stdin_line(None));
+        assert_eq!(line.unwrap(), "ada\n");
+        assert_eq!(terminal.screen(), "ada");
+    }
+}
@@ -0,0 +1,233 @@
+//! Driving prompts from tests, with a stand-in for the terminal.
+
+pub use crate::term::Key;
+use crate::term::{self, Backend};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::rc::Rc;
+use std::time::Duration;
+
+/// A pretend terminal for unit-testing prompt flows. Prompts run inside
+/// [`run`](MockTerminal::run) take their key presses from it, arrows and Esc
+/// included, and draw on it like they would on a real one.
+///
+/// Prompts that run out of keys return [`PromptError::Eof`](crate::PromptError::Eof),
+/// or [`PromptError::Timeout`](crate::PromptError::Timeout) if they have a timeout.
+/// `Key::Ctrl('c')` interrupts them.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// use cumaea::test::{Key, MockTerminal};
+///
+/// # fn main() -> Result<(), PromptError> {
+/// let mut terminal = MockTerminal::new()
+///     .type_text("ada\n")
+///     .press(Key::Down)
+///     .press(Key::Enter);
+/// let (name, shell) = terminal.run(|| {
+///     let name = Prompt::text("Name").ask()?;
+///     let shell = Prompt::menu("Shell", &["bash", "zsh", "fish"]).ask()?;
+///     Ok::<_, PromptError>((name, shell))
+/// })?;
+/// assert_eq!((name.as_str(), shell), ("ada", 1));
+/// assert_eq!(terminal.screen(), "Name: ada\nShell: zsh");
+/// # Ok(())
+/// # }
+/// ```
+pub struct MockTerminal {
+    state: Rc<RefCell<State>>,
+}
+
+struct State {
+    keys: VecDeque<Key>,
+    output: Vec<u8>,
+    input: io::Empty,
+    size: (usize, usize),
+}
+
+impl Backend for State {
+    fn reader(&mut self) -> &mut dyn BufRead {
+        &mut self.input
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        &mut self.output
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+
+    fn read_key(&mut self, _timeout: Option<Duration>) -> io::Result<Option<Key>> {
+        match self.keys.pop_front() {
+            Some(key) => Ok(Some(key)),
+            None => Err(io::ErrorKind::UnexpectedEof.into()),
+        }
+    }
+
+    fn size(&self) -> (usize, usize) {
+        self.size
+    }
+}
+
+impl MockTerminal {
+    /// Creates an 80 by 24 terminal with no keys to press and nothing on it.
+    pub fn new() -> Self {
+        MockTerminal {
+            state: Rc::new(RefCell::new(State {
+                keys: VecDeque::new(),
+                output: Vec::new(),
+                input: io::empty(),
+                size: (80, 24),
+            })),
+        }
+    }
+
+    /// Sets how many columns and rows the terminal has, in place of 80 by 24, for
+    /// the prompts that fit what they draw to it.
+    pub fn size(self, columns: usize, rows: usize) -> Self {
+        self.state.borrow_mut().size = (columns.max(1), rows.max(1));
+        self
+    }
+
+    /// Queues up `key` to be pressed after the keys queued so far.
+    pub fn press(self, key: Key) -> Self {
+        self.state.borrow_mut().keys.push_back(key);
+        self
+    }
+
+    /// Queues up typing `text`, one key per character. Newlines press Enter.
+    pub fn type_text(self, text: &str) -> Self {
+        self.state
+            .borrow_mut()
+            .keys
+            .extend(text.chars().map(|c| match c {
+                '\n' => Key::Enter,
+                c => Key::Char(c),
+            }));
+        self
+    }
+
+    /// Runs `f`, with the prompts it shows on this thread reading keys from and
+    /// drawing on this terminal, and returns what it returns.
+    pub fn run<T>(&mut self, f: impl FnOnce() -> T) -> T {
+        term::redirect(self.state.clone(), f)
+    }
+
+    /// How many queued keys haven't been pressed yet.
+    pub fn keys_left(&self) -> usize {
+        self.state.borrow().keys.len()
+    }
+
+    /// Everything the prompts have printed so far, escape codes and all.
+    pub fn output(&self) -> String {
+        String::from_utf8_lossy(&self.state.borrow().output).into_owned()
+    }
+
+    /// What the terminal shows now, with the effects of cursor movement and
+    /// clearing applied and colors left out. Lines are separated by `\n`, with
+    /// trailing spaces and blank lines at the bottom trimmed.
+    pub fn screen(&self) -> String {
+        let mut screen = Screen::default();
+        screen.write(&self.output());
+        let lines: Vec<String> = screen
+            .lines
+            .iter()
+            .map(|line| line.iter().collect::<String>().trim_end().to_string())
+            .collect();
+        lines.join("\n").trim_end().to_string()
+    }
+}
+
+impl Default for MockTerminal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Just enough of a terminal to play back what the prompts print.
+#[derive(Default)]
+struct Screen {
+    lines: Vec<Vec<char>>,
+    row: usize,
+    column: usize,
+}
+
+impl Screen {
+    fn write(&mut self, output: &str) {
+        let mut chars = output.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' => self.column = 0,
+                '\n' => {
+                    self.row += 1;
+                    self.column = 0;
+                }
+                '\u{8}' => self.column = self.column.saturating_sub(1),
+                '\x1b' => {
+                    if chars.next() != Some('[') {
+                        continue;
+                    }
+                    let mut params = String::new();
+                    let mut command = None;
+                    for c in chars.by_ref() {
+                        match c {
+                            '0'..='9' | ';' | '?' => params.push(c),
+                            c => {
+                                command = Some(c);
+                                break;
+                            }
+                        }
+                    }
+                    self.escape(&params, command);
+                }
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+    }
+
+    fn escape(&mut self, params: &str, command: Option<char>) {
+        let n = params.parse::<usize>().ok();
+        match command {
+            Some('A') => self.row = self.row.saturating_sub(n.unwrap_or(1)),
+            Some('B') => self.row += n.unwrap_or(1),
+            Some('C') => self.column += n.unwrap_or(1),
+            Some('D') => self.column = self.column.saturating_sub(n.unwrap_or(1)),
+            Some('G') => self.column = n.unwrap_or(1).saturating_sub(1),
+            Some('J') => {
+                let column = self.column;
+                self.line().truncate(column);
+                self.lines.truncate(self.row + 1);
+            }
+            Some('K') => match n.unwrap_or(0) {
+                2 => self.line().clear(),
+                _ => {
+                    let column = self.column;
+                    self.line().truncate(column);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn line(&mut self) -> &mut Vec<char> {
+        if self.lines.len() <= self.row {
+            self.lines.resize(self.row + 1, Vec::new());
+        }
+        &mut self.lines[self.row]
+    }
+
+    fn put(&mut self, c: char) {
+        let column = self.column;
+        let line = self.line();
+        if line.len() <= column {
+            line.resize(column + 1, ' ');
+        }
+        line[column] = c;
+        self.column += 1;
+    }
+}
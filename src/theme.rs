@@ -0,0 +1,370 @@
+//! The look shared by every prompt, so it can be set once rather than per call.
+
+use crate::config;
+use crate::term;
+use crate::{Choice, ChoiceColor};
+use std::borrow::Cow;
+use std::env;
+use std::sync::{Mutex, PoisonError, RwLock};
+
+/// How one part of a prompt is drawn: the colors and attributes it's given,
+/// applied in order. The default is plain text.
+///
+/// # Examples
+///
+/// ```rust
+/// # use cumaea::*;
+/// let banner = Style::new()
+///     .color(Choice::Bright(ChoiceColor::White))
+///     .color(Choice::On(ChoiceColor::Blue))
+///     .bold();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Style {
+    effects: Vec<Effect>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Effect {
+    Color(Choice),
+    Bold,
+    Underline,
+    Italic,
+    Dimmed,
+    Reversed,
+}
+
+impl Style {
+    /// Plain text.
+    pub fn new() -> Self {
+        Style::default()
+    }
+
+    /// Adds `color` on top of the colorings given so far, so a foreground and a
+    /// background can be combined.
+    pub fn color(self, color: Choice) -> Self {
+        self.with(Effect::Color(color))
+    }
+
+    /// Makes the text bold.
+    pub fn bold(self) -> Self {
+        self.with(Effect::Bold)
+    }
+
+    /// Underlines the text.
+    pub fn underline(self) -> Self {
+        self.with(Effect::Underline)
+    }
+
+    /// Makes the text italic, where the terminal can.
+    pub fn italic(self) -> Self {
+        self.with(Effect::Italic)
+    }
+
+    /// Makes the text fainter.
+    pub fn dimmed(self) -> Self {
+        self.with(Effect::Dimmed)
+    }
+
+    /// Swaps the text's foreground and background colors.
+    pub fn reversed(self) -> Self {
+        self.with(Effect::Reversed)
+    }
+
+    fn with(mut self, effect: Effect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// Applies this style to `text`, unless colors are off.
+    pub(crate) fn paint(&self, text: &str) -> String {
+        if text.is_empty() || self.effects.is_empty() || !colors_enabled() {
+            return text.to_string();
+        }
+        let codes: Vec<Cow<str>> = self
+            .effects
+            .iter()
+            .map(|effect| match effect {
+                Effect::Color(color) => color.code(),
+                Effect::Bold => "1".into(),
+                Effect::Dimmed => "2".into(),
+                Effect::Italic => "3".into(),
+                Effect::Underline => "4".into(),
+                Effect::Reversed => "7".into(),
+            })
+            .collect();
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), text)
+    }
+}
+
+impl From<Choice> for Style {
+    fn from(color: Choice) -> Self {
+        Style::new().color(color)
+    }
+}
+
+/// The styles every prompt is drawn with. A style or color given to a single
+/// prompt, like with [`PromptBuilder::style`](crate::PromptBuilder::style) or the
+/// `colored` argument of the free functions, takes the place of the matching style
+/// here.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_theme(Theme {
+///     prompt_style: Choice::Bright(ChoiceColor::Cyan).into(),
+///     highlight_style: Choice::Normal(ChoiceColor::Green).into(),
+///     ..Theme::default()
+/// });
+/// let name = prompt_text("Enter your", "name", None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Theme {
+    /// The question.
+    pub prompt_style: Style,
+    /// The choices listed in brackets after the question, like in
+    /// [`prompt_selection`](crate::prompt_selection).
+    pub list_style: Style,
+    /// The highlighted item in menus and lists.
+    pub highlight_style: Style,
+    /// The headers over groups of items in menus and lists. Bold by default.
+    pub header_style: Style,
+    /// The default, where it's shown, like the default's item in the list of
+    /// [`prompt_selection`](crate::prompt_selection) or the number the numbered
+    /// menus pick on an empty answer.
+    pub default_style: Style,
+    /// The answer as it's typed, and the pick a menu prints once it's made.
+    pub answer_style: Style,
+    /// Why an answer was rejected. Red by default.
+    pub error_style: Style,
+    /// The symbol left before a question once it's answered. Green by default.
+    pub success_style: Style,
+    /// The symbols drawn before questions. There are none by default.
+    pub symbols: Symbols,
+    /// The arrow of the line a prompt is left as once it's answered, like
+    /// `Name → ada`, so the answers given read as a list. There's none by default,
+    /// which leaves the question as it was asked, with the answer after it.
+    pub summary: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            prompt_style: Style::new(),
+            list_style: Style::new(),
+            highlight_style: Style::new(),
+            header_style: Style::new().bold(),
+            default_style: Style::new(),
+            answer_style: Style::new(),
+            error_style: Choice::Normal(ChoiceColor::Red).into(),
+            success_style: Choice::Normal(ChoiceColor::Green).into(),
+            symbols: Symbols::default(),
+            summary: None,
+        }
+    }
+}
+
+impl Theme {
+    /// The symbol for a question that's `status`, painted and with a space after
+    /// it, or nothing if the theme has none.
+    pub(crate) fn mark(&self, status: Status) -> String {
+        let (symbol, style) = match status {
+            Status::Asking => (&self.symbols.asking, &self.highlight_style),
+            Status::Answered => (&self.symbols.answered, &self.success_style),
+            Status::Cancelled => (&self.symbols.cancelled, &self.error_style),
+        };
+        match symbol.is_empty() {
+            true => String::new(),
+            false => format!("{} ", style.paint(symbol)),
+        }
+    }
+
+    /// The line `prompt` is left as once it's `status` with `answer`, if the theme
+    /// has a summary arrow.
+    pub(crate) fn summary(&self, status: Status, prompt: &str, answer: &str) -> Option<String> {
+        self.summary.as_ref().map(|arrow| {
+            format!(
+                "{}{} {} {}",
+                self.mark(status),
+                self.prompt_style.paint(prompt),
+                arrow,
+                self.answer_style.paint(answer)
+            )
+        })
+    }
+}
+
+/// Where a question is, for the symbol drawn before it.
+#[derive(Clone, Copy)]
+pub(crate) enum Status {
+    Asking,
+    Answered,
+    Cancelled,
+}
+
+/// The symbols drawn before a question: one while it's asked, in the theme's
+/// highlight style, and one it's left with once it's answered, or skipped or
+/// cancelled. Each is followed by a space, and an empty one isn't drawn.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// set_theme(Theme {
+///     symbols: Symbols::detect(),
+///     ..Theme::default()
+/// });
+/// // Asks "? Name: ", and leaves "✔ Name: ada" once it's answered.
+/// let name = Prompt::text("Name").ask();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Symbols {
+    /// Before a question while it's asked, like `?`.
+    pub asking: String,
+    /// Before a question once it's answered, like `✔`.
+    pub answered: String,
+    /// Before a question once it's skipped or cancelled, like `✖`.
+    pub cancelled: String,
+}
+
+impl Symbols {
+    /// `?`, `✔` and `✖`.
+    pub fn unicode() -> Self {
+        Symbols {
+            asking: "?".to_string(),
+            answered: "✔".to_string(),
+            cancelled: "✖".to_string(),
+        }
+    }
+
+    /// `?`, `+` and `x`, for terminals that can't show the [`unicode`](Symbols::unicode)
+    /// ones.
+    pub fn ascii() -> Self {
+        Symbols {
+            asking: "?".to_string(),
+            answered: "+".to_string(),
+            cancelled: "x".to_string(),
+        }
+    }
+
+    /// The [`unicode`](Symbols::unicode) symbols if the locale the program runs in
+    /// is UTF-8, as `LC_ALL`, `LC_CTYPE` or `LANG` say, or it's in Windows
+    /// Terminal, and the [`ascii`](Symbols::ascii) ones otherwise.
+    pub fn detect() -> Self {
+        match unicode() {
+            true => Symbols::unicode(),
+            false => Symbols::ascii(),
+        }
+    }
+}
+
+/// Whether the terminal can be expected to show more than ASCII: the locale the
+/// program runs in is UTF-8, as `LC_ALL`, `LC_CTYPE` or `LANG` say, or it's in
+/// Windows Terminal.
+fn unicode() -> bool {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    let utf8 = locale.contains("utf-8") || locale.contains("utf8");
+    utf8 || env::var_os("WT_SESSION").is_some()
+}
+
+/// The frames of the spinner drawn while something runs, in Braille dots where
+/// the terminal can show them.
+pub(crate) fn spinner() -> &'static [&'static str] {
+    match unicode() {
+        true => &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"],
+        false => &["|", "/", "-", "\\"],
+    }
+}
+
+static THEME: RwLock<Option<Theme>> = RwLock::new(None);
+
+/// Sets the theme every prompt is drawn with from here on, on every thread.
+pub fn set_theme(theme: Theme) {
+    *THEME.write().unwrap_or_else(PoisonError::into_inner) = Some(theme);
+}
+
+/// The theme set with [`set_theme`], or the default one.
+pub(crate) fn theme() -> Theme {
+    THEME
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default()
+}
+
+/// When prompts are drawn in color, set with [`set_color_mode`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorMode {
+    /// In color when the output is a terminal, unless `NO_COLOR` or
+    /// `CUMAEA_NO_COLOR` is set or `CLICOLOR` is `0`. A `CLICOLOR_FORCE` other than
+    /// `0` turns colors on even when the output isn't a terminal.
+    #[default]
+    Auto,
+    /// Always in color, unless plain mode is turned on with
+    /// [`set_plain`](crate::set_plain).
+    Always,
+    /// Never in color.
+    Never,
+}
+
+static COLOR_MODE: Mutex<ColorMode> = Mutex::new(ColorMode::Auto);
+
+/// Sets when prompts are drawn in color from here on, on every thread, in place
+/// of the environment's say. Only this crate's prompts are affected.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// if std::env::args().any(|arg| arg == "--no-color") {
+///     set_color_mode(ColorMode::Never);
+/// }
+/// ```
+pub fn set_color_mode(mode: ColorMode) {
+    *COLOR_MODE.lock().unwrap_or_else(PoisonError::into_inner) = mode;
+}
+
+/// Whether prompts should be drawn in color right now.
+fn colors_enabled() -> bool {
+    if term::plain() {
+        return false;
+    }
+    match *COLOR_MODE.lock().unwrap_or_else(PoisonError::into_inner) {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto if no_color() => false,
+        ColorMode::Auto if forced() => true,
+        ColorMode::Auto => !is("CLICOLOR", "0") && term::output_is_terminal(),
+    }
+}
+
+/// Whether prompts are drawn in color even when what they print doesn't go to a
+/// terminal.
+pub(crate) fn colors_forced() -> bool {
+    match *COLOR_MODE.lock().unwrap_or_else(PoisonError::into_inner) {
+        ColorMode::Always => true,
+        ColorMode::Never => false,
+        ColorMode::Auto => !no_color() && forced(),
+    }
+}
+
+/// Whether `NO_COLOR` or `CUMAEA_NO_COLOR` turns colors off.
+fn no_color() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) || config::env_config().no_color
+}
+
+/// Whether `CLICOLOR_FORCE` turns colors on.
+fn forced() -> bool {
+    env::var_os("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty())
+        && !is("CLICOLOR_FORCE", "0")
+}
+
+/// Whether `var` is set to `value`.
+fn is(var: &str, value: &str) -> bool {
+    env::var_os(var).is_some_and(|set| set == value)
+}
@@ -0,0 +1,390 @@
+//! A menu over nested items, like categories and what's in them.
+
+use crate::builder::{self, Attempts, NonInteractive};
+use crate::keymap::{self, Action, Keymap};
+use crate::locale::{fill, locale};
+use crate::select::{answered, cancel, page, page_size, parse_numbers, question, RESERVED};
+use crate::session;
+use crate::term::{self, out, outln, Frame, RawMode};
+use crate::theme::{self, Style};
+use crate::{paint, print_error, read_input, Choice, Prompt, PromptError};
+use std::collections::BTreeSet;
+
+/// An item in a [`TreeSelect`]: a leaf that can be picked, or a branch holding
+/// more items.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Node {
+    label: String,
+    children: Option<Vec<Node>>,
+}
+
+impl Node {
+    /// An item that can be picked.
+    pub fn leaf(label: &str) -> Self {
+        Node {
+            label: label.to_string(),
+            children: None,
+        }
+    }
+
+    /// An item that opens to show `children`.
+    pub fn branch(label: &str, children: Vec<Node>) -> Self {
+        Node {
+            label: label.to_string(),
+            children: Some(children),
+        }
+    }
+}
+
+impl Prompt {
+    /// A menu over nested items, like [`prompt_tree_select`](crate::prompt_tree_select).
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let services = [
+    ///     Node::branch("Databases", vec![Node::leaf("Postgres"), Node::leaf("MySQL")]),
+    ///     Node::branch("Caches", vec![Node::leaf("Redis"), Node::leaf("Memcached")]),
+    /// ];
+    /// let path = Prompt::tree_select("Service", &services).ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tree_select(prompt: &str, nodes: &[Node]) -> TreeSelect {
+        TreeSelect {
+            prompt: prompt.to_string(),
+            nodes: nodes.to_vec(),
+            style: None,
+            max_attempts: None,
+            non_interactive: NonInteractive::Read,
+            env: None,
+            key: None,
+            keymap: None,
+        }
+    }
+}
+
+/// A menu over nested items, created through [`Prompt::tree_select`].
+///
+/// Branches start out closed. Up/Down (or `k`/`j`) move the highlight, Home/End
+/// jump to either end, and Enter opens or closes the highlighted branch or picks the
+/// highlighted leaf, unless the [`Keymap`] says otherwise. Space opens and closes
+/// branches too, and a tree too long for the terminal is shown a page at a time.
+/// When stdin or stderr isn't a terminal, the leaves are printed as a
+/// numbered list of paths, like `Databases / Postgres`, and the pick is read as a
+/// number instead.
+pub struct TreeSelect {
+    prompt: String,
+    nodes: Vec<Node>,
+    style: Option<Style>,
+    max_attempts: Option<usize>,
+    non_interactive: NonInteractive,
+    env: Option<String>,
+    key: Option<String>,
+    keymap: Option<Keymap>,
+}
+
+/// A line of the tree as shown: the path to its node, and the node.
+struct Row<'a> {
+    path: Vec<usize>,
+    node: &'a Node,
+}
+
+impl TreeSelect {
+    /// Sets the coloration of the highlighted item.
+    pub fn color(self, color: Choice) -> Self {
+        self.style(color.into())
+    }
+
+    /// Sets the style of the highlighted item, in place of the theme's highlight style.
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = Some(style);
+        self
+    }
+
+    pub(crate) fn colored(mut self, colored: Option<Choice>) -> Self {
+        self.style = colored.map(Style::from);
+        self
+    }
+
+    /// Gives up with [`PromptError::TooManyAttempts`] once `attempts` picks have
+    /// been rejected in the numbered fallback, instead of asking forever.
+    pub fn max_attempts(mut self, attempts: usize) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Sets what happens when stdin or stderr isn't a terminal. By default the
+    /// pick is read from stdin as a number. There's no default to fall back on, so
+    /// [`NonInteractive::Default`] fails like [`NonInteractive::Fail`].
+    pub fn non_interactive(mut self, non_interactive: NonInteractive) -> Self {
+        self.non_interactive = non_interactive;
+        self
+    }
+
+    /// Takes the pick from the environment variable `var` when it's set and not
+    /// empty, without showing the tree. The value has to be the labels on the way
+    /// to a leaf separated by slashes, like `Databases/Postgres`, or as a JSON array
+    /// of strings; one that isn't is returned as [`PromptError::Validation`].
+    pub fn env(mut self, var: &str) -> Self {
+        self.env = Some(var.to_string());
+        self
+    }
+
+    /// Takes the pick from the file loaded with [`load_answers`](crate::load_answers)
+    /// when it has one under `key`, without showing the tree. The environment
+    /// variable set with `env` still comes first.
+    /// Arguments loaded with [`load_args`](crate::load_args) are taken before the
+    /// file.
+    pub fn key(mut self, key: &str) -> Self {
+        self.key = Some(key.to_string());
+        self
+    }
+
+    /// Sets which keys do what, in place of the keymap set with
+    /// [`set_keymap`](crate::set_keymap).
+    pub fn keymap(mut self, keymap: Keymap) -> Self {
+        self.keymap = Some(keymap);
+        self
+    }
+
+    /// Shows the tree and returns the path to the picked leaf: its index among the
+    /// top-level items, then among that branch's children, and so on.
+    ///
+    /// Returns [`PromptError::Validation`] if there are no leaves to pick from.
+    pub fn ask(&self) -> Result<Vec<usize>, PromptError> {
+        #[cfg(feature = "log")]
+        crate::logging::asking(&self.prompt);
+        #[cfg(feature = "tracing")]
+        let _span = crate::spans::asking(&self.prompt, self.key.as_deref());
+        term::asking(|| {
+            let leaves = self.leaves();
+            if leaves.is_empty() {
+                return Err(PromptError::Validation(
+                    "there's nothing to pick from".into(),
+                ));
+            }
+            if let Some((origin, value)) =
+                builder::given_answer(&self.prompt, self.key.as_ref(), self.env.as_ref(), false)
+            {
+                let labels = session::items(&value, '/');
+                let Some(path) = leaves.into_iter().find(|path| self.labels(path) == labels) else {
+                    let message = format!("there's no {:?} to pick", value.trim());
+                    return Err(builder::rejected(origin, message));
+                };
+                session::record(&self.prompt, &value)?;
+                return Ok(path);
+            }
+            if self.non_interactive.use_default()? {
+                return Err(PromptError::NotInteractive);
+            }
+
+            let path = if term::redraws() {
+                self.ask_interactive()?
+            } else {
+                self.ask_numbered(&leaves)?
+            };
+            session::record(&self.prompt, &session::list(&self.labels(&path)))?;
+            Ok(path)
+        })
+    }
+
+    fn ask_interactive(&self) -> Result<Vec<usize>, PromptError> {
+        let keymap = self.keymap.clone().unwrap_or_else(keymap::keymap);
+        let raw = RawMode::enable()?;
+        let mut frame = Frame::new();
+        let mut open = BTreeSet::new();
+        let mut cursor = 0;
+        let path = loop {
+            let rows = self.rows(&open);
+            let size = page_size(RESERVED);
+            frame.draw(&self.lines(&rows, &open, cursor, size))?;
+            let row = &rows[cursor];
+            match keymap.action(term::read_key()?) {
+                Some(Action::Up) => cursor = cursor.saturating_sub(1),
+                Some(Action::Down) if cursor + 1 < rows.len() => cursor += 1,
+                Some(Action::First) => cursor = 0,
+                Some(Action::Last) => cursor = rows.len() - 1,
+                Some(Action::PageUp) => cursor = cursor.saturating_sub(size),
+                Some(Action::PageDown) => cursor = (cursor + size).min(rows.len() - 1),
+                Some(Action::Pick) if row.node.children.is_none() => break row.path.clone(),
+                Some(Action::Pick | Action::Toggle) if row.node.children.is_some() => {
+                    match open.contains(&row.path) {
+                        true => open.remove(&row.path),
+                        false => open.insert(row.path.clone()),
+                    };
+                }
+                Some(Action::Cancel) => return cancel(&mut frame),
+                _ => {}
+            }
+        };
+
+        frame.clear()?;
+        drop(raw);
+        let shown = self.labels(&path).join(" / ");
+        outln!("{}", answered(&self.prompt, &shown))?;
+        Ok(path)
+    }
+
+    fn lines(
+        &self,
+        rows: &[Row],
+        open: &BTreeSet<Vec<usize>>,
+        cursor: usize,
+        size: usize,
+    ) -> Vec<String> {
+        let theme = theme::theme();
+        let mut lines = vec![format!("{} {}", question(&self.prompt), locale().tree_hint)];
+        let (shown, page_line) = page(cursor, rows.len(), size);
+        for (i, row) in rows.iter().enumerate().skip(shown.start).take(shown.len()) {
+            let indent = "  ".repeat(row.path.len() - 1);
+            let mark = match row.node.children {
+                Some(_) if open.contains(&row.path) => "- ",
+                Some(_) => "+ ",
+                None => "",
+            };
+            if i == cursor {
+                let line = format!("> {}{}{}", indent, mark, row.node.label);
+                lines.push(paint(&line, &self.style, &theme.highlight_style));
+            } else {
+                lines.push(format!("  {}{}{}", indent, mark, row.node.label));
+            }
+        }
+        lines.extend(page_line);
+        lines
+    }
+
+    /// The nodes showing while the branches in `open` are open, top to bottom.
+    fn rows(&self, open: &BTreeSet<Vec<usize>>) -> Vec<Row<'_>> {
+        fn walk<'a>(
+            nodes: &'a [Node],
+            path: &mut Vec<usize>,
+            open: &BTreeSet<Vec<usize>>,
+            rows: &mut Vec<Row<'a>>,
+        ) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                rows.push(Row {
+                    path: path.clone(),
+                    node,
+                });
+                if let Some(children) = node.children.as_ref().filter(|_| open.contains(path)) {
+                    walk(children, path, open, rows);
+                }
+                path.pop();
+            }
+        }
+        let mut rows = Vec::new();
+        walk(&self.nodes, &mut Vec::new(), open, &mut rows);
+        rows
+    }
+
+    /// The paths to every leaf, top to bottom.
+    fn leaves(&self) -> Vec<Vec<usize>> {
+        fn walk(nodes: &[Node], path: &mut Vec<usize>, leaves: &mut Vec<Vec<usize>>) {
+            for (i, node) in nodes.iter().enumerate() {
+                path.push(i);
+                match &node.children {
+                    Some(children) => walk(children, path, leaves),
+                    None => leaves.push(path.clone()),
+                }
+                path.pop();
+            }
+        }
+        let mut leaves = Vec::new();
+        walk(&self.nodes, &mut Vec::new(), &mut leaves);
+        leaves
+    }
+
+    /// The labels of the nodes along `path`.
+    fn labels(&self, path: &[usize]) -> Vec<&str> {
+        let mut nodes = self.nodes.as_slice();
+        let mut labels = Vec::new();
+        for &i in path {
+            let node = &nodes[i];
+            labels.push(node.label.as_str());
+            nodes = node.children.as_deref().unwrap_or_default();
+        }
+        labels
+    }
+
+    fn ask_numbered(&self, leaves: &[Vec<usize>]) -> Result<Vec<usize>, PromptError> {
+        outln!("{}", question(&self.prompt))?;
+        for (i, path) in leaves.iter().enumerate() {
+            outln!("  {}) {}", i + 1, self.labels(path).join(" / "))?;
+        }
+
+        let mut attempts = Attempts::new(&self.prompt, self.max_attempts);
+        loop {
+            out!("{}: ", locale().enter_number)?;
+            let input = read_input()?;
+            match parse_numbers(&input, leaves.len()).as_deref() {
+                Some(&[picked]) => return Ok(leaves[picked].clone()),
+                _ => {
+                    let count = leaves.len();
+                    let message = fill(&locale().pick_number, &[("count", &count)]);
+                    print_error(&message)?;
+                    attempts.reject()?;
+                }
+            }
+        }
+    }
+}
+
+/// Prompts for a leaf of a tree of items given a prompt, the top-level items, and
+/// color option, like for picking from a catalog sorted into categories. Returns
+/// the path to the picked leaf, as its index at each level.
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// let regions = [
+///     Node::branch("Europe", vec![Node::leaf("eu-west"), Node::leaf("eu-north")]),
+///     Node::branch("Asia", vec![Node::leaf("ap-south")]),
+/// ];
+/// let path = prompt_tree_select("Region", &regions, None);
+/// ```
+///
+/// Move the highlight with the arrow keys, open and close branches with Enter, and
+/// pick a leaf with Enter. Picking `eu-north` above returns `[0, 1]`. If stdin or
+/// stderr isn't a terminal, the leaves are printed as a numbered list of paths and
+/// the answer is read as a number.
+///
+/// # Panics
+///
+/// Panics on I/O failure, if stdin is closed, if Ctrl+C is pressed, or if there are
+/// no leaves. [`Prompt::tree_select`] returns a [`PromptError`] instead.
+pub fn prompt_tree_select(prompt: &str, nodes: &[Node], colored: Option<Choice>) -> Vec<usize> {
+    Prompt::tree_select(prompt, nodes)
+        .colored(colored)
+        .ask()
+        .expect("Failed to read selection.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Prompt;
+
+    #[test]
+    fn picks_labels_with_slashes_from_a_list() {
+        let nodes = [
+            Node::branch("TCP/IP", vec![Node::leaf("v4"), Node::leaf("v6")]),
+            Node::leaf("UDP"),
+        ];
+        std::env::set_var("CUMAEA_TEST_TREE_LIST", r#"["TCP/IP", "v6"]"#);
+        let path = Prompt::tree_select("Protocol", &nodes)
+            .env("CUMAEA_TEST_TREE_LIST")
+            .ask();
+        assert_eq!(path.unwrap(), [0, 1]);
+
+        std::env::set_var("CUMAEA_TEST_TREE_PATH", "UDP");
+        let path = Prompt::tree_select("Protocol", &nodes)
+            .env("CUMAEA_TEST_TREE_PATH")
+            .ask();
+        assert_eq!(path.unwrap(), [1]);
+    }
+}
@@ -0,0 +1,199 @@
+//! Prompting on a terminal in the browser, like xterm.js, behind the `wasm`
+//! feature.
+
+use crate::term::{Backend, Key};
+use js_sys::wasm_bindgen::JsValue;
+use js_sys::{Array, Function};
+use std::collections::VecDeque;
+use std::io::{self, BufRead, Write};
+use std::iter::Peekable;
+use std::str::Chars;
+use std::time::Duration;
+
+/// A [`Backend`] for a terminal drawn by JavaScript, like xterm.js, reached through
+/// the functions it's given:
+///
+/// - `write` is called with the text prompts print, escape sequences and all, the
+///   way xterm.js's `write` takes it.
+/// - `read` is called with how many milliseconds to wait, or `undefined` to wait
+///   for as long as it takes, and returns what was typed since it was last called,
+///   the way xterm.js's `onData` passes it on, or `null` if nothing was typed in
+///   time. It has to return before the prompt can go on, so prompts are usually
+///   run in a web worker that waits on a `SharedArrayBuffer` the page fills as
+///   keys are typed.
+///
+/// The terminal is taken to be 80 by 24 unless it's given a
+/// [`size`](JsBackend::size) function. Prompts are run on it with [`with_backend`](crate::with_backend).
+///
+/// # Examples
+///
+/// ```rust,no_run
+/// # use cumaea::*;
+/// use js_sys::Function;
+///
+/// fn ask_name(write: Function, read: Function, size: Function) -> Result<String, PromptError> {
+///     let terminal = JsBackend::new(write, read).size(size);
+///     with_backend(terminal, || Prompt::text("Name").ask())
+/// }
+/// ```
+pub struct JsBackend {
+    writer: JsWriter,
+    read: Function,
+    size: Option<Function>,
+    keys: VecDeque<Key>,
+    reader: io::Empty,
+}
+
+impl JsBackend {
+    /// A terminal that prints with `write` and takes what's typed from `read`.
+    pub fn new(write: Function, read: Function) -> Self {
+        JsBackend {
+            writer: JsWriter {
+                write,
+                pending: Vec::new(),
+            },
+            read,
+            size: None,
+            keys: VecDeque::new(),
+            reader: io::empty(),
+        }
+    }
+
+    /// Sets the function that says how big the terminal is, returning its columns
+    /// and rows as an array like `[term.cols, term.rows]`.
+    pub fn size(mut self, size: Function) -> Self {
+        self.size = Some(size);
+        self
+    }
+}
+
+impl Backend for JsBackend {
+    fn reader(&mut self) -> &mut dyn BufRead {
+        &mut self.reader
+    }
+
+    fn writer(&mut self) -> &mut dyn Write {
+        &mut self.writer
+    }
+
+    fn is_terminal(&self) -> bool {
+        true
+    }
+
+    fn read_key(&mut self, timeout: Option<Duration>) -> io::Result<Option<Key>> {
+        if self.keys.is_empty() {
+            let wait = timeout.map_or(JsValue::UNDEFINED, |timeout| {
+                JsValue::from_f64(timeout.as_secs_f64() * 1000.0)
+            });
+            let typed = self.read.call1(&JsValue::NULL, &wait).map_err(thrown)?;
+            if let Some(typed) = typed.as_string() {
+                self.keys.extend(keys(&typed));
+            }
+        }
+        Ok(self.keys.pop_front())
+    }
+
+    fn size(&self) -> (usize, usize) {
+        let Some(size) = &self.size else {
+            return crate::term::DEFAULT_SIZE;
+        };
+        let Ok(size) = size.call0(&JsValue::NULL) else {
+            return crate::term::DEFAULT_SIZE;
+        };
+        let size = Array::from(&size);
+        let dimension = |i| size.get(i).as_f64().filter(|&n| n >= 1.0);
+        match (dimension(0), dimension(1)) {
+            (Some(columns), Some(rows)) => (columns as usize, rows as usize),
+            _ => crate::term::DEFAULT_SIZE,
+        }
+    }
+}
+
+/// Passes what's written on to a JavaScript function, a whole character at a time.
+struct JsWriter {
+    write: Function,
+    /// The start of a character whose other bytes haven't been written yet.
+    pending: Vec<u8>,
+}
+
+impl Write for JsWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        let whole = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return Err(io::ErrorKind::InvalidData.into()),
+        };
+        if whole > 0 {
+            let text = String::from_utf8_lossy(&self.pending[..whole]);
+            let text = JsValue::from_str(&text);
+            self.write.call1(&JsValue::NULL, &text).map_err(thrown)?;
+            self.pending.drain(..whole);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// The error for something thrown by a JavaScript function.
+fn thrown(thrown: JsValue) -> io::Error {
+    io::Error::other(format!("{:?}", thrown))
+}
+
+/// The keys in `typed`, the characters and escape sequences a terminal sends.
+fn keys(typed: &str) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let mut chars = typed.chars().peekable();
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x1b' if matches!(chars.peek(), Some('[' | 'O')) => {
+                chars.next();
+                match sequence(&mut chars) {
+                    Some(key) => key,
+                    None => continue,
+                }
+            }
+            '\x1b' => Key::Esc,
+            '\r' => {
+                chars.next_if_eq(&'\n');
+                Key::Enter
+            }
+            '\n' => Key::Enter,
+            '\t' => Key::Tab,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x01'..='\x1a' => Key::Ctrl((b'a' + c as u8 - 1) as char),
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// The key an escape sequence stands for, read from just after its `\x1b[` or
+/// `\x1bO`, if it's one prompts use.
+fn sequence(chars: &mut Peekable<Chars>) -> Option<Key> {
+    let mut parameters = String::new();
+    let last = loop {
+        match chars.next()? {
+            c if c.is_ascii_digit() || c == ';' => parameters.push(c),
+            c => break c,
+        }
+    };
+    let key = match (last, parameters.as_str()) {
+        ('A', _) => Key::Up,
+        ('B', _) => Key::Down,
+        ('C', _) => Key::Right,
+        ('D', _) => Key::Left,
+        ('H', _) | ('~', "1" | "7") => Key::Home,
+        ('F', _) | ('~', "4" | "8") => Key::End,
+        ('~', "3") => Key::Delete,
+        ('~', "5") => Key::PageUp,
+        ('~', "6") => Key::PageDown,
+        _ => return None,
+    };
+    Some(key)
+}
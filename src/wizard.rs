@@ -0,0 +1,1053 @@
+//! Asking a series of questions as one flow, with the answers gathered by name.
+
+use crate::confirm;
+use crate::locale::{fill, locale};
+use crate::select::{FuzzySelect, Menu, MultiSelect, Ranking};
+use crate::session::{self, quote};
+use crate::term::{self, outln};
+use crate::theme;
+use crate::{Prompt, PromptBuilder, PromptError, Select, SelectOption, Slider};
+use std::cell::Cell;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+impl Prompt {
+    /// A series of named questions asked one after another, with the answers
+    /// returned together once they're all in.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let answers = Prompt::wizard("New project")
+    ///     .step("name", Prompt::text("Project name"))
+    ///     .step("license", Prompt::menu("License", &["MIT", "Apache-2.0", "GPL-3.0"]))
+    ///     .step("port", Prompt::int("Port").default(8080))
+    ///     .step("git", Prompt::confirm("Create a git repository? (Y/n)").default(true))
+    ///     .ask()?;
+    ///
+    /// let name = answers.get_str("name").unwrap_or_default();
+    /// if answers.get_bool("git") {
+    ///     println!("Creating a repository for {}", name);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn wizard(title: &str) -> Wizard {
+        Wizard {
+            title: title.to_string(),
+            steps: Vec::new(),
+            review: false,
+        }
+    }
+}
+
+/// One answer in a wizard's [`Answers`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    /// A yes or no.
+    Bool(bool),
+    /// A whole number.
+    Int(i64),
+    /// A floating-point number.
+    Float(f64),
+    /// Text, like a typed line or the item picked from a menu.
+    Text(String),
+    /// Several pieces of text, like the items picked from a multi-select.
+    List(Vec<String>),
+}
+
+/// Shows a yes or no in the locale's words for them, `yes` or `no` in English, and
+/// a list with its items separated by commas.
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Bool(true) => write!(f, "{}", locale().yes),
+            Answer::Bool(false) => write!(f, "{}", locale().no),
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::Float(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::List(items) => write!(f, "{}", items.join(", ")),
+        }
+    }
+}
+
+impl Answer {
+    /// The answer as a JSON value. A number that isn't finite is `null`.
+    fn to_json(&self) -> String {
+        match self {
+            Answer::Bool(value) => value.to_string(),
+            Answer::Int(value) => value.to_string(),
+            Answer::Float(value) if value.is_finite() => format!("{:?}", value),
+            Answer::Float(_) => "null".to_string(),
+            Answer::Text(text) => quote(text),
+            Answer::List(items) => {
+                let items: Vec<String> = items.iter().map(|item| quote(item)).collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+#[cfg(feature = "toml")]
+impl Answer {
+    /// The answer as a TOML value.
+    fn to_toml(&self) -> toml::Value {
+        match self {
+            Answer::Bool(value) => toml::Value::Boolean(*value),
+            Answer::Int(value) => toml::Value::Integer(*value),
+            Answer::Float(value) => toml::Value::Float(*value),
+            Answer::Text(text) => toml::Value::String(text.clone()),
+            Answer::List(items) => {
+                toml::Value::Array(items.iter().cloned().map(toml::Value::String).collect())
+            }
+        }
+    }
+}
+
+impl From<bool> for Answer {
+    fn from(value: bool) -> Self {
+        Answer::Bool(value)
+    }
+}
+
+macro_rules! whole_numbers {
+    ($($ty:ty),*) => {$(
+        impl From<$ty> for Answer {
+            fn from(value: $ty) -> Self {
+                Answer::Int(value.into())
+            }
+        }
+    )*};
+}
+
+whole_numbers!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f32> for Answer {
+    fn from(value: f32) -> Self {
+        Answer::Float(value.into())
+    }
+}
+
+impl From<f64> for Answer {
+    fn from(value: f64) -> Self {
+        Answer::Float(value)
+    }
+}
+
+impl From<char> for Answer {
+    fn from(value: char) -> Self {
+        Answer::Text(value.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<PathBuf> for Answer {
+    fn from(value: PathBuf) -> Self {
+        Answer::Text(value.display().to_string())
+    }
+}
+
+impl From<Vec<String>> for Answer {
+    fn from(value: Vec<String>) -> Self {
+        Answer::List(value)
+    }
+}
+
+/// The answers a [`Wizard`] gathered, by step name, in the order they were asked.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Answers {
+    entries: Vec<(String, Answer)>,
+}
+
+impl Answers {
+    /// The answer to the step called `name`, if it was asked.
+    pub fn get(&self, name: &str) -> Option<&Answer> {
+        self.entries
+            .iter()
+            .find(|(step, _)| step == name)
+            .map(|(_, answer)| answer)
+    }
+
+    /// Whether the step called `name` was answered yes. It's `false` if the answer
+    /// was no, isn't a yes or no, or wasn't asked for.
+    pub fn get_bool(&self, name: &str) -> bool {
+        matches!(self.get(name), Some(Answer::Bool(true)))
+    }
+
+    /// The answer to the step called `name`, if it's text.
+    pub fn get_str(&self, name: &str) -> Option<&str> {
+        match self.get(name) {
+            Some(Answer::Text(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// The answer to the step called `name`, if it's a whole number.
+    pub fn get_int(&self, name: &str) -> Option<i64> {
+        match self.get(name) {
+            Some(Answer::Int(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// The answer to the step called `name`, if it's a number. Whole numbers are
+    /// converted.
+    pub fn get_float(&self, name: &str) -> Option<f64> {
+        match self.get(name) {
+            Some(Answer::Float(value)) => Some(*value),
+            Some(Answer::Int(value)) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// The answer to the step called `name`, if it's a list.
+    pub fn get_list(&self, name: &str) -> Option<&[String]> {
+        match self.get(name) {
+            Some(Answer::List(items)) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// The step names and their answers, in the order they were asked.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Answer)> {
+        self.entries
+            .iter()
+            .map(|(name, answer)| (name.as_str(), answer))
+    }
+
+    /// How many steps were answered.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no steps were answered.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The answers as a JSON object, with a key for each step in the order they
+    /// were asked. Yes/no answers are booleans, numbers are numbers, and lists are
+    /// arrays of strings.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let answers = Prompt::wizard("New project")
+    ///     .step("name", Prompt::text("Project name"))
+    ///     .step("port", Prompt::int("Port").default(8080))
+    ///     .ask()?;
+    /// println!("{}", answers.to_json());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// This prints something like:
+    ///
+    /// ```text
+    /// {
+    ///   "name": "demo",
+    ///   "port": 8080
+    /// }
+    /// ```
+    ///
+    /// Answers to hidden steps, like passwords, are written out like any other, so
+    /// leave those steps out of a wizard whose answers are saved.
+    pub fn to_json(&self) -> String {
+        if self.entries.is_empty() {
+            return "{}".to_string();
+        }
+        let entries: Vec<String> = self
+            .entries
+            .iter()
+            .map(|(name, answer)| format!("  {}: {}", quote(name), answer.to_json()))
+            .collect();
+        format!("{{\n{}\n}}", entries.join(",\n"))
+    }
+
+    /// Writes [`to_json`](Answers::to_json) to `path`, replacing whatever was
+    /// there, so the answers can be saved for a later run.
+    pub fn write_json(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_json() + "\n")
+    }
+
+    /// The answers as a TOML document, behind the `toml` feature. Step names with
+    /// dots in them go into tables, so `database.host` is written as `host` under
+    /// `[database]`, the same way [`load_answers`](crate::load_answers) reads them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let answers = Prompt::wizard("New project")
+    ///     .step("name", Prompt::text("Project name"))
+    ///     .step("database.host", Prompt::text("Database host"))
+    ///     .step("database.port", Prompt::int("Database port").default(5432))
+    ///     .ask()?;
+    /// answers.write_toml("project.toml")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// This writes something like:
+    ///
+    /// ```toml
+    /// name = "demo"
+    ///
+    /// [database]
+    /// host = "localhost"
+    /// port = 5432
+    /// ```
+    ///
+    /// Like [`to_json`](Answers::to_json), answers to hidden steps are written out
+    /// too.
+    #[cfg(feature = "toml")]
+    pub fn to_toml(&self) -> String {
+        let mut document = toml::Table::new();
+        for (name, answer) in &self.entries {
+            let keys: Vec<&str> = name.split('.').collect();
+            place(&mut document, &keys, answer.to_toml());
+        }
+        document.to_string()
+    }
+
+    /// Writes [`to_toml`](Answers::to_toml) to `path`, replacing whatever was there,
+    /// behind the `toml` feature.
+    #[cfg(feature = "toml")]
+    pub fn write_toml(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        fs::write(path, self.to_toml())
+    }
+
+    fn remove(&mut self, name: &str) {
+        self.entries.retain(|(step, _)| step != name);
+    }
+
+    fn insert(&mut self, name: &str, answer: Answer) {
+        match self.entries.iter_mut().find(|(step, _)| step == name) {
+            Some((_, old)) => *old = answer,
+            None => self.entries.push((name.to_string(), answer)),
+        }
+    }
+}
+
+/// Puts `value` in `table` under `keys`, making a table for each key but the last.
+/// If a key's taken by a value already, the rest of the keys are kept whole.
+#[cfg(feature = "toml")]
+fn place(table: &mut toml::Table, keys: &[&str], value: toml::Value) {
+    let Some((&key, rest)) = keys.split_first() else {
+        return;
+    };
+    if rest.is_empty() {
+        table.insert(key.to_string(), value);
+        return;
+    }
+    let inner = table
+        .entry(key.to_string())
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+    match inner {
+        toml::Value::Table(inner) => place(inner, rest, value),
+        _ => {
+            table.insert(keys.join("."), value);
+        }
+    }
+}
+
+/// A question that can be a step in a [`Wizard`].
+///
+/// It's implemented for the [`PromptBuilder`]s whose values convert into an
+/// [`Answer`], and for [`Menu`], [`FuzzySelect`] and [`Select`], which answer with
+/// the picked item's text, [`MultiSelect`] and [`Ranking`], which answer with a list
+/// of them, and [`Slider`].
+pub trait WizardStep {
+    /// Asks the question and returns the answer. `previous` is the answer given
+    /// before, when the step is gone back to or changed in the review, for the
+    /// question to start from where it can.
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError>;
+
+    /// Whether the answer is kept out of sight, so a wizard's
+    /// [`review`](Wizard::review) doesn't show it. It's `false` unless overridden.
+    fn is_hidden(&self) -> bool {
+        false
+    }
+}
+
+/// The earlier answer is typed out again, ready to be edited, unless the prompt is
+/// hidden or takes several lines.
+impl<T: Clone + Into<Answer>> WizardStep for PromptBuilder<T> {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        let text = match previous {
+            _ if self.hidden || self.multiline || self.editor.is_some() => None,
+            Some(Answer::Bool(answer)) => Some(confirm::yes_no().word(*answer).to_string()),
+            Some(answer) => Some(answer.to_string()),
+            None => None,
+        };
+        self.ask_from(text.as_deref()).map(Into::into)
+    }
+
+    fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+/// The earlier pick is highlighted to start with.
+impl WizardStep for Menu {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        let earlier = match previous {
+            Some(Answer::Text(item)) => self.items.iter().position(|i| i == item),
+            _ => None,
+        };
+        let picked = match earlier {
+            Some(earlier) => self.ask_from(earlier)?,
+            None => self.ask()?,
+        };
+        Ok(Answer::Text(self.items[picked].clone()))
+    }
+}
+
+impl WizardStep for FuzzySelect {
+    fn ask_step(&self, _previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        let picked = self.ask()?;
+        Ok(Answer::Text(self.items[picked].clone()))
+    }
+}
+
+/// The earlier picks are checked to start with.
+impl WizardStep for MultiSelect {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        let picked = match previous {
+            Some(Answer::List(earlier)) => {
+                let checked: Vec<bool> = self.items.iter().map(|i| earlier.contains(i)).collect();
+                self.ask_from(&checked)?
+            }
+            _ => self.ask()?,
+        };
+        let items = picked.iter().map(|&i| self.items[i].clone()).collect();
+        Ok(Answer::List(items))
+    }
+}
+
+/// The items start out in the earlier order.
+impl WizardStep for Ranking {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        let earlier: Vec<usize> = match previous {
+            Some(Answer::List(earlier)) => earlier
+                .iter()
+                .filter_map(|item| self.items.iter().position(|i| i == item))
+                .collect(),
+            _ => Vec::new(),
+        };
+        let order = self.ask_from(&earlier)?;
+        let items = order.iter().map(|&i| self.items[i].clone()).collect();
+        Ok(Answer::List(items))
+    }
+}
+
+/// The earlier pick is highlighted to start with.
+impl<T: SelectOption> WizardStep for Select<T> {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        self.menu.ask_step(previous)
+    }
+}
+
+/// The slider starts at the earlier answer.
+impl WizardStep for Slider {
+    fn ask_step(&self, previous: Option<&Answer>) -> Result<Answer, PromptError> {
+        match previous {
+            Some(Answer::Int(earlier)) => self.ask_from(Some(*earlier)),
+            _ => self.ask(),
+        }
+        .map(Answer::Int)
+    }
+}
+
+thread_local! {
+    /// Whether going back a step is allowed, which is while a wizard on this thread
+    /// asks a step other than the first.
+    static BACK: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether the prompt being asked can go back a step, returning
+/// [`PromptError::Back`].
+pub(crate) fn can_go_back() -> bool {
+    BACK.get()
+}
+
+/// Whether `input` goes back a step: Esc, or `<` where Esc can't be read.
+pub(crate) fn goes_back(input: &str) -> bool {
+    can_go_back() && (input == term::ESCAPE || input == "<")
+}
+
+/// A series of named questions, created through [`Prompt::wizard`].
+///
+/// The title is printed first, and each question is headed by its step number,
+/// like `Step 2 of 5`, both in the theme's header style. Steps skipped by their
+/// [`when`](Wizard::when) conditions aren't counted. Stopping partway, like with
+/// Ctrl+C, returns the error without any of the answers.
+///
+/// At every step but the first, Esc goes back to the step before, with its answer
+/// filled in or picked again to start from. Where Esc can't be read, like when
+/// stdin isn't a terminal, answering `<` does the same. In menus and lists, it's
+/// whichever key their keymap binds to [`Action::Back`](crate::Action::Back).
+pub struct Wizard {
+    title: String,
+    steps: Vec<Step>,
+    review: bool,
+}
+
+/// Whether a step is asked, given the answers to the ones before it.
+type Condition = Box<dyn Fn(&Answers) -> bool>;
+
+struct Step {
+    name: String,
+    question: Box<dyn WizardStep>,
+    conditions: Vec<Condition>,
+}
+
+impl Step {
+    fn applies(&self, answers: &Answers) -> bool {
+        self.conditions.iter().all(|condition| condition(answers))
+    }
+}
+
+impl Wizard {
+    /// Adds `question` as the next step, with its answer kept under `name`.
+    pub fn step(mut self, name: &str, question: impl WizardStep + 'static) -> Self {
+        self.steps.push(Step {
+            name: name.to_string(),
+            question: Box::new(question),
+            conditions: Vec::new(),
+        });
+        self
+    }
+
+    /// Only asks the step added last if `condition` holds for the answers so far,
+    /// and skips it otherwise, leaving it out of the answers. Given more than once,
+    /// every condition has to hold.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// # use cumaea::*;
+    /// # fn main() -> Result<(), PromptError> {
+    /// let answers = Prompt::wizard("Server")
+    ///     .step("use_tls", Prompt::confirm("Serve over TLS? (y/n)"))
+    ///     .step("cert", Prompt::path("Certificate"))
+    ///     .when(|answers| answers.get_bool("use_tls"))
+    ///     .step("key", Prompt::path("Private key"))
+    ///     .when(|answers| answers.get_bool("use_tls"))
+    ///     .ask()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if no step has been added yet.
+    pub fn when(mut self, condition: impl Fn(&Answers) -> bool + 'static) -> Self {
+        let step = self.steps.last_mut().expect("a condition needs a step");
+        step.conditions.push(Box::new(condition));
+        self
+    }
+
+    /// Shows a summary of the answers once every step is done, and asks whether
+    /// they're right before returning them.
+    ///
+    /// If they aren't, a menu asks which one to change, that step is asked again,
+    /// and the summary comes back. Steps whose conditions hold only after the
+    /// change are asked then too, and ones whose conditions no longer hold are
+    /// dropped. Hidden answers, like passwords, are shown as `(hidden)`.
+    pub fn review(mut self) -> Self {
+        self.review = true;
+        self
+    }
+
+    /// Asks every step in order, other than the ones whose conditions don't hold,
+    /// and returns the answers.
+    ///
+    /// Returns [`PromptError::Validation`] if two steps have the same name.
+    pub fn ask(&self) -> Result<Answers, PromptError> {
+        term::asking(|| {
+            for (i, step) in self.steps.iter().enumerate() {
+                if self.steps[..i]
+                    .iter()
+                    .any(|earlier| earlier.name == step.name)
+                {
+                    return Err(PromptError::Validation(format!(
+                        "two steps are called {:?}",
+                        step.name
+                    )));
+                }
+            }
+            if !self.title.is_empty() {
+                outln!("{}", theme::theme().header_style.paint(&self.title))?;
+            }
+            let mut answers = Answers::default();
+            self.fill(&mut answers, 0, None)?;
+            while self.review && !self.confirmed(&answers)? {
+                let answered: Vec<usize> = (0..self.steps.len())
+                    .filter(|&i| answers.get(&self.steps[i].name).is_some())
+                    .collect();
+                let names: Vec<&str> = answered
+                    .iter()
+                    .map(|&i| self.steps[i].name.as_str())
+                    .collect();
+                let picked = Prompt::menu(&locale().change_answer, &names).ask()?;
+                let i = answered[picked];
+                self.fill(&mut answers, i, Some(i))?;
+            }
+            Ok(answers)
+        })
+    }
+
+    /// Goes through the steps from `from` on, asking the ones that apply and
+    /// haven't been answered, along with step `redo`, and dropping the answers to
+    /// ones that don't apply. Every step asked but the first can go back to the one
+    /// asked before it.
+    fn fill(
+        &self,
+        answers: &mut Answers,
+        from: usize,
+        redo: Option<usize>,
+    ) -> Result<(), PromptError> {
+        let asking: Vec<bool> = (0..self.steps.len())
+            .map(|i| i >= from && (redo == Some(i) || answers.get(&self.steps[i].name).is_none()))
+            .collect();
+        let mut asked = Vec::new();
+        let mut i = from;
+        while i < self.steps.len() {
+            let step = &self.steps[i];
+            if !step.applies(answers) {
+                answers.remove(&step.name);
+            } else if asking[i] {
+                let recorded = session::recorded();
+                match self.ask_step(answers, i, !asked.is_empty()) {
+                    Err(PromptError::Back) => {
+                        // The answer recorded for the step gone back to is replaced
+                        // by the one it's given now.
+                        let (earlier, recorded) = asked.pop().expect("only later steps go back");
+                        session::forget(recorded)?;
+                        i = earlier;
+                        continue;
+                    }
+                    answer => answer?,
+                }
+                asked.push((i, recorded));
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// Asks step `i` under its number, starting from its earlier answer if it has
+    /// one, and keeps the answer in step order. `back` says whether it can go back.
+    fn ask_step(&self, answers: &mut Answers, i: usize, back: bool) -> Result<(), PromptError> {
+        let answered = |step: &Step| answers.get(&step.name).is_some();
+        let number = self.steps[..i].iter().filter(|step| answered(step)).count() + 1;
+        // Later steps can only be judged on the answers so far, so the total can
+        // change as the wizard goes.
+        let left = self.steps[i + 1..]
+            .iter()
+            .filter(|later| later.applies(answers))
+            .count();
+        let total = number + left;
+        let heading = fill(&locale().step, &[("number", &number), ("total", &total)]);
+        outln!("{}", theme::theme().header_style.paint(&heading))?;
+        let previous = answers.get(&self.steps[i].name).cloned();
+        BACK.set(back);
+        let answer = self.steps[i].question.ask_step(previous.as_ref());
+        BACK.set(false);
+        answers.insert(&self.steps[i].name, answer?);
+        let position = |name: &str| self.steps.iter().position(|step| step.name == name);
+        answers.entries.sort_by_key(|(name, _)| position(name));
+        Ok(())
+    }
+
+    /// Prints the summary of `answers`, and asks whether they're right.
+    fn confirmed(&self, answers: &Answers) -> Result<bool, PromptError> {
+        let theme = theme::theme();
+        let locale = locale();
+        outln!()?;
+        outln!("{}", theme.header_style.paint(&locale.summary))?;
+        let width = answers.iter().map(|(name, _)| term::width(name)).max();
+        for (name, answer) in answers.iter() {
+            let hidden = self
+                .steps
+                .iter()
+                .any(|step| step.name == name && step.question.is_hidden());
+            let shown = match hidden {
+                true => locale.hidden.clone(),
+                false => answer.to_string(),
+            };
+            let pad = width.unwrap_or(0) - term::width(name);
+            let answer = theme.answer_style.paint(&shown);
+            outln!("  {}:{} {}", name, " ".repeat(pad), answer)?;
+        }
+        outln!()?;
+        let question = format!("{} {}", locale.answers_right, confirm::hint(Some(true)));
+        Prompt::confirm(&question).default(true).ask()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test::{Key, MockTerminal};
+    use crate::Prompter;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Keeps the tests that go back a step, and so take answers back out of a
+    /// recording, from running alongside the one that checks the recording.
+    fn going_back() -> MutexGuard<'static, ()> {
+        static GOING_BACK: Mutex<()> = Mutex::new(());
+        GOING_BACK.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    #[test]
+    fn records_only_the_answer_gone_back_to() {
+        let _going_back = going_back();
+        let path = std::env::temp_dir().join(format!("cumaea-{}-wizard.jsonl", std::process::id()));
+        session::record_answers(&path).unwrap();
+        let mut terminal = MockTerminal::new()
+            .type_text("ada\n")
+            .press(Key::Esc)
+            .press(Key::Backspace)
+            .press(Key::Backspace)
+            .press(Key::Backspace)
+            .type_text("bob\n8080\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("name", Prompt::text("Recorded name"))
+                .step("port", Prompt::int("Recorded port"))
+                .ask()
+        });
+        let recording = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let answers = answers.unwrap();
+        assert_eq!(answers.get_str("name"), Some("bob"));
+        let ours: Vec<&str> = recording
+            .lines()
+            .filter(|line| line.contains("Recorded "))
+            .collect();
+        assert_eq!(
+            ours,
+            [
+                r#"{"prompt": "Recorded name", "answer": "bob"}"#,
+                r#"{"prompt": "Recorded port", "answer": "8080"}"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn ranking_steps_start_from_their_earlier_order() {
+        let _going_back = going_back();
+        let mut terminal = MockTerminal::new()
+            .type_text("ada\n")
+            .type_text(" j \n")
+            .press(Key::Esc)
+            .press(Key::Esc)
+            .press(Key::Enter)
+            .press(Key::Enter)
+            .type_text("y\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("owner", Prompt::text("Owner"))
+                .step("order", Prompt::ranking("Order", &["a", "b", "c"]))
+                .step("done", Prompt::confirm("Done?"))
+                .ask()
+        });
+        let answers = answers.unwrap();
+        assert_eq!(answers.get_str("owner"), Some("ada"));
+        assert_eq!(answers.get_list("order").unwrap(), ["b", "a", "c"]);
+        assert!(answers.get_bool("done"));
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn numbered_rankings_go_back_on_a_less_than_sign() {
+        let _going_back = going_back();
+        let mut prompter = Prompter::new(&b"ada\n<\nbob\n2\n"[..], Vec::new());
+        let answers = prompter.run(|| {
+            Prompt::wizard("")
+                .step("owner", Prompt::text("Owner"))
+                .step("order", Prompt::ranking("Order", &["a", "b", "c"]))
+                .ask()
+        });
+        let answers = answers.unwrap();
+        assert_eq!(answers.get_str("owner"), Some("bob"));
+        assert_eq!(answers.get_list("order").unwrap(), ["b", "a", "c"]);
+    }
+
+    #[test]
+    fn asks_every_step_under_its_number() {
+        let mut terminal = MockTerminal::new().type_text("demo\n8080\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("New project")
+                .step("name", Prompt::text("Name"))
+                .step("port", Prompt::int("Port"))
+                .ask()
+        });
+        let answers = answers.unwrap();
+        let entries: Vec<(&str, &Answer)> = answers.iter().collect();
+        assert_eq!(
+            entries,
+            [
+                ("name", &Answer::Text("demo".to_string())),
+                ("port", &Answer::Int(8080)),
+            ]
+        );
+        assert_eq!(
+            terminal.screen(),
+            "New project\nStep 1 of 2\nName: demo\nStep 2 of 2\nPort: 8080"
+        );
+    }
+
+    #[test]
+    fn rejects_steps_with_the_same_name() {
+        let mut terminal = MockTerminal::new();
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("name", Prompt::text("Name"))
+                .step("name", Prompt::text("Nickname"))
+                .ask()
+        });
+        match answers {
+            Err(PromptError::Validation(message)) => {
+                assert_eq!(message, "two steps are called \"name\"")
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+        assert_eq!(terminal.output(), "");
+    }
+
+    #[test]
+    fn goes_back_several_steps() {
+        let _going_back = going_back();
+        let mut terminal = MockTerminal::new()
+            .type_text("a\nb\n")
+            .press(Key::Esc)
+            .press(Key::Esc)
+            .type_text("2\n")
+            .press(Key::Backspace)
+            .type_text("3\nc\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("first", Prompt::text("First"))
+                .step("second", Prompt::text("Second"))
+                .step("third", Prompt::text("Third"))
+                .ask()
+        });
+        let answers = answers.unwrap();
+        assert_eq!(answers.get_str("first"), Some("a2"));
+        assert_eq!(answers.get_str("second"), Some("3"));
+        assert_eq!(answers.get_str("third"), Some("c"));
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn the_first_step_cant_go_back() {
+        let _going_back = going_back();
+        let mut terminal = MockTerminal::new().press(Key::Esc).type_text("a\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("first", Prompt::text("First"))
+                .ask()
+        });
+        assert_eq!(answers.unwrap().get_str("first"), Some("a"));
+    }
+
+    /// A wizard that only asks for a certificate when TLS is on.
+    fn tls_wizard() -> Wizard {
+        Prompt::wizard("")
+            .step("tls", Prompt::confirm("TLS?"))
+            .step("cert", Prompt::text("Certificate"))
+            .when(|answers| answers.get_bool("tls"))
+            .step("port", Prompt::int("Port"))
+    }
+
+    #[test]
+    fn skips_steps_whose_conditions_dont_hold() {
+        let mut terminal = MockTerminal::new().type_text("n\n80\n");
+        let answers = terminal.run(|| tls_wizard().ask()).unwrap();
+        assert_eq!(answers.get("cert"), None);
+        assert_eq!(answers.len(), 2);
+        assert_eq!(
+            terminal.screen(),
+            "Step 1 of 2\nTLS? n\nStep 2 of 2\nPort: 80"
+        );
+    }
+
+    #[test]
+    fn every_condition_has_to_hold() {
+        let mut terminal = MockTerminal::new().type_text("y\nn\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("tls", Prompt::confirm("TLS?"))
+                .step("acme", Prompt::confirm("ACME?"))
+                .when(|answers| answers.get_bool("tls"))
+                .step("email", Prompt::text("Email"))
+                .when(|answers| answers.get_bool("tls"))
+                .when(|answers| answers.get_bool("acme"))
+                .ask()
+        });
+        let answers = answers.unwrap();
+        assert_eq!(answers.get("acme"), Some(&Answer::Bool(false)));
+        assert_eq!(answers.get("email"), None);
+        assert_eq!(answers.len(), 2);
+    }
+
+    #[test]
+    fn goes_back_over_skipped_steps() {
+        let _going_back = going_back();
+        let mut terminal = MockTerminal::new()
+            .type_text("n\n")
+            .press(Key::Esc)
+            .press(Key::Backspace)
+            .type_text("y\ncert.pem\n443\n");
+        let answers = terminal.run(|| tls_wizard().ask()).unwrap();
+        assert!(answers.get_bool("tls"));
+        assert_eq!(answers.get_str("cert"), Some("cert.pem"));
+        assert_eq!(answers.get_int("port"), Some(443));
+    }
+
+    #[test]
+    fn drops_answers_whose_conditions_stop_holding() {
+        let _going_back = going_back();
+        let mut terminal = MockTerminal::new()
+            .type_text("y\ncert.pem\n")
+            .press(Key::Esc)
+            .press(Key::Esc)
+            .press(Key::Backspace)
+            .type_text("n\n80\n");
+        let answers = terminal.run(|| tls_wizard().ask()).unwrap();
+        assert!(!answers.get_bool("tls"));
+        assert_eq!(answers.get("cert"), None);
+        assert_eq!(answers.get_int("port"), Some(80));
+    }
+
+    #[test]
+    fn reviews_the_answers_with_hidden_ones_hidden() {
+        let mut terminal = MockTerminal::new().type_text("ada\nhunter2\n\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("user", Prompt::text("User"))
+                .step("password", Prompt::password("Password"))
+                .review()
+                .ask()
+        });
+        assert_eq!(answers.unwrap().get_str("password"), Some("hunter2"));
+        let screen = terminal.screen();
+        assert!(
+            screen.ends_with(
+                "Summary\n  user:     ada\n  password: (hidden)\n\nAre these answers right? (Y/n)"
+            ),
+            "{}",
+            screen
+        );
+        assert!(!screen.contains("hunter2"));
+    }
+
+    #[test]
+    fn changes_an_answer_from_the_review() {
+        let mut terminal = MockTerminal::new()
+            .type_text("ada\n8080\nn\n")
+            .press(Key::Down)
+            .press(Key::Enter)
+            .press(Key::Backspace)
+            .type_text("1\n\n");
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("user", Prompt::text("User"))
+                .step("port", Prompt::int("Port"))
+                .review()
+                .ask()
+        });
+        let answers = answers.unwrap();
+        assert_eq!(answers.get_str("user"), Some("ada"));
+        assert_eq!(answers.get_int("port"), Some(8081));
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn changes_from_the_review_bring_in_and_drop_steps() {
+        let mut terminal = MockTerminal::new()
+            .type_text("n\n80\nn\n")
+            .press(Key::Enter)
+            .press(Key::Backspace)
+            .type_text("y\ncert.pem\nn\n")
+            .press(Key::Enter)
+            .press(Key::Backspace)
+            .type_text("n\ny\n");
+        let answers = terminal.run(|| tls_wizard().review().ask());
+        let answers = answers.unwrap();
+        assert!(!answers.get_bool("tls"));
+        assert_eq!(answers.get("cert"), None);
+        assert_eq!(answers.get_int("port"), Some(80));
+        assert_eq!(terminal.keys_left(), 0);
+    }
+
+    #[test]
+    fn writes_answers_as_json() {
+        let mut terminal = MockTerminal::new()
+            .type_text("say \"hi\"\n8080\ny\n")
+            .press(Key::Char(' '))
+            .press(Key::Enter);
+        let answers = terminal.run(|| {
+            Prompt::wizard("")
+                .step("name", Prompt::text("Name"))
+                .step("port", Prompt::int("Port"))
+                .step("git", Prompt::confirm("Git?"))
+                .step("tags", Prompt::multi_select("Tags", &["cli", "web"]))
+                .ask()
+        });
+        assert_eq!(
+            answers.unwrap().to_json(),
+            "{\n  \"name\": \"say \\\"hi\\\"\",\n  \"port\": 8080,\n  \"git\": true,\n  \"tags\": [\"cli\"]\n}"
+        );
+    }
+
+    #[test]
+    fn writes_numbers_json_can_hold() {
+        let mut answers = Answers::default();
+        answers.insert("ratio", Answer::Float(0.5));
+        answers.insert("whole", Answer::Float(2.0));
+        answers.insert("nan", Answer::Float(f64::NAN));
+        answers.insert("inf", Answer::Float(f64::INFINITY));
+        answers.insert("empty", Answer::List(Vec::new()));
+        assert_eq!(
+            answers.to_json(),
+            "{\n  \"ratio\": 0.5,\n  \"whole\": 2.0,\n  \"nan\": null,\n  \"inf\": null,\n  \"empty\": []\n}"
+        );
+        assert_eq!(Answers::default().to_json(), "{}");
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_reads_back() {
+        let mut answers = Answers::default();
+        answers.insert(
+            "line\nbreak",
+            Answer::Text("tab\t\u{1} \u{1f600}".to_string()),
+        );
+        answers.insert(
+            "list",
+            Answer::List(vec!["a, b".to_string(), "\\".to_string()]),
+        );
+        let value: serde_json::Value = serde_json::from_str(&answers.to_json()).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"line\nbreak": "tab\t\u{1} \u{1f600}", "list": ["a, b", "\\"]})
+        );
+    }
+}